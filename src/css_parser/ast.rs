@@ -1,5 +1,45 @@
+use std::borrow::Cow;
 use std::fmt;
 
+use crate::css_parser::component_value::{to_css, trim_whitespace, ComponentValue};
+use crate::css_parser::container::ContainerCondition;
+use crate::css_parser::counter_style::CounterStyleRule;
+use crate::css_parser::font_face::FontFaceRule;
+use crate::css_parser::font_feature_values::{FeatureType, FontFeatureValuesRule};
+use crate::css_parser::import::ImportRule;
+use crate::css_parser::keyframes::KeyframesRule;
+use crate::css_parser::error::PropertyError;
+use crate::css_parser::media::MediaQueryList;
+use crate::css_parser::namespace::{NamespacePrefix, NamespaceRule};
+use crate::css_parser::page::{MarginBox, PageSelector};
+use crate::css_parser::property::PropertyRule;
+use crate::css_parser::supports::SupportsCondition;
+
+/// Location of a node in the source text. `start` and `end` are byte offsets
+/// (`end` exclusive), `line` and `column` locate `start` like `lexer::Token`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Span {
+    pub start: usize,
+    pub end: usize,
+    pub line: usize,
+    pub column: usize,
+}
+
+impl Span {
+    pub fn len(&self) -> usize {
+        self.end - self.start
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.start == self.end
+    }
+
+    /// Source text covered by the span.
+    pub fn text<'a>(&self, source: &'a str) -> &'a str {
+        &source[self.start..self.end]
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub enum AttributeOperator {
     Exists,         // [attr]
@@ -17,30 +57,218 @@ pub enum CaseSensitivity {
     Insensitive,
 }
 
+/// A simple selector such as `.item` or `[href]` and where it is in the source.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SelectorPart<'a> {
+    pub kind: SelectorPartKind<'a>,
+    pub span: Span,
+}
+
 #[derive(Debug, Clone, PartialEq)]
-pub enum SelectorPart {
-    Class(String),
-    Id(String),
-    Element(String),
-    Universal,
-    PseudoClass(String),
-    PseudoClassFunction(String, String),
-    PseudoElement(String),
-    AttributeSelector(String, Option<(AttributeOperator, String, Option<CaseSensitivity>)>),
-}
-
-impl fmt::Display for SelectorPart {
+pub enum SelectorPartKind<'a> {
+    Class(Cow<'a, str>),
+    Id(Cow<'a, str>),
+    Element(Option<NamespacePrefix<'a>>, Cow<'a, str>), // `svg|rect` has the prefix `svg`
+    Universal(Option<NamespacePrefix<'a>>),
+    PseudoClass(Cow<'a, str>),
+    PseudoClassFunction(Cow<'a, str>, Cow<'a, str>),
+    PseudoElement(Cow<'a, str>),
+    AttributeSelector(Option<NamespacePrefix<'a>>, Cow<'a, str>, Option<(AttributeOperator, Cow<'a, str>, Option<CaseSensitivity>)>),
+    Nesting,         // `&`, the parent rule's selector
+}
+
+/// Detaches borrowed text from the input.
+pub(crate) fn owned(text: Cow<'_, str>) -> Cow<'static, str> {
+    Cow::Owned(text.into_owned())
+}
+
+/// Splits a declaration value at its top-level commas.
+pub(crate) fn comma_separated<'v, 'a>(value: &'v Value<'a>) -> Vec<Vec<&'v Value<'a>>> {
+    value
+        .items()
+        .split(|item| matches!(&item.kind, ValueKind::Literal(text) if text == ","))
+        .map(|group| group.to_vec())
+        .collect()
+}
+
+/// Text of a value without surrounding quotes.
+pub(crate) fn value_text<'a>(value: &Value<'a>) -> Cow<'a, str> {
+    let strip = |text: &str| -> (usize, usize) {
+        let quoted = text.len() >= 2 && (text.starts_with('"') && text.ends_with('"') || text.starts_with('\'') && text.ends_with('\''));
+        if quoted { (1, text.len() - 1) } else { (0, text.len()) }
+    };
+
+    match &value.kind {
+        ValueKind::QuotedString(text) => text.clone(),
+        ValueKind::Literal(Cow::Borrowed(text)) | ValueKind::Keyword(Cow::Borrowed(text)) => {
+            let (start, end) = strip(text);
+            Cow::Borrowed(&text[start..end])
+        },
+        value => {
+            let text = value.to_string();
+            let (start, end) = strip(&text);
+            Cow::Owned(text[start..end].to_string())
+        },
+    }
+}
+
+/// A `/* ... */` comment; `text` excludes the delimiters.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Comment<'a> {
+    pub text: Cow<'a, str>,
+    pub span: Span,
+}
+
+impl Comment<'_> {
+    pub fn into_owned(self) -> Comment<'static> {
+        Comment {
+            text: owned(self.text),
+            span: self.span,
+        }
+    }
+}
+
+impl fmt::Display for Comment<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "/*{}*/", self.text)
+    }
+}
+
+/// Comments attached to a rule, at-rule or declaration. Only filled in when
+/// the lexer preserves comments.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Comments<'a> {
+    pub leading: Vec<Comment<'a>>,  // between the previous node and this one
+    pub trailing: Vec<Comment<'a>>, // after the node, on the line it ends on
+    pub dangling: Vec<Comment<'a>>, // inside the node's block, after its last child
+}
+
+impl Comments<'_> {
+    pub fn is_empty(&self) -> bool {
+        self.leading.is_empty() && self.trailing.is_empty() && self.dangling.is_empty()
+    }
+
+    pub fn into_owned(self) -> Comments<'static> {
+        Comments {
+            leading: self.leading.into_iter().map(Comment::into_owned).collect(),
+            trailing: self.trailing.into_iter().map(Comment::into_owned).collect(),
+            dangling: self.dangling.into_iter().map(Comment::into_owned).collect(),
+        }
+    }
+
+    /// Writes each leading comment on a line of its own.
+    fn write_leading(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for comment in &self.leading {
+            writeln!(f, "{}", comment)?;
+        }
+        Ok(())
+    }
+
+    fn write_trailing(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for comment in &self.trailing {
+            write!(f, " {}", comment)?;
+        }
+        Ok(())
+    }
+
+    /// Writes each dangling comment indented, on a line of its own.
+    fn write_dangling(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for comment in &self.dangling {
+            write_indented(f, comment)?;
+        }
+        Ok(())
+    }
+}
+
+/// Writes `node` indented by one level, line by line.
+fn write_indented(f: &mut fmt::Formatter<'_>, node: &impl fmt::Display) -> fmt::Result {
+    for line in node.to_string().lines() {
+        writeln!(f, "    {}", line)?;
+    }
+    Ok(())
+}
+
+/// Writes the child rules of a block indented by one level. Declaration
+/// blocks are stored as a single rule without selectors and are written as
+/// bare declarations.
+fn write_child_rules(f: &mut fmt::Formatter<'_>, rules: &[CssRule<'_>]) -> fmt::Result {
+    for rule in rules {
+        match rule {
+            CssRule::Style(rule) if rule.is_declaration_block() => {
+                for decl in &rule.declarations {
+                    write_indented(f, decl)?;
+                }
+            },
+            _ => write_indented(f, rule)?,
+        }
+    }
+    Ok(())
+}
+
+impl<'a> SelectorPart<'a> {
+    /// Namespace component of a type, universal or attribute selector.
+    pub fn namespace(&self) -> Option<&NamespacePrefix<'a>> {
+        self.kind.namespace()
+    }
+
+    pub fn into_owned(self) -> SelectorPart<'static> {
+        SelectorPart {
+            kind: self.kind.into_owned(),
+            span: self.span,
+        }
+    }
+}
+
+impl fmt::Display for SelectorPart<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.kind)
+    }
+}
+
+impl<'a> SelectorPartKind<'a> {
+    /// Namespace component of a type, universal or attribute selector.
+    pub fn namespace(&self) -> Option<&NamespacePrefix<'a>> {
+        match self {
+            SelectorPartKind::Element(namespace, _) | SelectorPartKind::Universal(namespace) | SelectorPartKind::AttributeSelector(namespace, _, _) => {
+                namespace.as_ref()
+            },
+            _ => None,
+        }
+    }
+
+    pub fn into_owned(self) -> SelectorPartKind<'static> {
+        match self {
+            SelectorPartKind::Class(name) => SelectorPartKind::Class(owned(name)),
+            SelectorPartKind::Id(name) => SelectorPartKind::Id(owned(name)),
+            SelectorPartKind::Element(namespace, name) => SelectorPartKind::Element(namespace.map(NamespacePrefix::into_owned), owned(name)),
+            SelectorPartKind::Universal(namespace) => SelectorPartKind::Universal(namespace.map(NamespacePrefix::into_owned)),
+            SelectorPartKind::Nesting => SelectorPartKind::Nesting,
+            SelectorPartKind::PseudoClass(name) => SelectorPartKind::PseudoClass(owned(name)),
+            SelectorPartKind::PseudoClassFunction(name, args) => SelectorPartKind::PseudoClassFunction(owned(name), owned(args)),
+            SelectorPartKind::PseudoElement(name) => SelectorPartKind::PseudoElement(owned(name)),
+            SelectorPartKind::AttributeSelector(namespace, attr, matcher) => SelectorPartKind::AttributeSelector(
+                namespace.map(NamespacePrefix::into_owned),
+                owned(attr),
+                matcher.map(|(op, value, case_sensitivity)| (op, owned(value), case_sensitivity)),
+            ),
+        }
+    }
+}
+
+impl fmt::Display for SelectorPartKind<'_> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
-            SelectorPart::Class(name) => write!(f, ".{}", name),
-            SelectorPart::Id(name) => write!(f, "#{}", name),
-            SelectorPart::Element(name) => write!(f, "{}", name),
-            SelectorPart::Universal => write!(f, "*"),
-            SelectorPart::PseudoClass(name) => write!(f, ":{}", name),
-            SelectorPart::PseudoClassFunction(name, args) => write!(f, ":{}({})", name, args),
-            SelectorPart::PseudoElement(name) => write!(f, "::{}", name),
-            SelectorPart::AttributeSelector(attr, None) => write!(f, "[{}]", attr),
-            SelectorPart::AttributeSelector(attr, Some((op, value, case_sensitivity))) => {
+            SelectorPartKind::Class(name) => write!(f, ".{}", name),
+            SelectorPartKind::Id(name) => write!(f, "#{}", name),
+            SelectorPartKind::Element(namespace, name) => write!(f, "{}{}", prefix(namespace), name),
+            SelectorPartKind::Universal(namespace) => write!(f, "{}*", prefix(namespace)),
+            SelectorPartKind::Nesting => write!(f, "&"),
+            SelectorPartKind::PseudoClass(name) => write!(f, ":{}", name),
+            SelectorPartKind::PseudoClassFunction(name, args) => write!(f, ":{}({})", name, args),
+            SelectorPartKind::PseudoElement(name) => write!(f, "::{}", name),
+            SelectorPartKind::AttributeSelector(namespace, attr, None) => write!(f, "[{}{}]", prefix(namespace), attr),
+            SelectorPartKind::AttributeSelector(namespace, attr, Some((op, value, case_sensitivity))) => {
+                let attr = format!("{}{}", prefix(namespace), attr);
                 match op {
                     AttributeOperator::Exists => write!(f, "[{}", attr),
                     AttributeOperator::Equals => write!(f, "[{}=\"{}\"", attr, value),
@@ -66,6 +294,11 @@ impl fmt::Display for SelectorPart {
     }
 }
 
+/// `svg|` for a namespace component, empty without one.
+fn prefix(namespace: &Option<NamespacePrefix<'_>>) -> String {
+    namespace.as_ref().map(NamespacePrefix::to_string).unwrap_or_default()
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub enum SelectorCombinator {
     Descendant,      // Space
@@ -86,11 +319,21 @@ impl fmt::Display for SelectorCombinator {
 }
 
 #[derive(Debug, Clone, PartialEq)]
-pub struct SelectorGroup {
-    pub parts: Vec<SelectorPart>,
+pub struct SelectorGroup<'a> {
+    pub parts: Vec<SelectorPart<'a>>,
+    pub span: Span,
 }
 
-impl fmt::Display for SelectorGroup {
+impl SelectorGroup<'_> {
+    pub fn into_owned(self) -> SelectorGroup<'static> {
+        SelectorGroup {
+            parts: self.parts.into_iter().map(SelectorPart::into_owned).collect(),
+            span: self.span,
+        }
+    }
+}
+
+impl fmt::Display for SelectorGroup<'_> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         for part in &self.parts {
             write!(f, "{}", part)?;
@@ -100,17 +343,63 @@ impl fmt::Display for SelectorGroup {
 }
 
 #[derive(Debug, Clone, PartialEq)]
-pub struct Selector {
-    pub groups: Vec<SelectorGroup>,
-    pub combinators: Vec<SelectorCombinator>,
+pub struct Selector<'a> {
+    pub groups: Vec<SelectorGroup<'a>>,
+    pub combinators: Vec<SelectorCombinator>,         // between consecutive groups
+    pub leading_combinator: Option<SelectorCombinator>, // relative selector in a nested rule, e.g. `> .child`
+    pub span: Span,
 }
 
-impl fmt::Display for Selector {
+impl<'a> Selector<'a> {
+    /// Whether the selector refers to the scoping root or parent rule with
+    /// `:scope` or `&`, also inside functional pseudo-classes such as `:is()`.
+    pub fn refers_to_scope(&self) -> bool {
+        self.groups.iter().flat_map(|group| &group.parts).any(|part| match &part.kind {
+            SelectorPartKind::Nesting => true,
+            SelectorPartKind::PseudoClass(name) => name.eq_ignore_ascii_case("scope"),
+            SelectorPartKind::PseudoClassFunction(_, args) => args.contains('&') || args.to_ascii_lowercase().contains(":scope"),
+            _ => false,
+        })
+    }
+
+    /// The selector of a rule inside `@scope` with the implied `:scope` made
+    /// explicit: `img` becomes `:scope img` and `> img` becomes
+    /// `:scope > img`. Selectors that refer to the scope already are
+    /// returned as they are.
+    pub fn with_implied_scope(&self) -> Selector<'a> {
+        if self.refers_to_scope() {
+            return self.clone();
+        }
+
+        let scope = SelectorPart { kind: SelectorPartKind::PseudoClass("scope".into()), span: Span::default() };
+        let mut groups = vec![SelectorGroup { parts: vec![scope], span: Span::default() }];
+        groups.extend(self.groups.iter().cloned());
+        let mut combinators = vec![self.leading_combinator.clone().unwrap_or(SelectorCombinator::Descendant)];
+        combinators.extend(self.combinators.iter().cloned());
+
+        Selector { groups, combinators, leading_combinator: None, span: self.span }
+    }
+
+    pub fn into_owned(self) -> Selector<'static> {
+        Selector {
+            groups: self.groups.into_iter().map(SelectorGroup::into_owned).collect(),
+            combinators: self.combinators,
+            leading_combinator: self.leading_combinator,
+            span: self.span,
+        }
+    }
+}
+
+impl fmt::Display for Selector<'_> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         if self.groups.is_empty() {
             return Ok(());
         }
 
+        if let Some(combinator) = &self.leading_combinator {
+            write!(f, "{}", combinator.to_string().trim_start())?;
+        }
+
         write!(f, "{}", self.groups[0])?;
 
         for i in 0..self.combinators.len() {
@@ -123,8 +412,8 @@ impl fmt::Display for Selector {
     }
 }
 
-#[derive(Debug, PartialEq)]
-pub enum AtRuleType {
+#[derive(Debug, Clone, PartialEq)]
+pub enum AtRuleType<'a> {
     Media,
     Keyframes,
     Import,
@@ -133,60 +422,351 @@ pub enum AtRuleType {
     Charset,
     Namespace,
     Page,
+    PageMargin,  // @top-center and the other margin boxes inside @page
     CounterStyle,
     Property,
     Layer,
     FontFeatureValues,
+    FontFeatureType, // @styleset and the other feature-type blocks inside @font-feature-values
     Viewport,
-    Unknown(String),
+    Container,
+    Scope,
+    StartingStyle,
+    Unknown(Cow<'a, str>),
+}
+
+impl AtRuleType<'_> {
+    pub fn into_owned(self) -> AtRuleType<'static> {
+        match self {
+            AtRuleType::Media => AtRuleType::Media,
+            AtRuleType::Keyframes => AtRuleType::Keyframes,
+            AtRuleType::Import => AtRuleType::Import,
+            AtRuleType::FontFace => AtRuleType::FontFace,
+            AtRuleType::Supports => AtRuleType::Supports,
+            AtRuleType::Charset => AtRuleType::Charset,
+            AtRuleType::Namespace => AtRuleType::Namespace,
+            AtRuleType::Page => AtRuleType::Page,
+            AtRuleType::PageMargin => AtRuleType::PageMargin,
+            AtRuleType::CounterStyle => AtRuleType::CounterStyle,
+            AtRuleType::Property => AtRuleType::Property,
+            AtRuleType::Layer => AtRuleType::Layer,
+            AtRuleType::FontFeatureValues => AtRuleType::FontFeatureValues,
+            AtRuleType::FontFeatureType => AtRuleType::FontFeatureType,
+            AtRuleType::Viewport => AtRuleType::Viewport,
+            AtRuleType::Container => AtRuleType::Container,
+            AtRuleType::Scope => AtRuleType::Scope,
+            AtRuleType::StartingStyle => AtRuleType::StartingStyle,
+            AtRuleType::Unknown(name) => AtRuleType::Unknown(owned(name)),
+        }
+    }
+}
+
+/// Dotted cascade layer name such as `framework.base`; empty for an
+/// anonymous layer. In `Stylesheet::layer_order`, anonymous layers are
+/// written as an empty last part after the name of their parent layer.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct LayerName<'a> {
+    pub parts: Vec<Cow<'a, str>>,
+}
+
+impl LayerName<'_> {
+    pub fn is_anonymous(&self) -> bool {
+        self.parts.last().is_none_or(|part| part.is_empty())
+    }
+
+    pub fn into_owned(self) -> LayerName<'static> {
+        LayerName {
+            parts: self.parts.into_iter().map(owned).collect(),
+        }
+    }
+}
+
+impl fmt::Display for LayerName<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.parts.join("."))
+    }
+}
+
+/// Prelude of an `@scope` rule, e.g. `(.card) to (.content)`. Rules inside
+/// the scope match elements below a scope-start element, down to but not
+/// including scope-end elements; `:scope` and `&` refer to the scope-start
+/// element.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ScopeRange<'a> {
+    pub start: Vec<Selector<'a>>, // empty when omitted, the scope is the parent element
+    pub end: Vec<Selector<'a>>,   // empty without `to (...)`
+}
+
+impl ScopeRange<'_> {
+    pub fn into_owned(self) -> ScopeRange<'static> {
+        ScopeRange {
+            start: self.start.into_iter().map(Selector::into_owned).collect(),
+            end: self.end.into_iter().map(Selector::into_owned).collect(),
+        }
+    }
+}
+
+impl fmt::Display for ScopeRange<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let list = |selectors: &[Selector<'_>]| selectors.iter().map(Selector::to_string).collect::<Vec<_>>().join(", ");
+
+        match (self.start.is_empty(), self.end.is_empty()) {
+            (false, false) => write!(f, "({}) to ({})", list(&self.start), list(&self.end)),
+            (false, true) => write!(f, "({})", list(&self.start)),
+            (true, false) => write!(f, "to ({})", list(&self.end)),
+            (true, true) => Ok(()),
+        }
+    }
+}
+
+/// Typed form of an at-rule prelude, for at-rules whose prelude is parsed.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub enum AtRulePrelude<'a> {
+    #[default]
+    None,                             // only available as text in `AtRule::query`
+    Media(MediaQueryList<'a>),        // @media
+    Supports(SupportsCondition<'a>),  // @supports
+    Import(ImportRule<'a>),           // @import
+    Namespace(NamespaceRule<'a>),     // @namespace
+    Container(ContainerCondition<'a>), // @container
+    LayerStatement(Vec<LayerName<'a>>), // @layer a, b;
+    LayerBlock(LayerName<'a>),        // @layer a { ... }, anonymous for `@layer { ... }`
+    Scope(ScopeRange<'a>),            // @scope
+    Page(Vec<PageSelector<'a>>),      // @page, empty for all pages
+    MarginBox(MarginBox),             // @top-center, ...
+    FontFamilies(Vec<Cow<'a, str>>),  // @font-feature-values
+    FeatureType(FeatureType),         // @styleset, ...
+    Unknown(Vec<ComponentValue>),     // at-rules the parser does not know, e.g. `@tailwind base`, whitespace included
+}
+
+impl AtRulePrelude<'_> {
+    pub fn into_owned(self) -> AtRulePrelude<'static> {
+        match self {
+            AtRulePrelude::None => AtRulePrelude::None,
+            AtRulePrelude::Media(media) => AtRulePrelude::Media(media.into_owned()),
+            AtRulePrelude::Supports(condition) => AtRulePrelude::Supports(condition.into_owned()),
+            AtRulePrelude::Import(import) => AtRulePrelude::Import(import.into_owned()),
+            AtRulePrelude::Namespace(namespace) => AtRulePrelude::Namespace(namespace.into_owned()),
+            AtRulePrelude::Container(condition) => AtRulePrelude::Container(condition.into_owned()),
+            AtRulePrelude::LayerStatement(names) => AtRulePrelude::LayerStatement(names.into_iter().map(LayerName::into_owned).collect()),
+            AtRulePrelude::LayerBlock(name) => AtRulePrelude::LayerBlock(name.into_owned()),
+            AtRulePrelude::Scope(scope) => AtRulePrelude::Scope(scope.into_owned()),
+            AtRulePrelude::Page(selectors) => AtRulePrelude::Page(selectors.into_iter().map(PageSelector::into_owned).collect()),
+            AtRulePrelude::MarginBox(margin_box) => AtRulePrelude::MarginBox(margin_box),
+            AtRulePrelude::FontFamilies(families) => AtRulePrelude::FontFamilies(families.into_iter().map(owned).collect()),
+            AtRulePrelude::FeatureType(feature_type) => AtRulePrelude::FeatureType(feature_type),
+            AtRulePrelude::Unknown(values) => AtRulePrelude::Unknown(values),
+        }
+    }
 }
 
 #[derive(Debug)]
-pub struct AtRule {
-    pub rule_type: AtRuleType,
-    pub name: String,
+pub struct AtRule<'a> {
+    pub rule_type: AtRuleType<'a>,
+    pub name: Cow<'a, str>,
     pub query: String,
-    pub rules: Vec<Rule>,
-    pub at_rules: Vec<AtRule>,
+    pub prelude: AtRulePrelude<'a>,
+    pub rules: Vec<CssRule<'a>>,
+    pub block: Option<Vec<ComponentValue>>, // block of an unknown at-rule, which is not parsed into `rules`
+    pub span: Span,
+    pub comments: Comments<'a>,
+}
+
+impl<'a> AtRule<'a> {
+    pub fn style_rules(&self) -> impl Iterator<Item = &Rule<'a>> {
+        self.rules.iter().filter_map(CssRule::as_style)
+    }
+
+    pub fn at_rules(&self) -> impl Iterator<Item = &AtRule<'a>> {
+        self.rules.iter().filter_map(CssRule::as_at_rule)
+    }
+
+    /// Media queries of an `@media` rule.
+    pub fn media(&self) -> Option<&MediaQueryList<'a>> {
+        match &self.prelude {
+            AtRulePrelude::Media(media) => Some(media),
+            _ => None,
+        }
+    }
+
+    /// Condition of an `@supports` rule.
+    pub fn supports(&self) -> Option<&SupportsCondition<'a>> {
+        match &self.prelude {
+            AtRulePrelude::Supports(condition) => Some(condition),
+            _ => None,
+        }
+    }
+
+    /// Prelude of an `@import` rule.
+    pub fn import(&self) -> Option<&ImportRule<'a>> {
+        match &self.prelude {
+            AtRulePrelude::Import(import) => Some(import),
+            _ => None,
+        }
+    }
+
+    /// Prefix and URI of a `@namespace` rule.
+    pub fn namespace(&self) -> Option<&NamespaceRule<'a>> {
+        match &self.prelude {
+            AtRulePrelude::Namespace(namespace) => Some(namespace),
+            _ => None,
+        }
+    }
+
+    /// Condition of an `@container` rule.
+    pub fn container(&self) -> Option<&ContainerCondition<'a>> {
+        match &self.prelude {
+            AtRulePrelude::Container(condition) => Some(condition),
+            _ => None,
+        }
+    }
+
+    /// Scoping roots and limits of an `@scope` rule.
+    pub fn scope(&self) -> Option<&ScopeRange<'a>> {
+        match &self.prelude {
+            AtRulePrelude::Scope(scope) => Some(scope),
+            _ => None,
+        }
+    }
+
+    /// Selectors of an `@page` rule.
+    pub fn page_selectors(&self) -> Option<&[PageSelector<'a>]> {
+        match &self.prelude {
+            AtRulePrelude::Page(selectors) => Some(selectors),
+            _ => None,
+        }
+    }
+
+    /// Box of a page-margin rule such as `@top-center`.
+    pub fn margin_box(&self) -> Option<MarginBox> {
+        match &self.prelude {
+            AtRulePrelude::MarginBox(margin_box) => Some(*margin_box),
+            _ => None,
+        }
+    }
+
+    /// Typed descriptors of an `@font-face` rule.
+    pub fn font_face(&self) -> Option<FontFaceRule<'a>> {
+        FontFaceRule::from_at_rule(self)
+    }
+
+    /// Typed descriptors of an `@counter-style` rule, `None` if it is invalid.
+    pub fn counter_style(&self) -> Option<CounterStyleRule<'a>> {
+        CounterStyleRule::from_at_rule(self)
+    }
+
+    /// The registration of an `@property` rule, or why it is invalid; `None`
+    /// for other at-rules.
+    pub fn property(&self) -> Option<Result<PropertyRule<'a>, PropertyError>> {
+        (self.rule_type == AtRuleType::Property).then(|| PropertyRule::from_at_rule(self))
+    }
+
+    /// Typed keyframes of a `@keyframes` rule.
+    pub fn keyframes(&self) -> Option<KeyframesRule<'a>> {
+        KeyframesRule::from_at_rule(self)
+    }
+
+    /// Family names of a `@font-feature-values` rule.
+    pub fn font_families(&self) -> Option<&[Cow<'a, str>]> {
+        match &self.prelude {
+            AtRulePrelude::FontFamilies(families) => Some(families),
+            _ => None,
+        }
+    }
+
+    /// Block of a feature-type rule such as `@styleset`.
+    pub fn feature_type(&self) -> Option<FeatureType> {
+        match &self.prelude {
+            AtRulePrelude::FeatureType(feature_type) => Some(*feature_type),
+            _ => None,
+        }
+    }
+
+    /// Typed feature values of a `@font-feature-values` rule.
+    pub fn font_feature_values(&self) -> Option<FontFeatureValuesRule<'a>> {
+        FontFeatureValuesRule::from_at_rule(self)
+    }
+
+    /// Prelude of an unknown at-rule as component values, without the
+    /// whitespace around it.
+    pub fn unknown_prelude(&self) -> Option<&[ComponentValue]> {
+        match &self.prelude {
+            AtRulePrelude::Unknown(values) => Some(trim_whitespace(values)),
+            _ => None,
+        }
+    }
+
+    /// Layers declared by a `@layer` statement, or the layer of a `@layer`
+    /// block; empty for other at-rules.
+    pub fn layer_names(&self) -> &[LayerName<'a>] {
+        match &self.prelude {
+            AtRulePrelude::LayerStatement(names) => names,
+            AtRulePrelude::LayerBlock(name) => std::slice::from_ref(name),
+            _ => &[],
+        }
+    }
+
+    pub fn into_owned(self) -> AtRule<'static> {
+        AtRule {
+            rule_type: self.rule_type.into_owned(),
+            name: owned(self.name),
+            query: self.query,
+            prelude: self.prelude.into_owned(),
+            rules: self.rules.into_iter().map(CssRule::into_owned).collect(),
+            block: self.block,
+            span: self.span,
+            comments: self.comments.into_owned(),
+        }
+    }
 }
 
-impl fmt::Display for AtRule {
+impl fmt::Display for AtRule<'_> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.comments.write_leading(f)?;
         write!(f, "@{}", self.name)?;
 
+        // Unknown at-rules are written back as given. A statement ended by the
+        // `}` of the enclosing block still needs its `;` once other rules
+        // follow it.
+        if let AtRulePrelude::Unknown(prelude) = &self.prelude {
+            write!(f, "{}", to_css(prelude))?;
+            match &self.block {
+                Some(block) => write!(f, "{{{}}}", to_css(block))?,
+                None => write!(f, ";")?,
+            }
+
+            self.comments.write_trailing(f)?;
+            return writeln!(f);
+        }
+
         if !self.query.is_empty() {
             write!(f, " {}", self.query)?;
         }
 
         match self.rule_type {
             AtRuleType::Charset | AtRuleType::Import | AtRuleType::Namespace => {
-                writeln!(f, ";")
+                write!(f, ";")?;
+            },
+            _ if matches!(self.prelude, AtRulePrelude::LayerStatement(_)) => {
+                write!(f, ";")?;
             },
             _ => {
                 writeln!(f, " {{")?;
 
-                for at_rule in &self.at_rules {
-                    let at_rule_str = format!("{}", at_rule);
-                    for line in at_rule_str.lines() {
-                        writeln!(f, "    {}", line)?;
-                    }
-                }
-
-                for rule in &self.rules {
-                    let rule_str = format!("{}", rule);
-                    for line in rule_str.lines() {
-                        writeln!(f, "    {}", line)?;
-                    }
-                }
+                write_child_rules(f, &self.rules)?;
 
-                write!(f, "}}")
+                self.comments.write_dangling(f)?;
+                write!(f, "}}")?;
             }
         }
+
+        self.comments.write_trailing(f)?;
+        writeln!(f)
     }
 }
 
 #[derive(Debug, Clone, PartialEq)]
-pub enum Unit {
+pub enum Unit<'a> {
     Px,
     Em,
     Rem,
@@ -203,10 +783,34 @@ pub enum Unit {
     S,
     Ms,
     None,
-    Other(String),
+    Other(Cow<'a, str>),
+}
+
+impl Unit<'_> {
+    pub fn into_owned(self) -> Unit<'static> {
+        match self {
+            Unit::Px => Unit::Px,
+            Unit::Em => Unit::Em,
+            Unit::Rem => Unit::Rem,
+            Unit::Percent => Unit::Percent,
+            Unit::Vh => Unit::Vh,
+            Unit::Vw => Unit::Vw,
+            Unit::Pt => Unit::Pt,
+            Unit::Cm => Unit::Cm,
+            Unit::Mm => Unit::Mm,
+            Unit::In => Unit::In,
+            Unit::Deg => Unit::Deg,
+            Unit::Rad => Unit::Rad,
+            Unit::Fr => Unit::Fr,
+            Unit::S => Unit::S,
+            Unit::Ms => Unit::Ms,
+            Unit::None => Unit::None,
+            Unit::Other(text) => Unit::Other(owned(text)),
+        }
+    }
 }
 
-impl fmt::Display for Unit {
+impl fmt::Display for Unit<'_> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             Unit::Px => write!(f, "px"),
@@ -231,12 +835,21 @@ impl fmt::Display for Unit {
 }
 
 #[derive(Debug, Clone, PartialEq)]
-pub enum Color {
-    Hex(String),       // #fff, #ff0000
-    Named(String),     // red, blue, transparent
+pub enum Color<'a> {
+    Hex(Cow<'a, str>),       // #fff, #ff0000
+    Named(Cow<'a, str>),     // red, blue, transparent
+}
+
+impl Color<'_> {
+    pub fn into_owned(self) -> Color<'static> {
+        match self {
+            Color::Hex(hex) => Color::Hex(owned(hex)),
+            Color::Named(name) => Color::Named(owned(name)),
+        }
+    }
 }
 
-impl fmt::Display for Color {
+impl fmt::Display for Color<'_> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             Color::Hex(hex) => write!(f, "{}", hex),
@@ -271,15 +884,31 @@ impl fmt::Display for CalcOperator {
 }
 
 #[derive(Debug, Clone, PartialEq)]
-pub enum CalcExpression {
-    Number(f64, Option<Unit>),
-    Variable(String),
-    BinaryOperation(Box<CalcExpression>, CalcOperator, Box<CalcExpression>),
-    Function(String, Vec<CalcExpression>),
-    Parenthesized(Box<CalcExpression>),
+pub enum CalcExpression<'a> {
+    Number(f64, Option<Unit<'a>>),
+    Variable(Cow<'a, str>),
+    BinaryOperation(Box<CalcExpression<'a>>, CalcOperator, Box<CalcExpression<'a>>),
+    Function(Cow<'a, str>, Vec<CalcExpression<'a>>),
+    Parenthesized(Box<CalcExpression<'a>>),
 }
 
-impl fmt::Display for CalcExpression {
+impl CalcExpression<'_> {
+    pub fn into_owned(self) -> CalcExpression<'static> {
+        match self {
+            CalcExpression::Number(num, unit) => CalcExpression::Number(num, unit.map(Unit::into_owned)),
+            CalcExpression::Variable(name) => CalcExpression::Variable(owned(name)),
+            CalcExpression::BinaryOperation(left, op, right) => {
+                CalcExpression::BinaryOperation(Box::new(left.into_owned()), op, Box::new(right.into_owned()))
+            },
+            CalcExpression::Function(name, args) => {
+                CalcExpression::Function(owned(name), args.into_iter().map(CalcExpression::into_owned).collect())
+            },
+            CalcExpression::Parenthesized(expr) => CalcExpression::Parenthesized(Box::new(expr.into_owned())),
+        }
+    }
+}
+
+impl fmt::Display for CalcExpression<'_> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             CalcExpression::Number(num, None) => write!(f, "{}", num),
@@ -305,20 +934,19 @@ pub trait ValueExt {
     fn is(&self, value: &str) -> bool;
 }
 
-impl ValueExt for Value {
+impl ValueExt for Value<'_> {
     fn is(&self, value: &str) -> bool {
-        match self {
-            Value::Keyword(keyword) => keyword == value,
-            Value::Literal(text) => text == value,
-            Value::QuotedString(text) => text == value,
-            Value::Color(color) => {
+        match &self.kind {
+            ValueKind::Keyword(keyword) => keyword == value,
+            ValueKind::Literal(text) => text == value,
+            ValueKind::QuotedString(text) => text == value,
+            ValueKind::Color(color) => {
                 match color {
                     Color::Named(name) => name == value,
                     Color::Hex(hex) => hex == value,
-                    _ => false
                 }
             },
-            Value::Number(num, unit) => {
+            ValueKind::Number(num, unit) => {
                 let value_str = if let Some(unit) = unit {
                     format!("{}{}", num, unit)
                 } else {
@@ -334,28 +962,78 @@ impl ValueExt for Value {
     }
 }
 
+/// A property value, or one item or argument of one, and where it is in the source.
+#[derive(Debug, Clone)]
+pub struct Value<'a> {
+    pub kind: ValueKind<'a>,
+    pub span: Span,
+}
+
+impl<'a> From<ValueKind<'a>> for Value<'a> {
+    /// A value that didn't come from the source, with an empty span.
+    fn from(kind: ValueKind<'a>) -> Self {
+        Value { kind, span: Span::default() }
+    }
+}
+
+impl<'a> Value<'a> {
+    /// Items of a list value, or the value itself when it is not a list.
+    pub fn items(&self) -> Vec<&Value<'a>> {
+        match &self.kind {
+            ValueKind::List(items) => items.iter().collect(),
+            _ => vec![self],
+        }
+    }
+
+    pub fn into_owned(self) -> Value<'static> {
+        Value { kind: self.kind.into_owned(), span: self.span }
+    }
+}
+
+impl fmt::Display for Value<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.kind.fmt(f)
+    }
+}
+
 #[derive(Debug, Clone)]
-pub enum Value {
-    Literal(String),
-    QuotedString(String),
-    Number(f64, Option<Unit>),
-    Color(Color),
-    Function(String, Vec<Value>),
-    VarFunction(String, Option<Box<Value>>),
-    List(Vec<Value>),
-    Keyword(String),
-    Calc(CalcExpression),
-}
-
-impl fmt::Display for Value {
+pub enum ValueKind<'a> {
+    Literal(Cow<'a, str>),
+    QuotedString(Cow<'a, str>),
+    Number(f64, Option<Unit<'a>>),
+    Color(Color<'a>),
+    Function(Cow<'a, str>, Vec<Value<'a>>),
+    VarFunction(Cow<'a, str>, Option<Box<Value<'a>>>),
+    List(Vec<Value<'a>>),
+    Keyword(Cow<'a, str>),
+    Calc(CalcExpression<'a>),
+}
+
+impl ValueKind<'_> {
+    pub fn into_owned(self) -> ValueKind<'static> {
+        match self {
+            ValueKind::Literal(text) => ValueKind::Literal(owned(text)),
+            ValueKind::QuotedString(text) => ValueKind::QuotedString(owned(text)),
+            ValueKind::Number(num, unit) => ValueKind::Number(num, unit.map(Unit::into_owned)),
+            ValueKind::Color(color) => ValueKind::Color(color.into_owned()),
+            ValueKind::Function(name, args) => ValueKind::Function(owned(name), args.into_iter().map(Value::into_owned).collect()),
+            ValueKind::VarFunction(name, fallback) => ValueKind::VarFunction(owned(name), fallback.map(|value| Box::new(value.into_owned()))),
+            ValueKind::List(items) => ValueKind::List(items.into_iter().map(Value::into_owned).collect()),
+            ValueKind::Keyword(keyword) => ValueKind::Keyword(owned(keyword)),
+            ValueKind::Calc(expr) => ValueKind::Calc(expr.into_owned()),
+        }
+    }
+}
+
+impl fmt::Display for ValueKind<'_> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
-            Value::Literal(text) => write!(f, "{}", text),
-            Value::QuotedString(text) => write!(f, "\"{}\"", text),
-            Value::Number(num, None) => write!(f, "{}", num),
-            Value::Number(num, Some(unit)) => write!(f, "{}{}", num, unit),
-            Value::Color(color) => write!(f, "{}", color),
-            Value::Function(name, args) => {
+            ValueKind::Literal(text) => write!(f, "{}", text),
+            ValueKind::QuotedString(text) => write!(f, "\"{}\"", text),
+            ValueKind::Number(num, None) => write!(f, "{}", num),
+            ValueKind::Number(num, Some(unit)) => write!(f, "{}{}", num, unit),
+            ValueKind::Color(color) => write!(f, "{}", color),
+            ValueKind::Function(name, args) => {
                 write!(f, "{}(", name)?;
 
                 let space_separated_functions = ["drop-shadow", "box-shadow", "translate", "rotate", "scale",
@@ -371,9 +1049,9 @@ impl fmt::Display for Value {
                         first = false;
                     }
                 } else {
-                    if args.len() <= 3 && args.len() >= 1 {
-                        match &args[0] {
-                            Value::List(items) => {
+                    if args.len() <= 3 && !args.is_empty() {
+                        match &args[0].kind {
+                            ValueKind::List(items) => {
                                 let mut first = true;
                                 for item in items {
                                     if !first {
@@ -389,8 +1067,8 @@ impl fmt::Display for Value {
                         if args.len() >= 3 && args[1].to_string() == "/" {
                             write!(f, " / ")?;
 
-                            match &args[2] {
-                                Value::List(items) => {
+                            match &args[2].kind {
+                                ValueKind::List(items) => {
                                     let mut first = true;
                                     for item in items {
                                         if !first {
@@ -418,9 +1096,9 @@ impl fmt::Display for Value {
 
                 write!(f, ")")
             }
-            Value::VarFunction(name, None) => write!(f, "var({})", name),
-            Value::VarFunction(name, Some(fallback)) => write!(f, "var({}, {})", name, fallback),
-            Value::List(items) => {
+            ValueKind::VarFunction(name, None) => write!(f, "var({})", name),
+            ValueKind::VarFunction(name, Some(fallback)) => write!(f, "var({}, {})", name, fallback),
+            ValueKind::List(items) => {
                 let mut first = true;
                 for item in items {
                     if !first {
@@ -431,8 +1109,8 @@ impl fmt::Display for Value {
                 }
                 Ok(())
             }
-            Value::Keyword(keyword) => write!(f, "{}", keyword),
-            Value::Calc(expr) => write!(f, "calc({})", expr),
+            ValueKind::Keyword(keyword) => write!(f, "{}", keyword),
+            ValueKind::Calc(expr) => write!(f, "calc({})", expr),
         }
     }
 }
@@ -442,51 +1120,70 @@ pub trait DeclarationExt {
     fn color_is(&self, color_type: &str, value: &str) -> bool;
 }
 
-impl DeclarationExt for Declaration {
+impl DeclarationExt for Declaration<'_> {
     fn has_color(&self, color_str: &str) -> bool {
-        match &self.value {
-            Value::Color(Color::Named(name)) => name == color_str,
-            Value::Color(Color::Hex(hex)) => hex == color_str,
+        match &self.value.kind {
+            ValueKind::Color(Color::Named(name)) => name == color_str,
+            ValueKind::Color(Color::Hex(hex)) => hex == color_str,
             _ => false
         }
     }
 
     fn color_is(&self, color_type: &str, value: &str) -> bool {
-        match (color_type, &self.value) {
-            ("named", Value::Color(Color::Named(name))) => name == value,
-            ("hex", Value::Color(Color::Hex(hex))) => hex == value,
+        match (color_type, &self.value.kind) {
+            ("named", ValueKind::Color(Color::Named(name))) => name == value,
+            ("hex", ValueKind::Color(Color::Hex(hex))) => hex == value,
             _ => false
         }
     }
 }
 
 #[derive(Debug, Clone)]
-pub struct Declaration {
-    pub property: String,
-    pub value: Value,
+pub struct Declaration<'a> {
+    pub property: Cow<'a, str>,
+    pub value: Value<'a>,
     pub is_custom_property: bool,
     pub is_important: bool,
+    pub span: Span,       // property up to the value or `!important`, without `;`
+    pub comments: Comments<'a>,
+}
+
+impl Declaration<'_> {
+    pub fn into_owned(self) -> Declaration<'static> {
+        Declaration {
+            property: owned(self.property),
+            value: self.value.into_owned(),
+            is_custom_property: self.is_custom_property,
+            is_important: self.is_important,
+            span: self.span,
+            comments: self.comments.into_owned(),
+        }
+    }
 }
 
-impl fmt::Display for Declaration {
+impl fmt::Display for Declaration<'_> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.comments.write_leading(f)?;
+
         if self.is_important {
-            write!(f, "{}: {} !important;", self.property, self.value)
+            write!(f, "{}: {} !important;", self.property, self.value)?;
         } else {
-            write!(f, "{}: {};", self.property, self.value)
+            write!(f, "{}: {};", self.property, self.value)?;
         }
+
+        self.comments.write_trailing(f)
     }
 }
 
-pub trait RuleExt {
+pub trait RuleExt<'a> {
     fn declaration_count(&self) -> usize;
     fn has_declaration(&self, property: &str) -> bool;
-    fn get_declaration(&self, property: &str) -> Option<&Declaration>;
-    fn get_declarations(&self, property: &str) -> Vec<&Declaration>;
-    fn get_declaration_value(&self, property: &str) -> Option<&Value>;
+    fn get_declaration(&self, property: &str) -> Option<&Declaration<'a>>;
+    fn get_declarations(&self, property: &str) -> Vec<&Declaration<'a>>;
+    fn get_declaration_value(&self, property: &str) -> Option<&Value<'a>>;
 }
 
-impl RuleExt for Rule {
+impl<'a> RuleExt<'a> for Rule<'a> {
     fn declaration_count(&self) -> usize {
         self.declarations.len()
     }
@@ -495,28 +1192,52 @@ impl RuleExt for Rule {
         self.declarations.iter().any(|decl| decl.property == property)
     }
 
-    fn get_declaration(&self, property: &str) -> Option<&Declaration> {
+    fn get_declaration(&self, property: &str) -> Option<&Declaration<'a>> {
         self.declarations.iter().find(|decl| decl.property == property)
     }
 
-    fn get_declarations(&self, property: &str) -> Vec<&Declaration> {
+    fn get_declarations(&self, property: &str) -> Vec<&Declaration<'a>> {
         self.declarations.iter().filter(|decl| decl.property == property).collect()
     }
 
-    fn get_declaration_value(&self, property: &str) -> Option<&Value> {
+    fn get_declaration_value(&self, property: &str) -> Option<&Value<'a>> {
         self.get_declaration(property).map(|decl| &decl.value)
     }
 }
 
 
 #[derive(Debug)]
-pub struct Rule {
-    pub selectors: Vec<Selector>,
-    pub declarations: Vec<Declaration>,
+pub struct Rule<'a> {
+    pub selectors: Vec<Selector<'a>>,
+    pub declarations: Vec<Declaration<'a>>,
+    pub rules: Vec<CssRule<'a>>, // nested rules in source order, declarations after one in a declaration block
+    pub span: Span,
+    pub comments: Comments<'a>,
 }
 
-impl fmt::Display for Rule {
+impl Rule<'_> {
+    /// Whether this is the body of an at-rule such as `@font-face`, or the
+    /// declarations directly inside a nested at-rule or following a nested
+    /// rule, rather than a style rule.
+    pub fn is_declaration_block(&self) -> bool {
+        self.selectors.iter().all(|selector| selector.groups.is_empty())
+    }
+
+    pub fn into_owned(self) -> Rule<'static> {
+        Rule {
+            selectors: self.selectors.into_iter().map(Selector::into_owned).collect(),
+            declarations: self.declarations.into_iter().map(Declaration::into_owned).collect(),
+            rules: self.rules.into_iter().map(CssRule::into_owned).collect(),
+            span: self.span,
+            comments: self.comments.into_owned(),
+        }
+    }
+}
+
+impl fmt::Display for Rule<'_> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.comments.write_leading(f)?;
+
         for (i, selector) in self.selectors.iter().enumerate() {
             if i > 0 {
                 write!(f, ", ")?;
@@ -526,20 +1247,113 @@ impl fmt::Display for Rule {
 
         writeln!(f, " {{")?;
         for decl in &self.declarations {
-            writeln!(f, "    {}", decl)?;
+            write_indented(f, decl)?;
+        }
+        write_child_rules(f, &self.rules)?;
+        self.comments.write_dangling(f)?;
+        write!(f, "}}")?;
+        self.comments.write_trailing(f)?;
+        writeln!(f)
+    }
+}
+
+/// A rule at any nesting level, in source order. What kind of at-rule it
+/// is comes from its `rule_type`.
+#[derive(Debug)]
+pub enum CssRule<'a> {
+    Style(Rule<'a>),
+    At(AtRule<'a>),
+}
+
+impl<'a> CssRule<'a> {
+    pub fn as_style(&self) -> Option<&Rule<'a>> {
+        match self {
+            CssRule::Style(rule) => Some(rule),
+            CssRule::At(_) => None,
+        }
+    }
+
+    pub fn as_at_rule(&self) -> Option<&AtRule<'a>> {
+        match self {
+            CssRule::Style(_) => None,
+            CssRule::At(at_rule) => Some(at_rule),
+        }
+    }
+
+    /// Type of an at-rule; `None` for a style rule.
+    pub fn rule_type(&self) -> Option<&AtRuleType<'a>> {
+        self.as_at_rule().map(|at_rule| &at_rule.rule_type)
+    }
+
+    /// Rules nested directly inside this one.
+    pub fn children(&self) -> &[CssRule<'a>] {
+        match self {
+            CssRule::Style(rule) => &rule.rules,
+            CssRule::At(at_rule) => &at_rule.rules,
+        }
+    }
+
+    pub fn into_owned(self) -> CssRule<'static> {
+        match self {
+            CssRule::Style(rule) => CssRule::Style(rule.into_owned()),
+            CssRule::At(at_rule) => CssRule::At(at_rule.into_owned()),
+        }
+    }
+}
+
+impl<'a> From<Rule<'a>> for CssRule<'a> {
+    fn from(rule: Rule<'a>) -> Self {
+        CssRule::Style(rule)
+    }
+}
+
+impl<'a> From<AtRule<'a>> for CssRule<'a> {
+    fn from(at_rule: AtRule<'a>) -> Self {
+        CssRule::At(at_rule)
+    }
+}
+
+impl fmt::Display for CssRule<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CssRule::Style(rule) => write!(f, "{}", rule),
+            CssRule::At(at_rule) => write!(f, "{}", at_rule),
         }
-        writeln!(f, "}}")
     }
 }
 
-pub trait StylesheetExt {
-    fn get_rule_by_selector(&self, selector: &str) -> Option<&Rule>;
+/// Rules at any nesting level, depth-first in source order: each rule comes
+/// before the rules nested in it. Returned by `Stylesheet::descendants`.
+pub struct Descendants<'s, 'a> {
+    stack: Vec<std::slice::Iter<'s, CssRule<'a>>>,
+}
+
+impl<'s, 'a> Iterator for Descendants<'s, 'a> {
+    type Item = &'s CssRule<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            match self.stack.last_mut()?.next() {
+                Some(rule) => {
+                    self.stack.push(rule.children().iter());
+                    return Some(rule);
+                },
+                None => {
+                    self.stack.pop();
+                },
+            }
+        }
+    }
+}
+
+pub trait StylesheetExt<'a> {
+    fn get_rule_by_selector(&self, selector: &str) -> Option<&Rule<'a>>;
     fn has_selector(&self, selector: &str) -> bool;
 }
 
-impl StylesheetExt for Stylesheet {
-    fn get_rule_by_selector(&self, selector: &str) -> Option<&Rule> {
-        self.rules.iter().find(|rule| {
+impl<'a> StylesheetExt<'a> for Stylesheet<'a> {
+    fn get_rule_by_selector(&self, selector: &str) -> Option<&Rule<'a>> {
+        self.style_rules().find(|rule| {
             rule.selectors.iter().any(|s| s.to_string() == selector)
         })
     }
@@ -550,19 +1364,46 @@ impl StylesheetExt for Stylesheet {
 }
 
 #[derive(Debug)]
-pub struct Stylesheet {
-    pub rules: Vec<Rule>,
-    pub at_rules: Vec<AtRule>,
+pub struct Stylesheet<'a> {
+    pub rules: Vec<CssRule<'a>>,
+    pub comments: Vec<Comment<'a>>, // after the last rule, e.g. a footer
 }
 
-impl fmt::Display for Stylesheet {
+impl<'a> Stylesheet<'a> {
+    pub fn style_rules(&self) -> impl Iterator<Item = &Rule<'a>> {
+        self.rules.iter().filter_map(CssRule::as_style)
+    }
+
+    pub fn at_rules(&self) -> impl Iterator<Item = &AtRule<'a>> {
+        self.rules.iter().filter_map(CssRule::as_at_rule)
+    }
+
+    /// Every rule at any nesting level, depth-first in source order.
+    pub fn descendants(&self) -> Descendants<'_, 'a> {
+        Descendants { stack: vec![self.rules.iter()] }
+    }
+
+    /// At-rules of `rule_type` at any nesting level, in source order.
+    pub fn at_rules_of_type(&self, rule_type: AtRuleType<'static>) -> impl Iterator<Item = &AtRule<'a>> {
+        self.descendants().filter_map(CssRule::as_at_rule).filter(move |at_rule| at_rule.rule_type == rule_type)
+    }
+
+    /// Copies all borrowed text so the stylesheet can outlive the input.
+    pub fn into_owned(self) -> Stylesheet<'static> {
+        Stylesheet {
+            rules: self.rules.into_iter().map(CssRule::into_owned).collect(),
+            comments: self.comments.into_iter().map(Comment::into_owned).collect(),
+        }
+    }
+}
+
+impl fmt::Display for Stylesheet<'_> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         for rule in &self.rules {
             write!(f, "{}", rule)?;
         }
-
-        for at_rule in &self.at_rules {
-            write!(f, "{}", at_rule)?;
+        for comment in &self.comments {
+            writeln!(f, "{}", comment)?;
         }
 
         Ok(())
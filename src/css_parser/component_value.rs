@@ -0,0 +1,118 @@
+use std::fmt;
+
+use crate::css_parser::tokenizer::{write_escaped, CssTokenType, Tokenizer};
+
+/// Component value of CSS Syntax Level 3 (§5.3), the generic form at-rules
+/// the parser does not know are kept in. Tokens, comments included, keep the
+/// text they were written with, so the values are written back as given.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ComponentValue {
+    Token(CssTokenType, String),            // any token but a function or block delimiter, with its source text
+    Function(String, Vec<ComponentValue>),  // name without the '(', arguments up to ')'
+    Block(BlockKind, Vec<ComponentValue>),  // contents between the delimiters
+}
+
+/// Delimiters of a simple block.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BlockKind {
+    Paren,   // ( )
+    Bracket, // [ ]
+    Brace,   // { }
+}
+
+impl BlockKind {
+    fn delimiters(self) -> (char, char) {
+        match self {
+            BlockKind::Paren => ('(', ')'),
+            BlockKind::Bracket => ('[', ']'),
+            BlockKind::Brace => ('{', '}'),
+        }
+    }
+}
+
+impl ComponentValue {
+    /// Parses `css` as a list of component values. Closing delimiters without
+    /// an opening one are kept as tokens; blocks and functions left open end
+    /// with the input.
+    pub fn parse_list(css: &str) -> Vec<ComponentValue> {
+        let mut tokens = Tokenizer::keeping_comments(css).map(|token| {
+            let text = css[token.position..token.position + token.length].to_string();
+            (token.token_type, text)
+        });
+        consume_list(&mut tokens, None)
+    }
+
+    pub fn is_whitespace(&self) -> bool {
+        matches!(self, ComponentValue::Token(CssTokenType::Whitespace, _))
+    }
+}
+
+fn consume_list(tokens: &mut impl Iterator<Item = (CssTokenType, String)>, closing: Option<&CssTokenType>) -> Vec<ComponentValue> {
+    let mut values = Vec::new();
+
+    while let Some((token, text)) = tokens.next() {
+        if Some(&token) == closing {
+            break;
+        }
+
+        let value = match token {
+            CssTokenType::Function(name) => ComponentValue::Function(name, consume_list(tokens, Some(&CssTokenType::CloseParen))),
+            CssTokenType::OpenParen => ComponentValue::Block(BlockKind::Paren, consume_list(tokens, Some(&CssTokenType::CloseParen))),
+            CssTokenType::OpenBracket => ComponentValue::Block(BlockKind::Bracket, consume_list(tokens, Some(&CssTokenType::CloseBracket))),
+            CssTokenType::OpenBrace => ComponentValue::Block(BlockKind::Brace, consume_list(tokens, Some(&CssTokenType::CloseBrace))),
+            token => ComponentValue::Token(token, text),
+        };
+        values.push(value);
+    }
+
+    values
+}
+
+impl fmt::Display for ComponentValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ComponentValue::Token(_, text) => write!(f, "{}", text),
+            ComponentValue::Function(name, args) => {
+                write_escaped(f, name, None)?;
+                write!(f, "({})", to_css(args))
+            },
+            ComponentValue::Block(kind, contents) => {
+                let (open, close) = kind.delimiters();
+                write!(f, "{}{}{}", open, to_css(contents), close)
+            },
+        }
+    }
+}
+
+/// Writes component values back as CSS text.
+pub fn to_css(values: &[ComponentValue]) -> String {
+    values.iter().map(ComponentValue::to_string).collect()
+}
+
+/// Splits what follows an at-rule's name into its prelude, whitespace
+/// included, and the contents of its `{}` block; `None` when it ends with
+/// `;` instead.
+pub(crate) fn split_at_rule(values: Vec<ComponentValue>) -> (Vec<ComponentValue>, Option<Vec<ComponentValue>>) {
+    let mut prelude = Vec::new();
+    let mut block = None;
+
+    for value in values {
+        match value {
+            ComponentValue::Token(CssTokenType::Semicolon, _) => break,
+            ComponentValue::Block(BlockKind::Brace, contents) => {
+                block = Some(contents);
+                break;
+            },
+            value => prelude.push(value),
+        }
+    }
+
+    (prelude, block)
+}
+
+/// `values` without the whitespace around them.
+pub(crate) fn trim_whitespace(values: &[ComponentValue]) -> &[ComponentValue] {
+    let start = values.iter().take_while(|value| value.is_whitespace()).count();
+    let end = values.len() - values[start..].iter().rev().take_while(|value| value.is_whitespace()).count();
+    &values[start..end]
+}
@@ -0,0 +1,226 @@
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::fmt;
+
+use crate::css_parser::ast::owned;
+use crate::css_parser::media::{evaluate_and, evaluate_or, fmt_joined, FeatureValues, MediaFeature, MediaFeatureValue, Operand};
+
+/// Prelude of an `@container` rule: an optional container name and a query,
+/// e.g. `card (inline-size > 30em) and style(--theme: dark)`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ContainerCondition<'a> {
+    pub name: Option<Cow<'a, str>>,
+    pub query: Option<ContainerQuery<'a>>,
+}
+
+impl ContainerCondition<'_> {
+    /// Whether the rule applies inside `container`: the name, if any, must be
+    /// one of the container's names and the query must hold.
+    pub fn evaluate(&self, container: &ContainerInfo) -> bool {
+        let name_matches = self.name.as_ref().is_none_or(|name| container.names.iter().any(|candidate| candidate == name));
+
+        name_matches && self.query.as_ref().is_none_or(|query| query.evaluate(container) == Some(true))
+    }
+
+    pub fn into_owned(self) -> ContainerCondition<'static> {
+        ContainerCondition {
+            name: self.name.map(owned),
+            query: self.query.map(ContainerQuery::into_owned),
+        }
+    }
+}
+
+impl fmt::Display for ContainerCondition<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (&self.name, &self.query) {
+            (Some(name), Some(query)) => write!(f, "{} {}", name, query),
+            (Some(name), None) => write!(f, "{}", name),
+            (None, Some(query)) => write!(f, "{}", query),
+            (None, None) => Ok(()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ContainerQuery<'a> {
+    Not(Box<ContainerQuery<'a>>),
+    And(Vec<ContainerQuery<'a>>),
+    Or(Vec<ContainerQuery<'a>>),
+    Size(MediaFeature<'a>),  // (inline-size > 30em)
+    Style(StyleQuery<'a>),   // style(--theme: dark)
+    GeneralEnclosed(String), // anything else in parentheses, never matches
+}
+
+impl ContainerQuery<'_> {
+    /// Three-valued result, `None` meaning unknown.
+    pub fn evaluate(&self, container: &ContainerInfo) -> Option<bool> {
+        match self {
+            ContainerQuery::Not(query) => query.evaluate(container).map(|matches| !matches),
+            ContainerQuery::And(queries) => evaluate_and(queries.iter().map(|query| query.evaluate(container))),
+            ContainerQuery::Or(queries) => evaluate_or(queries.iter().map(|query| query.evaluate(container))),
+            ContainerQuery::Size(feature) => feature.evaluate(container),
+            ContainerQuery::Style(query) => Some(query.evaluate(container)),
+            ContainerQuery::GeneralEnclosed(_) => None,
+        }
+    }
+
+    pub fn into_owned(self) -> ContainerQuery<'static> {
+        match self {
+            ContainerQuery::Not(query) => ContainerQuery::Not(Box::new(query.into_owned())),
+            ContainerQuery::And(queries) => ContainerQuery::And(queries.into_iter().map(ContainerQuery::into_owned).collect()),
+            ContainerQuery::Or(queries) => ContainerQuery::Or(queries.into_iter().map(ContainerQuery::into_owned).collect()),
+            ContainerQuery::Size(feature) => ContainerQuery::Size(feature.into_owned()),
+            ContainerQuery::Style(query) => ContainerQuery::Style(query.into_owned()),
+            ContainerQuery::GeneralEnclosed(text) => ContainerQuery::GeneralEnclosed(text),
+        }
+    }
+}
+
+impl Operand for ContainerQuery<'_> {
+    fn is_parenthesized(&self) -> bool {
+        !matches!(self, ContainerQuery::Not(_) | ContainerQuery::And(_) | ContainerQuery::Or(_))
+    }
+}
+
+impl fmt::Display for ContainerQuery<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ContainerQuery::Not(query) => {
+                write!(f, "not ")?;
+                query.fmt_in_parens(f)
+            },
+            ContainerQuery::And(queries) => fmt_joined(f, queries, " and "),
+            ContainerQuery::Or(queries) => fmt_joined(f, queries, " or "),
+            ContainerQuery::Size(feature) => write!(f, "{}", feature),
+            ContainerQuery::Style(query) => write!(f, "style({})", query),
+            ContainerQuery::GeneralEnclosed(text) => write!(f, "{}", text),
+        }
+    }
+}
+
+/// Inside of `style(...)`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum StyleQuery<'a> {
+    Not(Box<StyleQuery<'a>>),
+    And(Vec<StyleQuery<'a>>),
+    Or(Vec<StyleQuery<'a>>),
+    Declaration {            // --theme: dark
+        property: Cow<'a, str>,
+        value: String,       // normalized value text
+    },
+    Property(Cow<'a, str>),  // --theme, true when the property has a value
+}
+
+impl StyleQuery<'_> {
+    /// Compares against the container's computed custom properties. Values are
+    /// compared as text with whitespace runs collapsed.
+    pub fn evaluate(&self, container: &ContainerInfo) -> bool {
+        match self {
+            StyleQuery::Not(query) => !query.evaluate(container),
+            StyleQuery::And(queries) => queries.iter().all(|query| query.evaluate(container)),
+            StyleQuery::Or(queries) => queries.iter().any(|query| query.evaluate(container)),
+            StyleQuery::Declaration { property, value } => container
+                .custom_properties
+                .get(property.as_ref())
+                .is_some_and(|actual| normalize_whitespace(actual) == normalize_whitespace(value)),
+            StyleQuery::Property(property) => container
+                .custom_properties
+                .get(property.as_ref())
+                .is_some_and(|actual| !actual.trim().is_empty()),
+        }
+    }
+
+    pub fn into_owned(self) -> StyleQuery<'static> {
+        match self {
+            StyleQuery::Not(query) => StyleQuery::Not(Box::new(query.into_owned())),
+            StyleQuery::And(queries) => StyleQuery::And(queries.into_iter().map(StyleQuery::into_owned).collect()),
+            StyleQuery::Or(queries) => StyleQuery::Or(queries.into_iter().map(StyleQuery::into_owned).collect()),
+            StyleQuery::Declaration { property, value } => StyleQuery::Declaration { property: owned(property), value },
+            StyleQuery::Property(property) => StyleQuery::Property(owned(property)),
+        }
+    }
+}
+
+impl Operand for StyleQuery<'_> {
+    fn is_parenthesized(&self) -> bool {
+        false
+    }
+}
+
+impl fmt::Display for StyleQuery<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StyleQuery::Not(query) => {
+                write!(f, "not ")?;
+                query.fmt_in_parens(f)
+            },
+            StyleQuery::And(queries) => fmt_joined(f, queries, " and "),
+            StyleQuery::Or(queries) => fmt_joined(f, queries, " or "),
+            StyleQuery::Declaration { property, value } => write!(f, "{}: {}", property, value),
+            StyleQuery::Property(property) => write!(f, "{}", property),
+        }
+    }
+}
+
+fn normalize_whitespace(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// The query container a `ContainerCondition` is evaluated against.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ContainerInfo {
+    pub names: Vec<String>,                        // from `container-name`
+    pub width: f64,                                // content box in CSS px
+    pub height: f64,
+    pub vertical: bool,                            // vertical writing mode, inline size is the height
+    pub font_size: f64,                            // px per em/rem in queries
+    pub custom_properties: HashMap<String, String>, // computed values, e.g. --theme: dark
+}
+
+impl ContainerInfo {
+    pub fn new(width: f64, height: f64) -> Self {
+        ContainerInfo {
+            names: Vec::new(),
+            width,
+            height,
+            vertical: false,
+            font_size: 16.0,
+            custom_properties: HashMap::new(),
+        }
+    }
+
+    pub fn with_name(mut self, name: &str) -> Self {
+        self.names.push(name.to_string());
+        self
+    }
+
+    pub fn with_custom_property(mut self, name: &str, value: &str) -> Self {
+        self.custom_properties.insert(name.to_string(), value.to_string());
+        self
+    }
+}
+
+/// Size features only: viewport, resolution and device features such as
+/// `resolution` or `device-width` are unknown inside `@container`.
+impl FeatureValues for ContainerInfo {
+    fn feature(&self, name: &str) -> Option<MediaFeatureValue<'static>> {
+        let (inline_size, block_size) = if self.vertical { (self.height, self.width) } else { (self.width, self.height) };
+
+        match name.to_ascii_lowercase().as_str() {
+            "width" => Some(MediaFeatureValue::Dimension(self.width, "px".into())),
+            "height" => Some(MediaFeatureValue::Dimension(self.height, "px".into())),
+            "inline-size" => Some(MediaFeatureValue::Dimension(inline_size, "px".into())),
+            "block-size" => Some(MediaFeatureValue::Dimension(block_size, "px".into())),
+            "aspect-ratio" => Some(MediaFeatureValue::Ratio(self.width, self.height)),
+            "orientation" => {
+                let orientation = if self.height >= self.width { "portrait" } else { "landscape" };
+                Some(MediaFeatureValue::Ident(orientation.into()))
+            },
+            _ => None,
+        }
+    }
+
+    fn font_size(&self) -> f64 {
+        self.font_size
+    }
+}
@@ -0,0 +1,560 @@
+use std::borrow::Cow;
+use std::ops::RangeInclusive;
+use std::sync::OnceLock;
+
+use crate::css_parser::ast::{comma_separated, owned, value_text, AtRule, AtRuleType, Stylesheet, Value, ValueKind};
+use crate::css_parser::parser::CssParser;
+
+/// Longest run of repeated symbols a symbolic or additive counter renders
+/// before falling back.
+const MAX_REPETITIONS: u64 = 60;
+
+/// How many `fallback` and `extends` references are followed.
+const MAX_DEPTH: usize = 16;
+
+/// Typed descriptors of an `@counter-style` rule. Descriptors that are
+/// missing are `None`, so that a style extending another one can inherit them.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CounterStyleRule<'a> {
+    pub name: Cow<'a, str>,
+    pub system: Option<CounterSystem<'a>>,         // symbolic when missing
+    pub symbols: Vec<CounterSymbol<'a>>,
+    pub additive_symbols: Vec<AdditiveSymbol<'a>>, // by descending weight
+    pub negative: Option<(CounterSymbol<'a>, Option<CounterSymbol<'a>>)>, // "-" when missing
+    pub prefix: Option<CounterSymbol<'a>>,
+    pub suffix: Option<CounterSymbol<'a>>,         // ". " when missing
+    pub range: Option<Vec<RangeInclusive<i64>>>,   // `auto` when missing
+    pub pad: Option<(usize, CounterSymbol<'a>)>,   // minimum length and padding symbol
+    pub fallback: Option<Cow<'a, str>>,            // decimal when missing
+}
+
+impl<'a> CounterStyleRule<'a> {
+    /// Reads the descriptors of an `@counter-style` rule; `None` for other
+    /// rules and for rules whose symbols don't suit their system.
+    pub fn from_at_rule(at_rule: &AtRule<'a>) -> Option<Self> {
+        if at_rule.rule_type != AtRuleType::CounterStyle {
+            return None;
+        }
+
+        let name = at_rule.query.trim();
+        if name.is_empty() || name.contains(char::is_whitespace) || name.eq_ignore_ascii_case("none") {
+            return None;
+        }
+
+        let mut style = CounterStyleRule {
+            name: Cow::Owned(name.to_string()),
+            system: None,
+            symbols: Vec::new(),
+            additive_symbols: Vec::new(),
+            negative: None,
+            prefix: None,
+            suffix: None,
+            range: None,
+            pad: None,
+            fallback: None,
+        };
+
+        // The last valid value of each descriptor wins.
+        for declaration in at_rule.style_rules().flat_map(|rule| &rule.declarations) {
+            let value = &declaration.value;
+            let items = value.items();
+
+            match declaration.property.to_ascii_lowercase().as_str() {
+                "system" => style.system = CounterSystem::from_values(&items).or(style.system),
+                "symbols" => {
+                    if let Some(symbols) = items.iter().map(|item| CounterSymbol::from_value(item)).collect::<Option<Vec<_>>>() {
+                        style.symbols = symbols;
+                    }
+                },
+                "additive-symbols" => {
+                    let symbols: Option<Vec<_>> = comma_separated(value).iter().map(|items| AdditiveSymbol::from_values(items)).collect();
+                    let descending = |symbols: &Vec<AdditiveSymbol>| symbols.windows(2).all(|pair| pair[0].weight > pair[1].weight);
+                    if let Some(symbols) = symbols.filter(descending) {
+                        style.additive_symbols = symbols;
+                    }
+                },
+                "negative" => match items.as_slice() {
+                    [prefix] => style.negative = CounterSymbol::from_value(prefix).map(|prefix| (prefix, None)).or(style.negative),
+                    [prefix, suffix] => {
+                        if let (Some(prefix), Some(suffix)) = (CounterSymbol::from_value(prefix), CounterSymbol::from_value(suffix)) {
+                            style.negative = Some((prefix, Some(suffix)));
+                        }
+                    },
+                    _ => {},
+                },
+                "prefix" => style.prefix = single(&items).and_then(CounterSymbol::from_value).or(style.prefix),
+                "suffix" => style.suffix = single(&items).and_then(CounterSymbol::from_value).or(style.suffix),
+                "range" => {
+                    if value.to_string().eq_ignore_ascii_case("auto") {
+                        style.range = None;
+                    } else if let Some(ranges) = comma_separated(value).iter().map(|items| range(items)).collect() {
+                        style.range = Some(ranges);
+                    }
+                },
+                "pad" => {
+                    let pad = match items.as_slice() {
+                        [Value { kind: ValueKind::Number(length, None), .. }, symbol] | [symbol, Value { kind: ValueKind::Number(length, None), .. }] => {
+                            let length = (length.fract() == 0.0 && *length >= 0.0).then_some(*length as usize);
+                            length.zip(CounterSymbol::from_value(symbol))
+                        },
+                        _ => None,
+                    };
+                    style.pad = pad.or(style.pad);
+                },
+                "fallback" => {
+                    if let [Value { kind: ValueKind::Literal(name) | ValueKind::Keyword(name), .. }] = items.as_slice() {
+                        style.fallback = Some(name.clone());
+                    }
+                },
+                _ => {},
+            }
+        }
+
+        style.is_valid().then_some(style)
+    }
+
+    /// One of the counter styles every document has, e.g. `decimal`,
+    /// `lower-roman` or `cjk-decimal`. The styles whose algorithm can't be
+    /// written as an `@counter-style` rule are not supported and give
+    /// `None`, see `UNSUPPORTED_PREDEFINED`.
+    pub fn predefined(name: &str) -> Option<&'static CounterStyleRule<'static>> {
+        static PREDEFINED: OnceLock<Vec<CounterStyleRule<'static>>> = OnceLock::new();
+
+        let styles = PREDEFINED.get_or_init(|| {
+            let stylesheet = CssParser::new(PREDEFINED_CSS).parse_stylesheet().expect("predefined counter styles are valid CSS");
+            stylesheet.counter_styles()
+        });
+
+        styles.iter().find(|style| style.name.eq_ignore_ascii_case(name))
+    }
+
+    /// Marker text for `counter_value`, e.g. `iv. ` for `lower-roman`.
+    /// `extends` and `fallback` refer to the predefined styles.
+    pub fn render(&self, counter_value: i64) -> String {
+        self.render_with(counter_value, &[])
+    }
+
+    /// Like `render`, with `extends` and `fallback` also referring to
+    /// `styles`; the last style of a name wins over earlier ones and over the
+    /// predefined styles.
+    pub fn render_with(&self, counter_value: i64, styles: &[CounterStyleRule]) -> String {
+        let mut style = self.resolve(styles);
+
+        for _ in 0..MAX_DEPTH {
+            if let Some(representation) = style.representation(counter_value) {
+                let prefix = style.prefix.as_ref().map_or("", CounterSymbol::text);
+                let suffix = style.suffix.as_ref().map_or(". ", CounterSymbol::text);
+                return format!("{}{}{}", prefix, representation, suffix);
+            }
+
+            let fallback = style.fallback.as_deref().unwrap_or("decimal");
+            match lookup(fallback, styles) {
+                Some(fallback) => style = fallback.resolve(styles),
+                None => break,
+            }
+        }
+
+        format!("{}. ", counter_value)
+    }
+
+    pub fn into_owned(self) -> CounterStyleRule<'static> {
+        CounterStyleRule {
+            name: owned(self.name),
+            system: self.system.map(CounterSystem::into_owned),
+            symbols: self.symbols.into_iter().map(CounterSymbol::into_owned).collect(),
+            additive_symbols: self.additive_symbols.into_iter().map(AdditiveSymbol::into_owned).collect(),
+            negative: self.negative.map(|(prefix, suffix)| (prefix.into_owned(), suffix.map(CounterSymbol::into_owned))),
+            prefix: self.prefix.map(CounterSymbol::into_owned),
+            suffix: self.suffix.map(CounterSymbol::into_owned),
+            range: self.range,
+            pad: self.pad.map(|(length, symbol)| (length, symbol.into_owned())),
+            fallback: self.fallback.map(owned),
+        }
+    }
+
+    fn is_valid(&self) -> bool {
+        match &self.system {
+            None | Some(CounterSystem::Cyclic | CounterSystem::Symbolic | CounterSystem::Fixed(_)) => !self.symbols.is_empty(),
+            Some(CounterSystem::Alphabetic | CounterSystem::Numeric) => self.symbols.len() >= 2,
+            Some(CounterSystem::Additive) => !self.additive_symbols.is_empty(),
+            Some(CounterSystem::Extends(_)) => self.symbols.is_empty() && self.additive_symbols.is_empty(),
+        }
+    }
+
+    /// The style with `extends` replaced by the system and symbols of the
+    /// extended style and missing descriptors taken from it. Unknown or
+    /// cyclic references extend `decimal`.
+    fn resolve<'s>(&'s self, styles: &'s [CounterStyleRule<'s>]) -> CounterStyleRule<'s> {
+        let mut style = self.clone();
+        let mut depth = 0;
+
+        while let Some(CounterSystem::Extends(name)) = &style.system {
+            depth += 1;
+            let base = lookup(name, styles)
+                .filter(|_| depth < MAX_DEPTH)
+                .unwrap_or_else(|| CounterStyleRule::predefined("decimal").expect("decimal is predefined"));
+
+            style = CounterStyleRule {
+                name: style.name,
+                system: base.system.clone(),
+                symbols: base.symbols.clone(),
+                additive_symbols: base.additive_symbols.clone(),
+                negative: style.negative.or_else(|| base.negative.clone()),
+                prefix: style.prefix.or_else(|| base.prefix.clone()),
+                suffix: style.suffix.or_else(|| base.suffix.clone()),
+                range: style.range.or_else(|| base.range.clone()),
+                pad: style.pad.or_else(|| base.pad.clone()),
+                fallback: style.fallback.or_else(|| base.fallback.clone()),
+            };
+        }
+
+        style
+    }
+
+    /// Counter text without prefix and suffix, or `None` when the value is out
+    /// of range or the system can't represent it.
+    fn representation(&self, value: i64) -> Option<String> {
+        let system = self.system.as_ref().unwrap_or(&CounterSystem::Symbolic);
+
+        let in_range = match &self.range {
+            Some(ranges) => ranges.iter().any(|range| range.contains(&value)),
+            None => system.auto_range().contains(&value),
+        };
+        if !in_range {
+            return None;
+        }
+
+        let symbols: Vec<&str> = self.symbols.iter().map(CounterSymbol::text).collect();
+        let uses_negative = matches!(system, CounterSystem::Symbolic | CounterSystem::Alphabetic | CounterSystem::Numeric | CounterSystem::Additive);
+        let negative = uses_negative && value < 0;
+        let magnitude = value.unsigned_abs();
+
+        let mut text = match system {
+            CounterSystem::Cyclic => symbols[(i128::from(value) - 1).rem_euclid(symbols.len() as i128) as usize].to_string(),
+            CounterSystem::Fixed(first) => {
+                let index = usize::try_from(value.checked_sub(*first)?).ok()?;
+                symbols.get(index)?.to_string()
+            },
+            CounterSystem::Symbolic => {
+                let count = symbols.len() as u64;
+                let repetitions = magnitude.checked_sub(1)? / count + 1;
+                if repetitions > MAX_REPETITIONS {
+                    return None;
+                }
+                symbols[((magnitude - 1) % count) as usize].repeat(repetitions as usize)
+            },
+            CounterSystem::Alphabetic => {
+                if magnitude == 0 {
+                    return None;
+                }
+                let count = symbols.len() as u64;
+                let mut digits = Vec::new();
+                let mut rest = magnitude;
+                while rest != 0 {
+                    rest -= 1;
+                    digits.push(symbols[(rest % count) as usize]);
+                    rest /= count;
+                }
+                digits.iter().rev().copied().collect()
+            },
+            CounterSystem::Numeric => {
+                let count = symbols.len() as u64;
+                let mut digits = vec![symbols[(magnitude % count) as usize]];
+                let mut rest = magnitude / count;
+                while rest != 0 {
+                    digits.push(symbols[(rest % count) as usize]);
+                    rest /= count;
+                }
+                digits.iter().rev().copied().collect()
+            },
+            CounterSystem::Additive => {
+                if magnitude == 0 {
+                    let zero = self.additive_symbols.iter().find(|symbol| symbol.weight == 0)?;
+                    zero.symbol.text().to_string()
+                } else {
+                    let mut text = String::new();
+                    let mut rest = magnitude;
+                    let mut repetitions = 0;
+                    for symbol in &self.additive_symbols {
+                        if symbol.weight == 0 || symbol.weight > rest {
+                            continue;
+                        }
+                        let count = rest / symbol.weight;
+                        repetitions += count;
+                        if repetitions > MAX_REPETITIONS {
+                            return None;
+                        }
+                        text.push_str(&symbol.symbol.text().repeat(count as usize));
+                        rest -= count * symbol.weight;
+                    }
+                    if rest != 0 {
+                        return None;
+                    }
+                    text
+                }
+            },
+            CounterSystem::Extends(_) => return None,
+        };
+
+        let (negative_prefix, negative_suffix) = match (&self.negative, negative) {
+            (_, false) => ("", ""),
+            (None, true) => ("-", ""),
+            (Some((prefix, suffix)), true) => (prefix.text(), suffix.as_ref().map_or("", CounterSymbol::text)),
+        };
+
+        // The negative sign counts towards the padded length.
+        if let Some((length, symbol)) = &self.pad {
+            let current = text.chars().count() + negative_prefix.chars().count() + negative_suffix.chars().count();
+            if current < *length {
+                text = symbol.text().repeat(length - current) + &text;
+            }
+        }
+
+        Some(format!("{}{}{}", negative_prefix, text, negative_suffix))
+    }
+}
+
+/// The named style in `styles`, the last one winning, or a predefined style.
+fn lookup<'s>(name: &str, styles: &'s [CounterStyleRule<'s>]) -> Option<&'s CounterStyleRule<'s>> {
+    styles.iter().rev().find(|style| style.name == name).or_else(|| CounterStyleRule::predefined(name))
+}
+
+fn single<'v, 'a>(items: &[&'v Value<'a>]) -> Option<&'v Value<'a>> {
+    match items {
+        [item] => Some(item),
+        _ => None,
+    }
+}
+
+/// One range of the `range` descriptor, e.g. `1 infinite`.
+fn range(items: &[&Value]) -> Option<RangeInclusive<i64>> {
+    let bound = |value: &Value, infinite: i64| match &value.kind {
+        ValueKind::Number(number, None) if number.fract() == 0.0 => Some(*number as i64),
+        ValueKind::Literal(text) | ValueKind::Keyword(text) if text.eq_ignore_ascii_case("infinite") => Some(infinite),
+        ValueKind::Literal(text) | ValueKind::Keyword(text) if text.eq_ignore_ascii_case("-infinite") => Some(i64::MIN),
+        _ => None,
+    };
+
+    match items {
+        [start, end] => {
+            let (start, end) = (bound(start, i64::MIN)?, bound(end, i64::MAX)?);
+            (start <= end).then_some(start..=end)
+        },
+        _ => None,
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum CounterSystem<'a> {
+    Cyclic,                 // loops through the symbols
+    Numeric,                // place-value notation, the first symbol is zero
+    Alphabetic,             // a, b, ..., z, aa, ab, ...
+    Symbolic,               // a, b, ..., z, aa, bb, ...
+    Additive,               // sums of weighted symbols, like roman numerals
+    Fixed(i64),             // the symbols once, starting at the given value
+    Extends(Cow<'a, str>),  // another style with some descriptors overridden
+}
+
+impl<'a> CounterSystem<'a> {
+    fn from_values(items: &[&Value<'a>]) -> Option<Self> {
+        let keyword = |value: &Value| match &value.kind {
+            ValueKind::Literal(text) | ValueKind::Keyword(text) => Some(text.to_ascii_lowercase()),
+            _ => None,
+        };
+
+        match items {
+            [system] => match keyword(system)?.as_str() {
+                "cyclic" => Some(CounterSystem::Cyclic),
+                "numeric" => Some(CounterSystem::Numeric),
+                "alphabetic" => Some(CounterSystem::Alphabetic),
+                "symbolic" => Some(CounterSystem::Symbolic),
+                "additive" => Some(CounterSystem::Additive),
+                "fixed" => Some(CounterSystem::Fixed(1)),
+                _ => None,
+            },
+            [system, Value { kind: ValueKind::Number(first, None), .. }] if keyword(system)? == "fixed" && first.fract() == 0.0 => Some(CounterSystem::Fixed(*first as i64)),
+            [system, Value { kind: ValueKind::Literal(name) | ValueKind::Keyword(name), .. }] if keyword(system)? == "extends" => Some(CounterSystem::Extends(name.clone())),
+            _ => None,
+        }
+    }
+
+    /// Counter values the system represents when `range` is `auto`.
+    fn auto_range(&self) -> RangeInclusive<i64> {
+        match self {
+            CounterSystem::Alphabetic | CounterSystem::Symbolic => 1..=i64::MAX,
+            CounterSystem::Additive => 0..=i64::MAX,
+            _ => i64::MIN..=i64::MAX,
+        }
+    }
+
+    pub fn into_owned(self) -> CounterSystem<'static> {
+        match self {
+            CounterSystem::Cyclic => CounterSystem::Cyclic,
+            CounterSystem::Numeric => CounterSystem::Numeric,
+            CounterSystem::Alphabetic => CounterSystem::Alphabetic,
+            CounterSystem::Symbolic => CounterSystem::Symbolic,
+            CounterSystem::Additive => CounterSystem::Additive,
+            CounterSystem::Fixed(first) => CounterSystem::Fixed(first),
+            CounterSystem::Extends(name) => CounterSystem::Extends(owned(name)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum CounterSymbol<'a> {
+    Text(Cow<'a, str>),  // a string or identifier
+    Image(Cow<'a, str>), // url(...), rendered as nothing
+}
+
+impl<'a> CounterSymbol<'a> {
+    fn from_value(value: &Value<'a>) -> Option<Self> {
+        match &value.kind {
+            ValueKind::QuotedString(text) | ValueKind::Literal(text) | ValueKind::Keyword(text) => Some(CounterSymbol::Text(text.clone())),
+            ValueKind::Function(name, args) if name.eq_ignore_ascii_case("url") => match args.as_slice() {
+                [url] => Some(CounterSymbol::Image(value_text(url))),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
+    pub fn text(&self) -> &str {
+        match self {
+            CounterSymbol::Text(text) => text,
+            CounterSymbol::Image(_) => "",
+        }
+    }
+
+    pub fn into_owned(self) -> CounterSymbol<'static> {
+        match self {
+            CounterSymbol::Text(text) => CounterSymbol::Text(owned(text)),
+            CounterSymbol::Image(url) => CounterSymbol::Image(owned(url)),
+        }
+    }
+}
+
+/// One entry of `additive-symbols`, e.g. `900 CM`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AdditiveSymbol<'a> {
+    pub weight: u64,
+    pub symbol: CounterSymbol<'a>,
+}
+
+impl<'a> AdditiveSymbol<'a> {
+    fn from_values(items: &[&Value<'a>]) -> Option<Self> {
+        match items {
+            [Value { kind: ValueKind::Number(weight, None), .. }, symbol] | [symbol, Value { kind: ValueKind::Number(weight, None), .. }] if weight.fract() == 0.0 && *weight >= 0.0 => {
+                Some(AdditiveSymbol { weight: *weight as u64, symbol: CounterSymbol::from_value(symbol)? })
+            },
+            _ => None,
+        }
+    }
+
+    pub fn into_owned(self) -> AdditiveSymbol<'static> {
+        AdditiveSymbol {
+            weight: self.weight,
+            symbol: self.symbol.into_owned(),
+        }
+    }
+}
+
+impl<'a> Stylesheet<'a> {
+    /// All valid `@counter-style` rules in source order, including those
+    /// inside conditional rules.
+    pub fn counter_styles(&self) -> Vec<CounterStyleRule<'a>> {
+        self.at_rules_of_type(AtRuleType::CounterStyle).filter_map(CounterStyleRule::from_at_rule).collect()
+    }
+}
+
+/// Predefined counter styles that CSS Counter Styles defines with special
+/// algorithms rather than `@counter-style` rules. They are not implemented,
+/// so they behave like unknown styles: `extends` and `fallback` referring to
+/// them end up at `decimal`.
+pub const UNSUPPORTED_PREDEFINED: &[&str] = &[
+    "japanese-informal",
+    "japanese-formal",
+    "korean-hangul-formal",
+    "korean-hanja-informal",
+    "korean-hanja-formal",
+    "simp-chinese-informal",
+    "simp-chinese-formal",
+    "trad-chinese-informal",
+    "trad-chinese-formal",
+    "ethiopic-numeric",
+];
+
+/// The predefined counter styles written as `@counter-style` rules, i.e.
+/// all of them except `UNSUPPORTED_PREDEFINED`.
+const PREDEFINED_CSS: &str = r#"
+@counter-style decimal { system: numeric; symbols: "0" "1" "2" "3" "4" "5" "6" "7" "8" "9"; }
+@counter-style decimal-leading-zero { system: extends decimal; pad: 2 "0"; }
+@counter-style lower-roman {
+    system: additive;
+    range: 1 3999;
+    additive-symbols: 1000 m, 900 cm, 500 d, 400 cd, 100 c, 90 xc, 50 l, 40 xl, 10 x, 9 ix, 5 v, 4 iv, 1 i;
+}
+@counter-style upper-roman {
+    system: additive;
+    range: 1 3999;
+    additive-symbols: 1000 M, 900 CM, 500 D, 400 CD, 100 C, 90 XC, 50 L, 40 XL, 10 X, 9 IX, 5 V, 4 IV, 1 I;
+}
+@counter-style lower-alpha { system: alphabetic; symbols: a b c d e f g h i j k l m n o p q r s t u v w x y z; }
+@counter-style lower-latin { system: extends lower-alpha; }
+@counter-style upper-alpha { system: alphabetic; symbols: A B C D E F G H I J K L M N O P Q R S T U V W X Y Z; }
+@counter-style upper-latin { system: extends upper-alpha; }
+@counter-style lower-greek {
+    system: alphabetic;
+    symbols: "α" "β" "γ" "δ" "ε" "ζ" "η" "θ" "ι" "κ" "λ" "μ" "ν" "ξ" "ο" "π" "ρ" "σ" "τ" "υ" "φ" "χ" "ψ" "ω";
+}
+@counter-style lower-armenian {
+    system: additive;
+    range: 1 9999;
+    additive-symbols: 9000 "ք", 8000 "փ", 7000 "ւ", 6000 "ց", 5000 "ր", 4000 "տ", 3000 "վ", 2000 "ս", 1000 "ռ",
+        900 "ջ", 800 "պ", 700 "չ", 600 "ո", 500 "շ", 400 "ն", 300 "յ", 200 "մ", 100 "ճ",
+        90 "ղ", 80 "ձ", 70 "հ", 60 "կ", 50 "ծ", 40 "խ", 30 "լ", 20 "ի", 10 "ժ",
+        9 "թ", 8 "ը", 7 "է", 6 "զ", 5 "ե", 4 "դ", 3 "գ", 2 "բ", 1 "ա";
+}
+@counter-style upper-armenian {
+    system: additive;
+    range: 1 9999;
+    additive-symbols: 9000 "Ք", 8000 "Փ", 7000 "Ւ", 6000 "Ց", 5000 "Ր", 4000 "Տ", 3000 "Վ", 2000 "Ս", 1000 "Ռ",
+        900 "Ջ", 800 "Պ", 700 "Չ", 600 "Ո", 500 "Շ", 400 "Ն", 300 "Յ", 200 "Մ", 100 "Ճ",
+        90 "Ղ", 80 "Ձ", 70 "Հ", 60 "Կ", 50 "Ծ", 40 "Խ", 30 "Լ", 20 "Ի", 10 "Ժ",
+        9 "Թ", 8 "Ը", 7 "Է", 6 "Զ", 5 "Ե", 4 "Դ", 3 "Գ", 2 "Բ", 1 "Ա";
+}
+@counter-style disc { system: cyclic; symbols: "•"; suffix: " "; }
+@counter-style circle { system: cyclic; symbols: "◦"; suffix: " "; }
+@counter-style square { system: cyclic; symbols: "▪"; suffix: " "; }
+@counter-style disclosure-open { system: cyclic; symbols: "▾"; suffix: " "; }
+@counter-style disclosure-closed { system: cyclic; symbols: "▸"; suffix: " "; }
+@counter-style cjk-decimal {
+    system: numeric;
+    range: 0 infinite;
+    symbols: "〇" "一" "二" "三" "四" "五" "六" "七" "八" "九";
+    suffix: "、";
+}
+@counter-style cjk-earthly-branch {
+    system: alphabetic;
+    symbols: "子" "丑" "寅" "卯" "辰" "巳" "午" "未" "申" "酉" "戌" "亥";
+    suffix: "、";
+}
+@counter-style cjk-heavenly-stem {
+    system: alphabetic;
+    symbols: "甲" "乙" "丙" "丁" "戊" "己" "庚" "辛" "壬" "癸";
+    suffix: "、";
+}
+@counter-style hiragana {
+    system: alphabetic;
+    symbols: "あ" "い" "う" "え" "お" "か" "き" "く" "け" "こ" "さ" "し" "す" "せ" "そ" "た" "ち" "つ" "て" "と"
+        "な" "に" "ぬ" "ね" "の" "は" "ひ" "ふ" "へ" "ほ" "ま" "み" "む" "め" "も" "や" "ゆ" "よ"
+        "ら" "り" "る" "れ" "ろ" "わ" "ゐ" "ゑ" "を" "ん";
+    suffix: "、";
+}
+@counter-style katakana {
+    system: alphabetic;
+    symbols: "ア" "イ" "ウ" "エ" "オ" "カ" "キ" "ク" "ケ" "コ" "サ" "シ" "ス" "セ" "ソ" "タ" "チ" "ツ" "テ" "ト"
+        "ナ" "ニ" "ヌ" "ネ" "ノ" "ハ" "ヒ" "フ" "ヘ" "ホ" "マ" "ミ" "ム" "メ" "モ" "ヤ" "ユ" "ヨ"
+        "ラ" "リ" "ル" "レ" "ロ" "ワ" "ヰ" "ヱ" "ヲ" "ン";
+    suffix: "、";
+}
+"#;
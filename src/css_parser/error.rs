@@ -0,0 +1,224 @@
+use std::fmt;
+use std::io;
+use crate::css_parser::ast::Span;
+use crate::css_parser::lexer::{Token, TokenType};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ParseErrorKind {
+    UnexpectedEof,            // input ended in the middle of a construct
+    UnexpectedToken,          // punctuation or other structural token mismatch
+    ExpectedIdentifier,       // after @, ., #, :, ::
+    ExpectedPropertyName,     // declaration does not start with an identifier
+    InvalidSelector,          // empty or malformed selector
+    InvalidAttributeSelector, // [attr op value flags]
+    ExpectedNumber,           // numeric value or unit missing
+    InvalidVariableName,      // var() argument not starting with --
+    InvalidImportant,         // anything after ! other than important
+    InvalidFunction,          // wrong arguments for a known function
+    InvalidKeyframeSelector,  // anything other than from, to or a percentage
+    InvalidMediaQuery,        // malformed media query outside of general-enclosed parentheses
+    InvalidSupportsCondition, // malformed @supports condition outside of general-enclosed parentheses
+    InvalidContainerQuery,    // malformed @container query outside of general-enclosed parentheses
+}
+
+impl ParseErrorKind {
+    /// Stable identifier of the error kind, safe to match on and to use as a
+    /// localization key.
+    pub fn code(&self) -> &'static str {
+        match self {
+            ParseErrorKind::UnexpectedEof => "E001",
+            ParseErrorKind::UnexpectedToken => "E002",
+            ParseErrorKind::ExpectedIdentifier => "E003",
+            ParseErrorKind::ExpectedPropertyName => "E004",
+            ParseErrorKind::InvalidSelector => "E005",
+            ParseErrorKind::InvalidAttributeSelector => "E006",
+            ParseErrorKind::ExpectedNumber => "E007",
+            ParseErrorKind::InvalidVariableName => "E008",
+            ParseErrorKind::InvalidImportant => "E009",
+            ParseErrorKind::InvalidFunction => "E010",
+            ParseErrorKind::InvalidKeyframeSelector => "E011",
+            ParseErrorKind::InvalidMediaQuery => "E012",
+            ParseErrorKind::InvalidSupportsCondition => "E013",
+            ParseErrorKind::InvalidContainerQuery => "E014",
+        }
+    }
+}
+
+impl fmt::Display for ParseErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseErrorKind::UnexpectedEof => write!(f, "unexpected end of input"),
+            ParseErrorKind::UnexpectedToken => write!(f, "unexpected token"),
+            ParseErrorKind::ExpectedIdentifier => write!(f, "expected identifier"),
+            ParseErrorKind::ExpectedPropertyName => write!(f, "expected property name"),
+            ParseErrorKind::InvalidSelector => write!(f, "invalid selector"),
+            ParseErrorKind::InvalidAttributeSelector => write!(f, "invalid attribute selector"),
+            ParseErrorKind::ExpectedNumber => write!(f, "expected number"),
+            ParseErrorKind::InvalidVariableName => write!(f, "invalid variable name"),
+            ParseErrorKind::InvalidImportant => write!(f, "invalid !important"),
+            ParseErrorKind::InvalidFunction => write!(f, "invalid function"),
+            ParseErrorKind::InvalidKeyframeSelector => write!(f, "invalid keyframe selector"),
+            ParseErrorKind::InvalidMediaQuery => write!(f, "invalid media query"),
+            ParseErrorKind::InvalidSupportsCondition => write!(f, "invalid supports condition"),
+            ParseErrorKind::InvalidContainerQuery => write!(f, "invalid container query"),
+        }
+    }
+}
+
+/// Error produced by the parser. `line`, `column` and `length` describe the
+/// offending range in the same terms as `lexer::Token`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParseError {
+    pub kind: ParseErrorKind,
+    pub expected: Option<String>,
+    pub found: Option<TokenType<'static>>,
+    pub line: usize,
+    pub column: usize,
+    pub length: usize,
+}
+
+impl ParseError {
+    /// Error reported against `found`. Reaching EOF always yields
+    /// `ParseErrorKind::UnexpectedEof` regardless of the requested kind.
+    pub fn unexpected(kind: ParseErrorKind, expected: impl Into<String>, found: &Token<'_>) -> Self {
+        let kind = if matches!(found.token_type, TokenType::EOF) {
+            ParseErrorKind::UnexpectedEof
+        } else {
+            kind
+        };
+
+        ParseError {
+            kind,
+            expected: Some(expected.into()),
+            found: Some(found.token_type.clone().into_owned()),
+            line: found.line,
+            column: found.column,
+            length: found.length,
+        }
+    }
+
+    /// Error that is not caused by a single token, e.g. a wrong argument count.
+    pub fn at(kind: ParseErrorKind, expected: impl Into<String>, line: usize, column: usize, length: usize) -> Self {
+        ParseError {
+            kind,
+            expected: Some(expected.into()),
+            found: None,
+            line,
+            column,
+            length,
+        }
+    }
+
+    pub fn code(&self) -> &'static str {
+        self.kind.code()
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} [{}]", self.kind, self.kind.code())?;
+
+        if let Some(expected) = &self.expected {
+            write!(f, ": expected {}", expected)?;
+        }
+
+        if let Some(found) = &self.found {
+            write!(f, ", found {}", found)?;
+        }
+
+        write!(f, " at line {}, column {}", self.line, self.column)
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// What the parser skipped to get past an error in recovery mode.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Recovery {
+    SkippedDeclaration, // input dropped up to the next ';' of the block
+    SkippedRule,        // rule or at-rule dropped up to its matching '}'
+    ClosedBlock,        // input ended before '}', the block is kept as is
+}
+
+/// Error that was recovered from by `CssParser::parse_stylesheet_with_recovery`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Diagnostic {
+    pub error: ParseError,
+    pub recovery: Recovery,
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.recovery {
+            Recovery::SkippedDeclaration => write!(f, "{} (declaration skipped)", self.error),
+            Recovery::SkippedRule => write!(f, "{} (rule skipped)", self.error),
+            Recovery::ClosedBlock => write!(f, "{} (block closed)", self.error),
+        }
+    }
+}
+
+/// Error produced while inlining imported stylesheets.
+#[derive(Debug)]
+pub enum ImportError {
+    Io { location: String, error: io::Error },       // stylesheet could not be loaded
+    Parse { location: String, error: ParseError },   // stylesheet could not be parsed
+    Cycle(Vec<String>),                              // chain of locations leading back to the first one
+    OutsideRoot(String),                             // url that is absolute or leads outside the resolver's root
+}
+
+impl fmt::Display for ImportError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ImportError::Io { location, error } => write!(f, "cannot load {}: {}", location, error),
+            ImportError::Parse { location, error } => write!(f, "cannot parse {}: {}", location, error),
+            ImportError::Cycle(chain) => write!(f, "import cycle: {}", chain.join(" -> ")),
+            ImportError::OutsideRoot(url) => write!(f, "import outside the root directory: {}", url),
+        }
+    }
+}
+
+impl std::error::Error for ImportError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ImportError::Io { error, .. } => Some(error),
+            ImportError::Parse { error, .. } => Some(error),
+            ImportError::Cycle(_) | ImportError::OutsideRoot(_) => None,
+        }
+    }
+}
+
+/// Why an `@property` rule, or a value of a registered custom property, is
+/// invalid.
+#[derive(Debug, Clone, PartialEq)]
+pub enum PropertyError {
+    InvalidName(String),                                              // prelude is not a custom property name
+    MissingDescriptor { property: String, descriptor: &'static str }, // missing or invalid `syntax`, `inherits` or `initial-value`
+    InvalidSyntax { property: String, syntax: String },               // syntax string that does not parse
+    InvalidInitialValue { property: String, value: String },          // does not match the syntax
+    ComputationallyDependent { property: String, value: String },     // initial value using relative units or var()
+    ValueMismatch {                                                   // declaration that does not match the registered syntax
+        property: String,
+        value: String,
+        syntax: String,
+        span: Span,
+    },
+}
+
+impl fmt::Display for PropertyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PropertyError::InvalidName(name) => write!(f, "invalid custom property name '{}' in @property", name),
+            PropertyError::MissingDescriptor { property, descriptor } => write!(f, "@property {} needs a valid '{}' descriptor", property, descriptor),
+            PropertyError::InvalidSyntax { property, syntax } => write!(f, "@property {} has invalid syntax '{}'", property, syntax),
+            PropertyError::InvalidInitialValue { property, value } => write!(f, "initial value '{}' of {} does not match its syntax", value, property),
+            PropertyError::ComputationallyDependent { property, value } => {
+                write!(f, "initial value '{}' of {} is not computationally independent", value, property)
+            },
+            PropertyError::ValueMismatch { property, value, syntax, span } => {
+                write!(f, "value '{}' of {} does not match '{}' at line {}, column {}", value, property, syntax, span.line, span.column)
+            },
+        }
+    }
+}
+
+impl std::error::Error for PropertyError {}
@@ -0,0 +1,332 @@
+use std::borrow::Cow;
+use std::fmt;
+
+use crate::css_parser::ast::{comma_separated, owned, value_text, AtRule, AtRuleType, Declaration, Unit, Value, ValueKind};
+
+/// Typed descriptors of an `@font-face` rule. Descriptors that are missing
+/// or invalid are `None`; `unicode_range` then covers every code point.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FontFaceRule<'a> {
+    pub family: Option<Cow<'a, str>>,
+    pub sources: Vec<FontSource<'a>>,    // in order of preference
+    pub weight: Option<FontRange>,       // 1 to 1000
+    pub stretch: Option<FontRange>,      // percentages
+    pub style: Option<FontStyle>,
+    pub display: Option<FontDisplay>,
+    pub unicode_range: Vec<UnicodeRange>,
+}
+
+impl<'a> FontFaceRule<'a> {
+    /// Reads the descriptors of an `@font-face` rule; `None` for other rules.
+    pub fn from_at_rule(at_rule: &AtRule<'a>) -> Option<Self> {
+        if at_rule.rule_type != AtRuleType::FontFace {
+            return None;
+        }
+
+        let declarations = at_rule.style_rules().flat_map(|rule| &rule.declarations);
+        Some(Self::from_declarations(declarations))
+    }
+
+    /// Reads descriptors from the declarations of an `@font-face` block. The
+    /// last valid value of each descriptor wins.
+    pub fn from_declarations<'d>(declarations: impl IntoIterator<Item = &'d Declaration<'a>>) -> Self
+    where
+        'a: 'd,
+    {
+        let mut font_face = FontFaceRule {
+            family: None,
+            sources: Vec::new(),
+            weight: None,
+            stretch: None,
+            style: None,
+            display: None,
+            unicode_range: vec![UnicodeRange::ALL],
+        };
+
+        for declaration in declarations {
+            let value = &declaration.value;
+
+            match declaration.property.to_ascii_lowercase().as_str() {
+                "font-family" => font_face.family = Some(value_text(value)),
+                "src" => {
+                    let sources: Option<Vec<_>> = comma_separated(value).iter().map(|items| FontSource::from_values(items)).collect();
+                    if let Some(sources) = sources {
+                        font_face.sources = sources;
+                    }
+                },
+                "font-weight" => font_face.weight = FontRange::from_value(value, font_weight).or(font_face.weight),
+                "font-stretch" => font_face.stretch = FontRange::from_value(value, font_stretch).or(font_face.stretch),
+                "font-style" => font_face.style = FontStyle::from_value(value).or(font_face.style),
+                "font-display" => font_face.display = FontDisplay::from_name(&value.to_string()).or(font_face.display),
+                "unicode-range" => {
+                    let ranges: Option<Vec<_>> = comma_separated(value)
+                        .iter()
+                        .map(|items| match items.as_slice() {
+                            [item] => UnicodeRange::parse(&item.to_string()),
+                            _ => None,
+                        })
+                        .collect();
+                    if let Some(ranges) = ranges {
+                        font_face.unicode_range = ranges;
+                    }
+                },
+                _ => {},
+            }
+        }
+
+        font_face
+    }
+
+    /// Whether the font is used for `ch` according to its `unicode-range`.
+    pub fn covers(&self, ch: char) -> bool {
+        self.unicode_range.iter().any(|range| range.contains(ch))
+    }
+
+    /// Fraction of the characters of `text` the font is used for, from 0 to 1;
+    /// 1 for empty text.
+    pub fn coverage(&self, text: &str) -> f64 {
+        let total = text.chars().count();
+        if total == 0 {
+            return 1.0;
+        }
+
+        let covered = text.chars().filter(|&ch| self.covers(ch)).count();
+        covered as f64 / total as f64
+    }
+
+    pub fn into_owned(self) -> FontFaceRule<'static> {
+        FontFaceRule {
+            family: self.family.map(owned),
+            sources: self.sources.into_iter().map(FontSource::into_owned).collect(),
+            weight: self.weight,
+            stretch: self.stretch,
+            style: self.style,
+            display: self.display,
+            unicode_range: self.unicode_range,
+        }
+    }
+}
+
+/// One entry of the `src` descriptor.
+#[derive(Debug, Clone, PartialEq)]
+pub enum FontSource<'a> {
+    Url {                          // url("a.woff2") format("woff2") tech(variations)
+        url: Cow<'a, str>,
+        format: Option<Cow<'a, str>>,
+        tech: Vec<Cow<'a, str>>,
+    },
+    Local(Cow<'a, str>),           // local("Open Sans"), a font installed on the system
+}
+
+impl<'a> FontSource<'a> {
+    fn from_values(items: &[&Value<'a>]) -> Option<Self> {
+        let (first, hints) = items.split_first()?;
+
+        match &first.kind {
+            ValueKind::Function(name, args) if name.eq_ignore_ascii_case("local") && hints.is_empty() => {
+                Some(FontSource::Local(text_of_args(args)))
+            },
+            ValueKind::Function(name, args) if name.eq_ignore_ascii_case("url") => {
+                let mut format = None;
+                let mut tech = Vec::new();
+
+                for hint in hints {
+                    match &hint.kind {
+                        ValueKind::Function(name, args) if name.eq_ignore_ascii_case("format") => format = Some(text_of_args(args)),
+                        ValueKind::Function(name, args) if name.eq_ignore_ascii_case("tech") => {
+                            tech.extend(args.iter().map(value_text).filter(|tech| tech != ","));
+                        },
+                        _ => return None,
+                    }
+                }
+
+                Some(FontSource::Url { url: text_of_args(args), format, tech })
+            },
+            _ => None,
+        }
+    }
+
+    pub fn into_owned(self) -> FontSource<'static> {
+        match self {
+            FontSource::Url { url, format, tech } => FontSource::Url {
+                url: owned(url),
+                format: format.map(owned),
+                tech: tech.into_iter().map(owned).collect(),
+            },
+            FontSource::Local(name) => FontSource::Local(owned(name)),
+        }
+    }
+}
+
+/// Inclusive range of a variable font axis; both ends are equal for a single
+/// value.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FontRange {
+    pub min: f64,
+    pub max: f64,
+}
+
+impl FontRange {
+    /// One or two values, each converted by `number`, e.g. `100 900`.
+    fn from_value(value: &Value, number: fn(&Value) -> Option<f64>) -> Option<Self> {
+        let values: Vec<f64> = match &value.kind {
+            ValueKind::List(items) => items.iter().map(number).collect::<Option<_>>()?,
+            _ => vec![number(value)?],
+        };
+
+        match values.as_slice() {
+            [value] => Some(FontRange { min: *value, max: *value }),
+            [min, max] => Some(FontRange { min: min.min(*max), max: min.max(*max) }),
+            _ => None,
+        }
+    }
+
+    pub fn contains(&self, value: f64) -> bool {
+        self.min <= value && value <= self.max
+    }
+}
+
+fn font_weight(value: &Value) -> Option<f64> {
+    match &value.kind {
+        ValueKind::Number(weight, None | Some(Unit::None)) if (1.0..=1000.0).contains(weight) => Some(*weight),
+        ValueKind::Number(..) => None,
+        _ => match value.to_string().to_ascii_lowercase().as_str() {
+            "normal" => Some(400.0),
+            "bold" => Some(700.0),
+            _ => None,
+        },
+    }
+}
+
+fn font_stretch(value: &Value) -> Option<f64> {
+    match &value.kind {
+        ValueKind::Number(percentage, Some(Unit::Percent)) => Some(*percentage),
+        ValueKind::Number(..) => None,
+        _ => match value.to_string().to_ascii_lowercase().as_str() {
+            "ultra-condensed" => Some(50.0),
+            "extra-condensed" => Some(62.5),
+            "condensed" => Some(75.0),
+            "semi-condensed" => Some(87.5),
+            "normal" => Some(100.0),
+            "semi-expanded" => Some(112.5),
+            "expanded" => Some(125.0),
+            "extra-expanded" => Some(150.0),
+            "ultra-expanded" => Some(200.0),
+            _ => None,
+        },
+    }
+}
+
+fn angle(value: &Value) -> Option<f64> {
+    match &value.kind {
+        ValueKind::Number(degrees, Some(Unit::Deg)) => Some(*degrees),
+        ValueKind::Number(radians, Some(Unit::Rad)) => Some(radians.to_degrees()),
+        ValueKind::Number(angle, None | Some(Unit::None)) if *angle == 0.0 => Some(0.0),
+        _ => None,
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FontStyle {
+    Normal,
+    Italic,
+    Oblique(FontRange), // angles in degrees, 14deg when not given
+}
+
+impl FontStyle {
+    fn from_value(value: &Value) -> Option<Self> {
+        let (keyword, angles) = match &value.kind {
+            ValueKind::List(items) => (items.first()?, &items[1..]),
+            _ => (value, &[][..]),
+        };
+
+        match keyword.to_string().to_ascii_lowercase().as_str() {
+            "normal" if angles.is_empty() => Some(FontStyle::Normal),
+            "italic" if angles.is_empty() => Some(FontStyle::Italic),
+            "oblique" if angles.is_empty() => Some(FontStyle::Oblique(FontRange { min: 14.0, max: 14.0 })),
+            "oblique" => FontRange::from_value(&Value::from(ValueKind::List(angles.to_vec())), angle).map(FontStyle::Oblique),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FontDisplay {
+    Auto,
+    Block,
+    Swap,
+    Fallback,
+    Optional,
+}
+
+impl FontDisplay {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "auto" => Some(FontDisplay::Auto),
+            "block" => Some(FontDisplay::Block),
+            "swap" => Some(FontDisplay::Swap),
+            "fallback" => Some(FontDisplay::Fallback),
+            "optional" => Some(FontDisplay::Optional),
+            _ => None,
+        }
+    }
+}
+
+/// Inclusive interval of code points from `unicode-range`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct UnicodeRange {
+    pub start: u32,
+    pub end: u32,
+}
+
+impl UnicodeRange {
+    pub const ALL: UnicodeRange = UnicodeRange { start: 0, end: 0x10FFFF };
+
+    /// Parses `U+0131`, `U+0025-00FF` or `U+4??`, where each `?` stands for
+    /// any hex digit.
+    pub fn parse(text: &str) -> Option<Self> {
+        let digits = text.strip_prefix("U+").or_else(|| text.strip_prefix("u+"))?;
+        let hex = |digits: &str| {
+            let valid = (1..=6).contains(&digits.len()) && digits.chars().all(|ch| ch.is_ascii_hexdigit());
+            if valid { u32::from_str_radix(digits, 16).ok() } else { None }
+        };
+
+        let (start, end) = if let Some((start, end)) = digits.split_once('-') {
+            (hex(start)?, hex(end)?)
+        } else if digits.contains('?') {
+            let fixed = digits.trim_end_matches('?');
+            if fixed.contains('?') {
+                return None;
+            }
+            let wildcards = digits.len() - fixed.len();
+            (hex(&format!("{}{}", fixed, "0".repeat(wildcards)))?, hex(&format!("{}{}", fixed, "F".repeat(wildcards)))?)
+        } else {
+            let code_point = hex(digits)?;
+            (code_point, code_point)
+        };
+
+        (start <= end && end <= Self::ALL.end).then_some(UnicodeRange { start, end })
+    }
+
+    pub fn contains(&self, ch: char) -> bool {
+        (self.start..=self.end).contains(&(ch as u32))
+    }
+}
+
+impl fmt::Display for UnicodeRange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.start == self.end {
+            write!(f, "U+{:X}", self.start)
+        } else {
+            write!(f, "U+{:X}-{:X}", self.start, self.end)
+        }
+    }
+}
+
+/// Text of the arguments of a function such as `local(Open Sans)`.
+fn text_of_args<'a>(args: &[Value<'a>]) -> Cow<'a, str> {
+    match args {
+        [arg] => value_text(arg),
+        args => Cow::Owned(args.iter().map(value_text).collect::<Vec<_>>().join(" ")),
+    }
+}
@@ -0,0 +1,217 @@
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::fmt;
+
+use crate::css_parser::ast::{owned, AtRule, AtRuleType, Stylesheet, Value, ValueKind};
+use crate::css_parser::font_face::FontDisplay;
+
+/// The feature-type blocks of `@font-feature-values`, one at-rule each.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FeatureType {
+    Stylistic,        // salt, one value
+    Styleset,         // ss01 to ss20, any number of values
+    CharacterVariant, // cv01 to cv99, the variant and an optional value
+    Swash,            // swsh and cswh, one value
+    Ornaments,        // ornm, one value
+    Annotation,       // nalt, one value
+}
+
+impl FeatureType {
+    pub const ALL: [FeatureType; 6] = [
+        FeatureType::Stylistic,
+        FeatureType::Styleset,
+        FeatureType::CharacterVariant,
+        FeatureType::Swash,
+        FeatureType::Ornaments,
+        FeatureType::Annotation,
+    ];
+
+    /// The block for an at-rule name such as `styleset`, which is also the
+    /// function name in `font-variant-alternates`.
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|feature_type| feature_type.name().eq_ignore_ascii_case(name))
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            FeatureType::Stylistic => "stylistic",
+            FeatureType::Styleset => "styleset",
+            FeatureType::CharacterVariant => "character-variant",
+            FeatureType::Swash => "swash",
+            FeatureType::Ornaments => "ornaments",
+            FeatureType::Annotation => "annotation",
+        }
+    }
+
+    /// Whether a feature value declaration is valid for this block.
+    fn accepts(&self, values: &[u32]) -> bool {
+        match self {
+            FeatureType::Styleset => !values.is_empty() && values.iter().all(|value| (1..=20).contains(value)),
+            FeatureType::CharacterVariant => matches!(values, [1..=99] | [1..=99, _]),
+            _ => values.len() == 1,
+        }
+    }
+}
+
+impl fmt::Display for FeatureType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+/// Typed form of a `@font-feature-values` rule: per feature type, the names
+/// authors use in `font-variant-alternates` and the values they stand for.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct FontFeatureValuesRule<'a> {
+    pub families: Vec<Cow<'a, str>>,
+    pub font_display: Option<FontDisplay>,
+    pub features: HashMap<FeatureType, HashMap<Cow<'a, str>, Vec<u32>>>,
+}
+
+impl<'a> FontFeatureValuesRule<'a> {
+    /// Reads a `@font-feature-values` rule; `None` for other rules. Feature
+    /// values that aren't non-negative integers, or too many or out of range
+    /// for their block, are ignored.
+    pub fn from_at_rule(at_rule: &AtRule<'a>) -> Option<Self> {
+        if at_rule.rule_type != AtRuleType::FontFeatureValues {
+            return None;
+        }
+
+        let families = at_rule.font_families().unwrap_or_default().to_vec();
+        let mut rule = FontFeatureValuesRule { families, ..Self::default() };
+        rule.merge(at_rule);
+        Some(rule)
+    }
+
+    /// Values for `name` in the block of `feature_type`.
+    pub fn get(&self, feature_type: FeatureType, name: &str) -> Option<&[u32]> {
+        self.features.get(&feature_type)?.get(name).map(Vec::as_slice)
+    }
+
+    /// OpenType feature settings for a `font-variant-alternates` value, e.g.
+    /// `styleset(nice-style) historical-forms` gives `"ss12" 1, "hist" 1`.
+    /// Unknown names are ignored.
+    pub fn resolve(&self, font_variant_alternates: &Value) -> Vec<FeatureSetting> {
+        let mut settings = Vec::new();
+        for item in font_variant_alternates.items() {
+            let (feature_type, args) = match &item.kind {
+                ValueKind::Function(name, args) => match FeatureType::from_name(name) {
+                    Some(feature_type) => (feature_type, args),
+                    None => continue,
+                },
+                ValueKind::Literal(keyword) | ValueKind::Keyword(keyword) if keyword.eq_ignore_ascii_case("historical-forms") => {
+                    settings.push(FeatureSetting::new("hist", 1));
+                    continue;
+                },
+                _ => continue,
+            };
+
+            let mut names = args.iter().filter(|arg| !matches!(&arg.kind, ValueKind::Literal(text) if text == ",")).map(Value::to_string);
+            // Only `styleset` and `character-variant` take several names.
+            let names: Vec<String> = match feature_type {
+                FeatureType::Styleset | FeatureType::CharacterVariant => names.collect(),
+                _ => names.next().into_iter().collect(),
+            };
+
+            for name in names {
+                let Some(values) = self.get(feature_type, &name) else { continue };
+
+                match (feature_type, values) {
+                    (FeatureType::Stylistic, [value]) => settings.push(FeatureSetting::new("salt", *value)),
+                    (FeatureType::Styleset, values) => {
+                        settings.extend(values.iter().map(|value| FeatureSetting::new(format!("ss{:02}", value), 1)));
+                    },
+                    (FeatureType::CharacterVariant, [variant]) => settings.push(FeatureSetting::new(format!("cv{:02}", variant), 1)),
+                    (FeatureType::CharacterVariant, [variant, value]) => settings.push(FeatureSetting::new(format!("cv{:02}", variant), *value)),
+                    (FeatureType::Swash, [value]) => {
+                        settings.push(FeatureSetting::new("swsh", *value));
+                        settings.push(FeatureSetting::new("cswh", *value));
+                    },
+                    (FeatureType::Ornaments, [value]) => settings.push(FeatureSetting::new("ornm", *value)),
+                    (FeatureType::Annotation, [value]) => settings.push(FeatureSetting::new("nalt", *value)),
+                    _ => {},
+                }
+            }
+        }
+
+        settings
+    }
+
+    pub fn into_owned(self) -> FontFeatureValuesRule<'static> {
+        FontFeatureValuesRule {
+            families: self.families.into_iter().map(owned).collect(),
+            font_display: self.font_display,
+            features: self
+                .features
+                .into_iter()
+                .map(|(feature_type, values)| (feature_type, values.into_iter().map(|(name, values)| (owned(name), values)).collect()))
+                .collect(),
+        }
+    }
+
+    /// Adds the declarations of `at_rule`; later values for a name win.
+    fn merge(&mut self, at_rule: &AtRule<'a>) {
+        for declaration in at_rule.style_rules().flat_map(|rule| &rule.declarations) {
+            if declaration.property.eq_ignore_ascii_case("font-display") {
+                self.font_display = FontDisplay::from_name(&declaration.value.to_string()).or(self.font_display);
+            }
+        }
+
+        for block in at_rule.at_rules() {
+            let Some(feature_type) = block.feature_type() else { continue };
+
+            for declaration in block.style_rules().flat_map(|rule| &rule.declarations) {
+                let values: Option<Vec<u32>> = declaration
+                    .value
+                    .items()
+                    .iter()
+                    .map(|item| match &item.kind {
+                        ValueKind::Number(value, None) if value.fract() == 0.0 && *value >= 0.0 => u32::try_from(*value as u64).ok(),
+                        _ => None,
+                    })
+                    .collect();
+
+                if let Some(values) = values.filter(|values| feature_type.accepts(values)) {
+                    self.features.entry(feature_type).or_default().insert(declaration.property.clone(), values);
+                }
+            }
+        }
+    }
+}
+
+/// An OpenType feature and its value, as in `font-feature-settings`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FeatureSetting {
+    pub tag: String, // four letters, e.g. ss01
+    pub value: u32,
+}
+
+impl FeatureSetting {
+    pub fn new(tag: impl Into<String>, value: u32) -> Self {
+        FeatureSetting { tag: tag.into(), value }
+    }
+}
+
+impl fmt::Display for FeatureSetting {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "\"{}\" {}", self.tag, self.value)
+    }
+}
+
+impl<'a> Stylesheet<'a> {
+    /// Feature values for `family` from all `@font-feature-values` rules
+    /// listing it, merged in source order. Family names are compared
+    /// case-insensitively.
+    pub fn font_feature_values(&self, family: &str) -> Option<FontFeatureValuesRule<'a>> {
+        let mut merged: Option<FontFeatureValuesRule<'a>> = None;
+        for at_rule in self.at_rules_of_type(AtRuleType::FontFeatureValues) {
+            let families = at_rule.font_families().unwrap_or_default();
+            if !families.iter().any(|name| name.eq_ignore_ascii_case(family)) {
+                continue;
+            }
+
+            merged.get_or_insert_with(|| FontFeatureValuesRule { families: vec![Cow::Owned(family.to_string())], ..Default::default() }).merge(at_rule);
+        }
+        merged
+    }
+}
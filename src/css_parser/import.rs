@@ -0,0 +1,187 @@
+use std::borrow::Cow;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use crate::css_parser::ast::{owned, AtRule, AtRulePrelude, AtRuleType, Comments, CssRule, LayerName, Span, Stylesheet};
+use crate::css_parser::error::ImportError;
+use crate::css_parser::media::MediaQueryList;
+use crate::css_parser::parser::CssParser;
+use crate::css_parser::supports::SupportsCondition;
+
+/// Prelude of an `@import` rule, e.g.
+/// `url("theme.css") layer(base) supports(display: grid) screen`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ImportRule<'a> {
+    pub url: Cow<'a, str>,
+    pub layer: Option<LayerName<'a>>, // anonymous for a bare `layer`
+    pub supports: Option<SupportsCondition<'a>>,
+    pub media: MediaQueryList<'a>,    // empty when the import is unconditional
+}
+
+impl ImportRule<'_> {
+    pub fn into_owned(self) -> ImportRule<'static> {
+        ImportRule {
+            url: owned(self.url),
+            layer: self.layer.map(LayerName::into_owned),
+            supports: self.supports.map(SupportsCondition::into_owned),
+            media: self.media.into_owned(),
+        }
+    }
+}
+
+impl fmt::Display for ImportRule<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "\"{}\"", self.url)?;
+
+        match &self.layer {
+            Some(layer) if layer.is_anonymous() => write!(f, " layer")?,
+            Some(layer) => write!(f, " layer({})", layer)?,
+            None => {}
+        }
+
+        match &self.supports {
+            // A single declaration is written without its own parentheses.
+            Some(SupportsCondition::Declaration { property, value }) => write!(f, " supports({}: {})", property, value)?,
+            Some(condition) => write!(f, " supports({})", condition)?,
+            None => {}
+        }
+
+        if !self.media.queries.is_empty() {
+            write!(f, " {}", self.media)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Locates and loads imported stylesheets.
+pub trait ImportResolver {
+    /// Canonical location of `url` imported by the stylesheet at `base`, or
+    /// of the root stylesheet when `base` is `None`. Two imports of the same
+    /// stylesheet must yield the same location.
+    fn locate(&self, url: &str, base: Option<&str>) -> Result<String, ImportError>;
+
+    fn load(&self, location: &str) -> io::Result<String>;
+}
+
+/// Resolves imports relative to the importing file, and the root stylesheet
+/// relative to `root`. Absolute paths and paths that lead outside `root` are
+/// rejected with `ImportError::OutsideRoot`.
+#[derive(Debug, Clone)]
+pub struct FileSystemResolver {
+    pub root: PathBuf,
+}
+
+impl FileSystemResolver {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        FileSystemResolver { root: root.into() }
+    }
+}
+
+impl ImportResolver for FileSystemResolver {
+    fn locate(&self, url: &str, base: Option<&str>) -> Result<String, ImportError> {
+        if Path::new(url).is_absolute() {
+            return Err(ImportError::OutsideRoot(url.to_string()));
+        }
+
+        let directory = match base {
+            Some(base) => Path::new(base).parent().map(Path::to_path_buf).unwrap_or_default(),
+            None => self.root.clone(),
+        };
+
+        let path = directory.join(url);
+        let root = fs::canonicalize(&self.root).unwrap_or_else(|_| self.root.clone());
+        match fs::canonicalize(&path) {
+            Ok(path) if path.starts_with(&root) => Ok(path.to_string_lossy().into_owned()),
+            Ok(_) => Err(ImportError::OutsideRoot(url.to_string())),
+            Err(error) => Err(ImportError::Io { location: path.to_string_lossy().into_owned(), error }),
+        }
+    }
+
+    fn load(&self, location: &str) -> io::Result<String> {
+        fs::read_to_string(location)
+    }
+}
+
+/// Loads the stylesheet at `url` and replaces every `@import` with the rules
+/// of the imported stylesheet, recursively. Conditions and layers of an
+/// import are kept by wrapping the inlined rules in `@layer`, `@supports` and
+/// `@media` blocks, outermost first. Imports that lead back to a stylesheet
+/// that is being inlined fail with `ImportError::Cycle`.
+pub fn inline_imports(resolver: &dyn ImportResolver, url: &str) -> Result<Stylesheet<'static>, ImportError> {
+    let location = resolver.locate(url, None)?;
+    let mut stack = Vec::new();
+    load_inlined(resolver, location, &mut stack)
+}
+
+fn load_inlined(resolver: &dyn ImportResolver, location: String, stack: &mut Vec<String>) -> Result<Stylesheet<'static>, ImportError> {
+    if stack.contains(&location) {
+        let mut chain = stack.clone();
+        chain.push(location);
+        return Err(ImportError::Cycle(chain));
+    }
+
+    let css = resolver.load(&location).map_err(|error| ImportError::Io { location: location.clone(), error })?;
+    let stylesheet = CssParser::new(&css)
+        .parse_stylesheet()
+        .map_err(|error| ImportError::Parse { location: location.clone(), error })?
+        .into_owned();
+
+    stack.push(location);
+
+    let mut rules = Vec::new();
+    for rule in stylesheet.rules {
+        let import = match &rule {
+            CssRule::At(AtRule { prelude: AtRulePrelude::Import(import), .. }) => import,
+            _ => {
+                rules.push(rule);
+                continue;
+            },
+        };
+
+        let base = stack.last().map(String::as_str);
+        let location = resolver.locate(&import.url, base)?;
+        let imported = load_inlined(resolver, location, stack)?;
+
+        // @charset is only meaningful at the start of a file.
+        let mut inlined: Vec<CssRule> = imported.rules.into_iter().filter(|rule| rule.rule_type() != Some(&AtRuleType::Charset)).collect();
+
+        if !import.media.queries.is_empty() {
+            let query = import.media.to_string();
+            inlined = vec![wrap(AtRuleType::Media, query, AtRulePrelude::Media(import.media.clone()), inlined)];
+        }
+        if let Some(condition) = &import.supports {
+            inlined = vec![wrap(AtRuleType::Supports, condition.to_string(), AtRulePrelude::Supports(condition.clone()), inlined)];
+        }
+        if let Some(layer) = &import.layer {
+            inlined = vec![wrap(AtRuleType::Layer, layer.to_string(), AtRulePrelude::LayerBlock(layer.clone()), inlined)];
+        }
+
+        rules.extend(inlined);
+    }
+
+    stack.pop();
+
+    Ok(Stylesheet { rules, comments: Vec::new() })
+}
+
+fn wrap(rule_type: AtRuleType<'static>, query: String, prelude: AtRulePrelude<'static>, rules: Vec<CssRule<'static>>) -> CssRule<'static> {
+    let name = match rule_type {
+        AtRuleType::Media => "media",
+        AtRuleType::Supports => "supports",
+        _ => "layer",
+    };
+
+    CssRule::from(AtRule {
+        rule_type,
+        name: name.into(),
+        query,
+        prelude,
+        rules,
+        block: None,
+        span: Span::default(),
+        comments: Comments::default(),
+    })
+}
@@ -0,0 +1,193 @@
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::fmt;
+
+use crate::css_parser::ast::{owned, AtRule, AtRuleType, Declaration, Rule, Stylesheet, Value};
+
+const VENDOR_PREFIXES: [&str; 4] = ["-webkit-", "-moz-", "-o-", "-ms-"];
+
+/// Typed form of a `@keyframes` rule or one of its vendor-prefixed variants.
+#[derive(Debug, Clone)]
+pub struct KeyframesRule<'a> {
+    pub name: Cow<'a, str>,
+    pub prefix: Option<&'static str>, // "-webkit-" for `@-webkit-keyframes`
+    pub frames: Vec<Keyframe<'a>>,    // in source order
+}
+
+impl<'a> KeyframesRule<'a> {
+    /// Reads the keyframes of a `@keyframes` rule; `None` for other rules.
+    pub fn from_at_rule(at_rule: &AtRule<'a>) -> Option<Self> {
+        if at_rule.rule_type != AtRuleType::Keyframes {
+            return None;
+        }
+
+        let prefix = VENDOR_PREFIXES.into_iter().find(|prefix| at_rule.name.starts_with(prefix));
+        let name = at_rule.query.trim();
+        let name = name.strip_prefix('"').and_then(|name| name.strip_suffix('"')).unwrap_or(name);
+
+        Some(KeyframesRule {
+            name: Cow::Owned(name.to_string()),
+            prefix,
+            frames: at_rule.style_rules().map(Keyframe::from_rule).collect(),
+        })
+    }
+
+    /// The frames that apply at `offset`, from 0 to 1, on the whole animation
+    /// rather than a named timeline range.
+    pub fn frames_at(&self, offset: f64) -> impl Iterator<Item = &Keyframe<'a>> {
+        self.frames.iter().filter(move |frame| frame.offsets.iter().any(|frame_offset| frame_offset.range.is_none() && frame_offset.offset == offset))
+    }
+
+    pub fn into_owned(self) -> KeyframesRule<'static> {
+        KeyframesRule {
+            name: owned(self.name),
+            prefix: self.prefix,
+            frames: self.frames.into_iter().map(Keyframe::into_owned).collect(),
+        }
+    }
+}
+
+/// One block of a `@keyframes` rule, e.g. `from, 50% { opacity: 0 }`.
+#[derive(Debug, Clone)]
+pub struct Keyframe<'a> {
+    pub offsets: Vec<KeyframeOffset>,
+    pub declarations: Vec<Declaration<'a>>,  // without the timing function and `!important` declarations
+    pub timing_function: Option<Value<'a>>,  // `animation-timing-function` until the next keyframe
+}
+
+impl<'a> Keyframe<'a> {
+    fn from_rule(rule: &Rule<'a>) -> Self {
+        let offsets = rule.selectors.iter().filter_map(|selector| KeyframeOffset::parse(&selector.to_string())).collect();
+
+        let mut declarations = Vec::new();
+        let mut timing_function = None;
+
+        // `!important` is ignored inside keyframes.
+        for declaration in rule.declarations.iter().filter(|declaration| !declaration.is_important) {
+            if declaration.property.eq_ignore_ascii_case("animation-timing-function") {
+                timing_function = Some(declaration.value.clone());
+            } else {
+                declarations.push(declaration.clone());
+            }
+        }
+
+        Keyframe { offsets, declarations, timing_function }
+    }
+
+    pub fn into_owned(self) -> Keyframe<'static> {
+        Keyframe {
+            offsets: self.offsets,
+            declarations: self.declarations.into_iter().map(Declaration::into_owned).collect(),
+            timing_function: self.timing_function.map(Value::into_owned),
+        }
+    }
+}
+
+/// A keyframe selector: `from` is 0, `to` is 1 and `40%` is 0.4, optionally
+/// within a named timeline range such as `cover 40%`. Written as a
+/// percentage, so `from` becomes `0%` as in CSSOM; the selectors of the rule
+/// keep the keyword.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct KeyframeOffset {
+    pub range: Option<TimelineRange>,
+    pub offset: f64,
+}
+
+impl KeyframeOffset {
+    /// Reads a keyframe selector such as `from`, `50%` or `cover 50%`; `None`
+    /// for anything else, including percentages outside 0% to 100%.
+    pub fn parse(text: &str) -> Option<Self> {
+        let text = text.trim();
+        let (range, percentage) = match text.split_once(char::is_whitespace) {
+            Some((name, percentage)) => (Some(TimelineRange::from_name(name)?), percentage.trim()),
+            None => (None, text),
+        };
+
+        let offset = match percentage.to_ascii_lowercase().as_str() {
+            "from" if range.is_none() => 0.0,
+            "to" if range.is_none() => 1.0,
+            percentage => {
+                let number: f64 = percentage.strip_suffix('%')?.parse().ok()?;
+                if !(0.0..=100.0).contains(&number) {
+                    return None;
+                }
+                number / 100.0
+            },
+        };
+
+        Some(KeyframeOffset { range, offset })
+    }
+}
+
+impl fmt::Display for KeyframeOffset {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(range) = self.range {
+            write!(f, "{} ", range)?;
+        }
+        // Rounded so that `7%`, stored as 0.07, doesn't come back as `7.000000000000001%`.
+        write!(f, "{}%", (self.offset * 100.0 * 1e9).round() / 1e9)
+    }
+}
+
+/// Named ranges of a view progress timeline.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TimelineRange {
+    Normal,
+    Cover,         // from entering the scrollport to leaving it completely
+    Contain,       // while fully inside the scrollport, or covering it
+    Entry,         // while entering the scrollport
+    Exit,          // while leaving the scrollport
+    EntryCrossing, // while crossing the starting edge
+    ExitCrossing,  // while crossing the ending edge
+}
+
+impl TimelineRange {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "normal" => Some(TimelineRange::Normal),
+            "cover" => Some(TimelineRange::Cover),
+            "contain" => Some(TimelineRange::Contain),
+            "entry" => Some(TimelineRange::Entry),
+            "exit" => Some(TimelineRange::Exit),
+            "entry-crossing" => Some(TimelineRange::EntryCrossing),
+            "exit-crossing" => Some(TimelineRange::ExitCrossing),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for TimelineRange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TimelineRange::Normal => write!(f, "normal"),
+            TimelineRange::Cover => write!(f, "cover"),
+            TimelineRange::Contain => write!(f, "contain"),
+            TimelineRange::Entry => write!(f, "entry"),
+            TimelineRange::Exit => write!(f, "exit"),
+            TimelineRange::EntryCrossing => write!(f, "entry-crossing"),
+            TimelineRange::ExitCrossing => write!(f, "exit-crossing"),
+        }
+    }
+}
+
+impl<'a> Stylesheet<'a> {
+    /// All `@keyframes` rules named `name`, prefixed or not, in source order
+    /// and including those inside conditional rules.
+    pub fn keyframes(&self, name: &str) -> Vec<KeyframesRule<'a>> {
+        self.all_keyframes().filter(|rule| rule.name == name).collect()
+    }
+
+    /// All `@keyframes` rules grouped by animation name, so that a name's
+    /// unprefixed and vendor-prefixed variants end up together.
+    pub fn keyframes_by_name(&self) -> HashMap<String, Vec<KeyframesRule<'a>>> {
+        let mut by_name: HashMap<String, Vec<KeyframesRule<'a>>> = HashMap::new();
+        for rule in self.all_keyframes() {
+            by_name.entry(rule.name.to_string()).or_default().push(rule);
+        }
+        by_name
+    }
+
+    fn all_keyframes(&self) -> impl Iterator<Item = KeyframesRule<'a>> + '_ {
+        self.at_rules_of_type(AtRuleType::Keyframes).filter_map(KeyframesRule::from_at_rule)
+    }
+}
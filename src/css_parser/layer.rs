@@ -0,0 +1,89 @@
+use std::borrow::Cow;
+
+use crate::css_parser::ast::{AtRulePrelude, CssRule, LayerName, Stylesheet};
+
+/// A declared cascade layer and its sublayers, in declaration order.
+#[derive(Default)]
+struct LayerTree<'a> {
+    name: Cow<'a, str>, // empty for anonymous layers
+    sublayers: Vec<LayerTree<'a>>,
+}
+
+impl<'a> LayerTree<'a> {
+    /// The sublayer at `path`, declaring every missing layer on the way.
+    fn declare(&mut self, path: &[Cow<'a, str>]) -> &mut LayerTree<'a> {
+        let Some((first, rest)) = path.split_first() else { return self };
+
+        let index = match self.sublayers.iter().position(|layer| !layer.name.is_empty() && layer.name == *first) {
+            Some(index) => index,
+            None => {
+                self.sublayers.push(LayerTree { name: first.clone(), sublayers: Vec::new() });
+                self.sublayers.len() - 1
+            },
+        };
+
+        self.sublayers[index].declare(rest)
+    }
+
+    /// A new anonymous sublayer; every `@layer { ... }` creates its own.
+    fn declare_anonymous(&mut self) -> &mut LayerTree<'a> {
+        self.sublayers.push(LayerTree::default());
+        self.sublayers.last_mut().expect("just pushed")
+    }
+
+    fn collect(&mut self, rules: &[CssRule<'a>]) {
+        for rule in rules {
+            let Some(at_rule) = rule.as_at_rule() else {
+                self.collect(rule.children());
+                continue;
+            };
+
+            match &at_rule.prelude {
+                AtRulePrelude::LayerStatement(names) => {
+                    for name in names {
+                        self.declare(&name.parts);
+                    }
+                },
+                AtRulePrelude::LayerBlock(name) if name.is_anonymous() => self.declare_anonymous().collect(&at_rule.rules),
+                AtRulePrelude::LayerBlock(name) => self.declare(&name.parts).collect(&at_rule.rules),
+                AtRulePrelude::Import(import) => match &import.layer {
+                    Some(layer) if layer.is_anonymous() => {
+                        self.declare_anonymous();
+                    },
+                    Some(layer) => {
+                        self.declare(&layer.parts);
+                    },
+                    None => {},
+                },
+                _ => self.collect(&at_rule.rules),
+            }
+        }
+    }
+
+    /// Appends the full names of the sublayers, each after its own sublayers.
+    fn flatten(&self, parent: &[Cow<'a, str>], order: &mut Vec<LayerName<'a>>) {
+        for layer in &self.sublayers {
+            let mut parts = parent.to_vec();
+            parts.push(layer.name.clone());
+
+            layer.flatten(&parts, order);
+            order.push(LayerName { parts });
+        }
+    }
+}
+
+impl<'a> Stylesheet<'a> {
+    /// Full names of all cascade layers from lowest to highest priority,
+    /// counting `@layer` statements and blocks at any nesting level and
+    /// `@import ... layer(...)`. A layer comes after its sublayers, as its own
+    /// styles win over theirs; styles outside any layer win over all of them.
+    /// Layers inside conditional rules count whether or not the condition holds.
+    pub fn layer_order(&self) -> Vec<LayerName<'a>> {
+        let mut root = LayerTree::default();
+        root.collect(&self.rules);
+
+        let mut order = Vec::new();
+        root.flatten(&[], &mut order);
+        order
+    }
+}
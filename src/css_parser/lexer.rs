@@ -1,7 +1,10 @@
+use std::borrow::Cow;
+use std::collections::VecDeque;
 use std::fmt;
 
 #[derive(Debug, Clone, PartialEq)]
-pub enum TokenType {
+#[allow(clippy::upper_case_acronyms)]
+pub enum TokenType<'a> {
     // Punctuation
     OpenBrace,       // {
     CloseBrace,      // }
@@ -29,6 +32,7 @@ pub enum TokenType {
     // CSS Selectors
     GreaterThan,     // > (child selector)
     Tilde,           // ~ (general sibling)
+    Ampersand,       // & (nesting selector)
     // Plus is already defined above (adjacent sibling)
 
     // Comparison operators
@@ -41,19 +45,62 @@ pub enum TokenType {
     AtSymbol,        // @
 
     // Literals
-    Identifier(String),      // property names, element names, etc.
-    Number(f64),             // numeric values (without unit)
-    String(String),          // quoted strings
+    Identifier(Cow<'a, str>),   // property names, element names, etc.
+    Number(f64),                // numeric values (without unit)
+    String(Cow<'a, str>),       // quoted strings
 
     // Special CSS values
-    Unit(String),            // px, em, %, etc.
-    UnicodeRange(String),    // U+XXXX
+    Unit(Cow<'a, str>),         // px, em, %, etc.
+    UnicodeRange(Cow<'a, str>), // U+XXXX
+    Comment(Cow<'a, str>),      // text between /* and */, only with `preserve_comments`
 
     // End of file
     EOF,
 }
 
-impl fmt::Display for TokenType {
+impl TokenType<'_> {
+    pub fn into_owned(self) -> TokenType<'static> {
+        match self {
+            TokenType::Identifier(val) => TokenType::Identifier(Cow::Owned(val.into_owned())),
+            TokenType::String(val) => TokenType::String(Cow::Owned(val.into_owned())),
+            TokenType::Unit(val) => TokenType::Unit(Cow::Owned(val.into_owned())),
+            TokenType::UnicodeRange(val) => TokenType::UnicodeRange(Cow::Owned(val.into_owned())),
+            TokenType::Comment(val) => TokenType::Comment(Cow::Owned(val.into_owned())),
+            TokenType::Number(val) => TokenType::Number(val),
+            TokenType::OpenBrace => TokenType::OpenBrace,
+            TokenType::CloseBrace => TokenType::CloseBrace,
+            TokenType::OpenParen => TokenType::OpenParen,
+            TokenType::CloseParen => TokenType::CloseParen,
+            TokenType::OpenBracket => TokenType::OpenBracket,
+            TokenType::CloseBracket => TokenType::CloseBracket,
+            TokenType::Semicolon => TokenType::Semicolon,
+            TokenType::Colon => TokenType::Colon,
+            TokenType::DoubleColon => TokenType::DoubleColon,
+            TokenType::Comma => TokenType::Comma,
+            TokenType::Hash => TokenType::Hash,
+            TokenType::Dot => TokenType::Dot,
+            TokenType::Asterisk => TokenType::Asterisk,
+            TokenType::ExclamationMark => TokenType::ExclamationMark,
+            TokenType::Backslash => TokenType::Backslash,
+            TokenType::Whitespace => TokenType::Whitespace,
+            TokenType::LessThan => TokenType::LessThan,
+            TokenType::Plus => TokenType::Plus,
+            TokenType::Minus => TokenType::Minus,
+            TokenType::Slash => TokenType::Slash,
+            TokenType::GreaterThan => TokenType::GreaterThan,
+            TokenType::Tilde => TokenType::Tilde,
+            TokenType::Ampersand => TokenType::Ampersand,
+            TokenType::Equals => TokenType::Equals,
+            TokenType::Caret => TokenType::Caret,
+            TokenType::Dollar => TokenType::Dollar,
+            TokenType::Pipe => TokenType::Pipe,
+            TokenType::AtSymbol => TokenType::AtSymbol,
+            TokenType::EOF => TokenType::EOF,
+        }
+    }
+}
+
+impl fmt::Display for TokenType<'_> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             TokenType::OpenBrace => write!(f, "{{"),
@@ -78,6 +125,7 @@ impl fmt::Display for TokenType {
             TokenType::GreaterThan => write!(f, ">"),
             TokenType::LessThan => write!(f, "<"),
             TokenType::Tilde => write!(f, "~"),
+            TokenType::Ampersand => write!(f, "&"),
             TokenType::Equals => write!(f, "="),
             TokenType::Caret => write!(f, "^"),
             TokenType::Dollar => write!(f, "$"),
@@ -88,23 +136,26 @@ impl fmt::Display for TokenType {
             TokenType::String(val) => write!(f, "String(\"{}\")", val),
             TokenType::Unit(val) => write!(f, "Unit({})", val),
             TokenType::UnicodeRange(val) => write!(f, "UnicodeRange({})", val),
+            TokenType::Comment(val) => write!(f, "Comment({})", val),
             TokenType::EOF => write!(f, "EOF"),
         }
     }
 }
 
 #[derive(Debug, Clone)]
-pub struct Token {
-    pub token_type: TokenType,
+pub struct Token<'a> {
+    pub token_type: TokenType<'a>,
+    pub position: usize, // byte offset of the first character
     pub line: usize,
     pub column: usize,
-    pub length: usize,
+    pub length: usize,   // in bytes
 }
 
-impl Token {
-    pub fn new(token_type: TokenType, line: usize, column: usize, length: usize) -> Self {
+impl<'a> Token<'a> {
+    pub fn new(token_type: TokenType<'a>, position: usize, line: usize, column: usize, length: usize) -> Self {
         Token {
             token_type,
+            position,
             line,
             column,
             length,
@@ -112,25 +163,63 @@ impl Token {
     }
 }
 
-#[derive(Debug, Clone, PartialEq)]
-pub enum LexerMode {
-    Normal,     // Skip whitespace (default)
-    Selector,   // Return whitespace tokens (for parsing selectors)
+/// Syntax accepted on top of standard CSS.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Dialect {
+    #[default]
+    Standard, // CSS Syntax Level 3 only
+    Lenient,  // identifiers may contain `# @ $ % & * ~ .`, legacy browser hacks are accepted
+    ScssLike, // Lenient plus `//` line comments
+}
+
+impl Dialect {
+    /// Whether `//` starts a comment running to the end of the line.
+    pub fn has_line_comments(self) -> bool {
+        self == Dialect::ScssLike
+    }
+
+    /// Whether identifiers may contain the preprocessor characters `# @ $ % & * ~ .`.
+    pub fn has_extended_identifiers(self) -> bool {
+        self != Dialect::Standard
+    }
+
+    /// Whether legacy browser hacks such as `*zoom: 1` are accepted.
+    pub fn allows_hacks(self) -> bool {
+        self != Dialect::Standard
+    }
+}
+
+/// Position in the token stream to return to with `Lexer::restore`. Not
+/// `Copy`, so a checkpoint can only be restored or committed once.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Checkpoint {
+    position: usize,
+    depth: usize, // number of active checkpoints, including this one
 }
 
-pub struct Lexer {
-    input: String,
+/// Token stream over the input. Whitespace is always reported as
+/// `TokenType::Whitespace`, comments are dropped unless `preserve_comments`
+/// is enabled.
+///
+/// Tokens are lexed on demand into a ring buffer that serves as lookahead
+/// for `peek_nth` and keeps consumed tokens while a checkpoint is active.
+pub struct Lexer<'a> {
+    input: &'a str,
     position: usize,
     read_position: usize,
     ch: Option<char>,
     line: usize,
     column: usize,
-    next_token_cache: Vec<Token>,
-    pub mode: LexerMode,
+    buffer: VecDeque<Token<'a>>,
+    cursor: usize,       // index of the next token in `buffer`
+    buffer_start: usize, // number of tokens dropped from the front of `buffer`
+    checkpoints: Vec<usize>, // positions of the active checkpoints, innermost last
+    preserve_comments: bool,
+    dialect: Dialect,
 }
 
-impl Lexer {
-    pub fn new(input: String) -> Self {
+impl<'a> Lexer<'a> {
+    pub fn new(input: &'a str) -> Self {
         let mut lexer = Lexer {
             input,
             position: 0,
@@ -138,16 +227,119 @@ impl Lexer {
             ch: None,
             line: 1,
             column: 0,
-            next_token_cache: Vec::new(),
-            mode: LexerMode::Normal,
+            buffer: VecDeque::new(),
+            cursor: 0,
+            buffer_start: 0,
+            checkpoints: Vec::new(),
+            preserve_comments: false,
+            dialect: Dialect::Standard,
         };
         lexer.read_char();
         lexer
     }
 
+    /// Reports `/* ... */` comments as `TokenType::Comment` tokens instead of
+    /// dropping them.
+    pub fn preserve_comments(mut self, preserve: bool) -> Self {
+        self.preserve_comments = preserve;
+        self
+    }
+
+    pub fn with_dialect(mut self, dialect: Dialect) -> Self {
+        self.dialect = dialect;
+        self
+    }
+
+    pub fn dialect(&self) -> Dialect {
+        self.dialect
+    }
+
+    pub fn input(&self) -> &'a str {
+        self.input
+    }
+
+    /// Consumes the next token. Once the input is exhausted EOF is returned
+    /// indefinitely.
+    pub fn next_token(&mut self) -> Token<'a> {
+        self.fill(0);
+
+        if self.buffer[self.cursor].token_type == TokenType::EOF {
+            return self.buffer[self.cursor].clone();
+        }
+
+        if !self.checkpoints.is_empty() {
+            self.cursor += 1;
+            return self.buffer[self.cursor - 1].clone();
+        }
+
+        self.buffer_start += 1;
+        self.buffer.pop_front().expect("buffer was filled")
+    }
+
+    /// Returns the token `n` positions ahead without consuming anything;
+    /// `peek_nth(0)` is the token `next_token` returns next.
+    pub fn peek_nth(&mut self, n: usize) -> &Token<'a> {
+        self.fill(n);
+        self.buffered(n).expect("buffer was filled")
+    }
+
+    /// Like `peek_nth`, but only looks at tokens that have already been lexed.
+    pub fn buffered(&self, n: usize) -> Option<&Token<'a>> {
+        match self.buffer.get(self.cursor + n) {
+            Some(token) => Some(token),
+            None => self.buffer.back().filter(|token| token.token_type == TokenType::EOF),
+        }
+    }
+
+    /// Marks the current position. Every checkpoint must be passed to either
+    /// `restore` or `commit`, innermost first.
+    pub fn checkpoint(&mut self) -> Checkpoint {
+        let position = self.buffer_start + self.cursor;
+        self.checkpoints.push(position);
+        Checkpoint { position, depth: self.checkpoints.len() }
+    }
+
+    /// Rewinds to `checkpoint`; the tokens consumed since are returned again.
+    pub fn restore(&mut self, checkpoint: Checkpoint) {
+        self.cursor = checkpoint.position - self.buffer_start;
+        self.commit(checkpoint);
+    }
+
+    /// Keeps everything consumed since `checkpoint`.
+    pub fn commit(&mut self, checkpoint: Checkpoint) {
+        debug_assert_eq!(
+            self.checkpoints.last().map(|&position| (position, self.checkpoints.len())),
+            Some((checkpoint.position, checkpoint.depth)),
+            "checkpoints must be released innermost first"
+        );
+        // Releasing an outer checkpoint releases the ones inside it as well.
+        self.checkpoints.truncate(checkpoint.depth.saturating_sub(1));
+
+        if self.checkpoints.is_empty() {
+            self.buffer.drain(..self.cursor);
+            self.buffer_start += self.cursor;
+            self.cursor = 0;
+        }
+    }
+
+    fn fill(&mut self, n: usize) {
+        while self.buffer.len() <= self.cursor + n {
+            if self.buffer.back().is_some_and(|token| token.token_type == TokenType::EOF) {
+                return;
+            }
+
+            // Tokens that are split from the one being lexed (units, url
+            // arguments, hex digits) are pushed directly and follow it.
+            let index = self.buffer.len();
+            let token = self.lex_token();
+            self.buffer.insert(index, token);
+        }
+    }
+
     fn read_char(&mut self) {
         if self.read_position >= self.input.len() {
             self.ch = None;
+            self.position = self.input.len();
         } else {
             let mut chars = self.input[self.read_position..].chars();
             let current_char = chars.next();
@@ -180,30 +372,22 @@ impl Lexer {
         }
     }
 
-    pub fn next_token(&mut self) -> Token {
-        if !self.next_token_cache.is_empty() {
-            return self.next_token_cache.remove(0);
-        }
-
-        if matches!(self.mode, LexerMode::Selector) {
-            if self.ch.is_some() && self.ch.unwrap().is_whitespace() {
-                let start_line = self.line;
-                let start_column = self.column;
-
-                let start_pos = self.position;
-                while self.ch.is_some() && self.ch.unwrap().is_whitespace() {
-                    self.read_char();
-                }
+    fn lex_token(&mut self) -> Token<'a> {
+        if self.ch.is_some() && self.ch.unwrap().is_whitespace() {
+            let start_line = self.line;
+            let start_column = self.column;
 
-                let length = self.position - start_pos;
-                return Token::new(TokenType::Whitespace, start_line, start_column, length);
+            let start_pos = self.position;
+            while self.ch.is_some() && self.ch.unwrap().is_whitespace() {
+                self.read_char();
             }
-        } else {
-            self.skip_whitespace();
+
+            let length = self.position - start_pos;
+            return Token::new(TokenType::Whitespace, start_pos, start_line, start_column, length);
         }
 
         if self.ch.is_none() {
-            return Token::new(TokenType::EOF, self.line, self.column, 0);
+            return Token::new(TokenType::EOF, self.position, self.line, self.column, 0);
         }
 
         let ch = self.ch.unwrap();
@@ -222,113 +406,120 @@ impl Lexer {
             }
 
             if peek_index < self.input.len() &&
-                self.input[peek_index..].chars().next().unwrap() == '(' {
+                self.input[peek_index..].starts_with('(') {
                 return self.handle_url_function();
             }
         }
 
         match ch {
             '{' => {
-                let token = Token::new(TokenType::OpenBrace, self.line, self.column, 1);
+                let token = Token::new(TokenType::OpenBrace, self.position, self.line, self.column, 1);
                 self.read_char();
                 token
             },
             '}' => {
-                let token = Token::new(TokenType::CloseBrace, self.line, self.column, 1);
+                let token = Token::new(TokenType::CloseBrace, self.position, self.line, self.column, 1);
                 self.read_char();
                 token
             },
             '(' => {
-                let token = Token::new(TokenType::OpenParen, self.line, self.column, 1);
+                let token = Token::new(TokenType::OpenParen, self.position, self.line, self.column, 1);
                 self.read_char();
                 token
             },
             ')' => {
-                let token = Token::new(TokenType::CloseParen, self.line, self.column, 1);
+                let token = Token::new(TokenType::CloseParen, self.position, self.line, self.column, 1);
                 self.read_char();
                 token
             },
             '[' => {
-                let token = Token::new(TokenType::OpenBracket, self.line, self.column, 1);
+                let token = Token::new(TokenType::OpenBracket, self.position, self.line, self.column, 1);
                 self.read_char();
                 token
             },
             ']' => {
-                let token = Token::new(TokenType::CloseBracket, self.line, self.column, 1);
+                let token = Token::new(TokenType::CloseBracket, self.position, self.line, self.column, 1);
                 self.read_char();
                 token
             },
             ';' => {
-                let token = Token::new(TokenType::Semicolon, self.line, self.column, 1);
+                let token = Token::new(TokenType::Semicolon, self.position, self.line, self.column, 1);
                 self.read_char();
                 token
             },
             ':' => {
                 if self.peek_char() == Some(':') {
-                    let token = Token::new(TokenType::DoubleColon, self.line, self.column, 2);
+                    let token = Token::new(TokenType::DoubleColon, self.position, self.line, self.column, 2);
                     self.read_char();
                     self.read_char();
                     token
                 } else {
-                    let token = Token::new(TokenType::Colon, self.line, self.column, 1);
+                    let token = Token::new(TokenType::Colon, self.position, self.line, self.column, 1);
                     self.read_char();
                     token
                 }
             },
             ',' => {
-                let token = Token::new(TokenType::Comma, self.line, self.column, 1);
+                let token = Token::new(TokenType::Comma, self.position, self.line, self.column, 1);
                 self.read_char();
                 token
             },
             '>' => {
-                let token = Token::new(TokenType::GreaterThan, self.line, self.column, 1);
+                let token = Token::new(TokenType::GreaterThan, self.position, self.line, self.column, 1);
                 self.read_char();
                 token
             },
             '<' => {
-                let token = Token::new(TokenType::LessThan, self.line, self.column, 1);
+                let token = Token::new(TokenType::LessThan, self.position, self.line, self.column, 1);
                 self.read_char();
                 token
             },
             '~' => {
-                let token = Token::new(TokenType::Tilde, self.line, self.column, 1);
+                let token = Token::new(TokenType::Tilde, self.position, self.line, self.column, 1);
+                self.read_char();
+                token
+            },
+            '&' => {
+                let token = Token::new(TokenType::Ampersand, self.position, self.line, self.column, 1);
                 self.read_char();
                 token
             },
             '=' => {
-                let token = Token::new(TokenType::Equals, self.line, self.column, 1);
+                let token = Token::new(TokenType::Equals, self.position, self.line, self.column, 1);
                 self.read_char();
                 token
             },
             '^' => {
-                let token = Token::new(TokenType::Caret, self.line, self.column, 1);
+                let token = Token::new(TokenType::Caret, self.position, self.line, self.column, 1);
                 self.read_char();
                 token
             },
             '$' => {
-                let token = Token::new(TokenType::Dollar, self.line, self.column, 1);
+                let token = Token::new(TokenType::Dollar, self.position, self.line, self.column, 1);
                 self.read_char();
                 token
             },
             '|' => {
-                let token = Token::new(TokenType::Pipe, self.line, self.column, 1);
+                let token = Token::new(TokenType::Pipe, self.position, self.line, self.column, 1);
                 self.read_char();
                 token
             },
             '@' => {
-                let token = Token::new(TokenType::AtSymbol, self.line, self.column, 1);
+                let token = Token::new(TokenType::AtSymbol, self.position, self.line, self.column, 1);
                 self.read_char();
                 token
             },
             '.' => {
                 if let Some(next_ch) = self.peek_char() {
-                    if next_ch.is_digit(10) {
+                    if next_ch.is_ascii_digit() {
                         let start_col = self.column;
+                        let start_line = self.line;
+                        let start_pos = self.position;
                         self.read_char();
 
                         let mut number_str = String::from("0.");
 
-                        while self.ch.is_some() && self.ch.unwrap().is_digit(10) {
+                        while self.ch.is_some() && self.ch.unwrap().is_ascii_digit() {
                             number_str.push(self.ch.unwrap());
                             self.read_char();
                         }
@@ -338,99 +529,113 @@ impl Lexer {
 
                         if self.ch.is_some() && (self.ch.unwrap().is_alphabetic() || self.ch.unwrap() == '%') {
                             let unit_start_col = self.column;
+                            let unit_start_line = self.line;
+                            let unit_start_pos = self.position;
                             let unit = self.read_unit();
 
-                            self.next_token_cache.push(Token::new(TokenType::Unit(unit.clone()),
-                                                                  self.line,
+                            self.buffer.push_back(Token::new(TokenType::Unit(unit),
+                                                                  unit_start_pos,
+                                                                  unit_start_line,
                                                                   unit_start_col,
-                                                                  unit.len()));
+                                                                  self.position - unit_start_pos));
 
-                            return Token::new(TokenType::Number(number), self.line, start_col, length);
+                            return Token::new(TokenType::Number(number), start_pos, start_line, start_col, length);
                         }
 
-                        return Token::new(TokenType::Number(number), self.line, start_col, length);
+                        return Token::new(TokenType::Number(number), start_pos, start_line, start_col, length);
                     }
                 }
 
-                let token = Token::new(TokenType::Dot, self.line, self.column, 1);
+                let token = Token::new(TokenType::Dot, self.position, self.line, self.column, 1);
                 self.read_char();
                 token
             },
             '*' => {
-                let token = Token::new(TokenType::Asterisk, self.line, self.column, 1);
+                let token = Token::new(TokenType::Asterisk, self.position, self.line, self.column, 1);
                 self.read_char();
                 token
             },
             '!' => {
-                let token = Token::new(TokenType::ExclamationMark, self.line, self.column, 1);
+                let token = Token::new(TokenType::ExclamationMark, self.position, self.line, self.column, 1);
                 self.read_char();
                 token
             },
             '+' => {
                 let peek = self.peek_char();
-                if peek.is_some() && peek.unwrap().is_digit(10) {
+                if peek.is_some() && peek.unwrap().is_ascii_digit() {
                     let start_col = self.column;
+                    let start_line = self.line;
+                    let start_pos = self.position;
                     let (number, length) = self.read_number();
 
                     if self.ch.is_some() && (self.ch.unwrap().is_alphabetic() || self.ch.unwrap() == '%') {
                         let unit_start_col = self.column;
+                        let unit_start_line = self.line;
+                        let unit_start_pos = self.position;
                         let unit = self.read_unit();
 
-                        self.next_token_cache.push(Token::new(TokenType::Unit(unit.clone()), self.line, unit_start_col, unit.len()));
+                        self.buffer.push_back(Token::new(TokenType::Unit(unit), unit_start_pos, unit_start_line, unit_start_col, self.position - unit_start_pos));
 
-                        return Token::new(TokenType::Number(number), self.line, start_col, length);
+                        return Token::new(TokenType::Number(number), start_pos, start_line, start_col, length);
                     }
 
-                    Token::new(TokenType::Number(number), self.line, start_col, length)
+                    Token::new(TokenType::Number(number), start_pos, start_line, start_col, length)
                 } else {
-                    let token = Token::new(TokenType::Plus, self.line, self.column, 1);
+                    let token = Token::new(TokenType::Plus, self.position, self.line, self.column, 1);
                     self.read_char();
                     token
                 }
             },
             '/' => {
                 if self.peek_char() == Some('*') {
+                    if self.preserve_comments {
+                        return self.read_block_comment();
+                    }
                     self.skip_block_comment();
-                    self.next_token()
-                } else if self.peek_char() == Some('/') {
+                    self.lex_token()
+                } else if self.peek_char() == Some('/') && self.dialect.has_line_comments() {
                     self.skip_line_comment();
-                    self.next_token()
+                    self.lex_token()
                 } else {
-                    let token = Token::new(TokenType::Slash, self.line, self.column, 1);
+                    let token = Token::new(TokenType::Slash, self.position, self.line, self.column, 1);
                     self.read_char();
                     token
                 }
             },
             '\\' => {
                 let start_col = self.column;
+                let start_line = self.line;
+                let start_pos = self.position;
 
                 let identifier = self.read_identifier();
 
                 if !identifier.is_empty() {
-                    return Token::new(TokenType::Identifier(identifier.clone()),
-                                      self.line, start_col, identifier.len());
+                    return Token::new(TokenType::Identifier(identifier),
+                                      start_pos, start_line, start_col, self.position - start_pos);
                 }
 
-                Token::new(TokenType::Backslash, self.line, start_col, 1)
+                Token::new(TokenType::Backslash, start_pos, start_line, start_col, 1)
             }
             '#' => {
-                let hash_token = Token::new(TokenType::Hash, self.line, self.column, 1);
+                let hash_token = Token::new(TokenType::Hash, self.position, self.line, self.column, 1);
                 self.read_char();
 
                 if self.ch.is_some() && self.is_hex_digit(self.ch.unwrap()) {
                     let hex_start_col = self.column;
+                    let hex_start_line = self.line;
                     let hex_start_position = self.position;
 
                     while self.ch.is_some() && self.is_hex_digit(self.ch.unwrap()) {
                         self.read_char();
                     }
 
-                    let hex_value = self.input[hex_start_position..self.position].to_string();
+                    let hex_value = Cow::Borrowed(&self.input[hex_start_position..self.position]);
                     let hex_length = hex_value.len();
 
-                    self.next_token_cache.push(Token::new(
+                    self.buffer.push_back(Token::new(
                         TokenType::Identifier(hex_value),
-                        self.line,
+                        hex_start_position,
+                        hex_start_line,
                         hex_start_col,
                         hex_length
                     ));
@@ -441,31 +646,37 @@ impl Lexer {
             '"' | '\'' => {
                 let quote_char = ch;
                 let start_col = self.column;
+                let start_line = self.line;
+                let start_pos = self.position;
                 self.read_char();
 
                 let string = self.read_string(quote_char);
                 let length = string.len() + 2;
                 self.read_char();
-                Token::new(TokenType::String(string), self.line, start_col, length)
+                Token::new(TokenType::String(string), start_pos, start_line, start_col, length)
             },
             'U' => {
                 if self.peek_char() == Some('+') {
                     self.read_unicode_range()
                 } else {
                     let start_col = self.column;
+                    let start_line = self.line;
+                    let start_pos = self.position;
                     let identifier = self.read_identifier();
-                    Token::new(TokenType::Identifier(identifier.clone()),
-                                      self.line, start_col, identifier.len())
+                    Token::new(TokenType::Identifier(identifier),
+                                      start_pos, start_line, start_col, self.position - start_pos)
                 }
             },
             '0'..='9' | '-' => {
                 let start_col = self.column;
+                let start_line = self.line;
+                let start_pos = self.position;
 
                 if ch == '-' {
                     let peek = self.peek_char();
                     if peek.is_none() || !self.is_digit_or_decimal(peek.unwrap()) {
                         let identifier = self.read_identifier();
-                        return Token::new(TokenType::Identifier(identifier.clone()), self.line, start_col, identifier.len());
+                        return Token::new(TokenType::Identifier(identifier), start_pos, start_line, start_col, self.position - start_pos);
                     }
                 }
 
@@ -473,28 +684,33 @@ impl Lexer {
 
                 if self.ch.is_some() && (self.ch.unwrap().is_alphabetic() || self.ch.unwrap() == '%') {
                     let unit_start_col = self.column;
+                    let unit_start_line = self.line;
+                    let unit_start_pos = self.position;
                     let unit = self.read_unit();
 
-                    self.next_token_cache.push(Token::new(TokenType::Unit(unit.clone()), self.line, unit_start_col, unit.len()));
+                    self.buffer.push_back(Token::new(TokenType::Unit(unit), unit_start_pos, unit_start_line, unit_start_col, self.position - unit_start_pos));
 
-                    return Token::new(TokenType::Number(number), self.line, start_col, length);
+                    return Token::new(TokenType::Number(number), start_pos, start_line, start_col, length);
                 }
 
-                Token::new(TokenType::Number(number), self.line, start_col, length)
+                Token::new(TokenType::Number(number), start_pos, start_line, start_col, length)
             },
             c if self.is_identifier_start(c) => {
                 let start_col = self.column;
+                let start_line = self.line;
+                let start_pos = self.position;
                 let identifier = self.read_identifier();
-                Token::new(TokenType::Identifier(identifier.clone()), self.line, start_col, identifier.len())
+                Token::new(TokenType::Identifier(identifier), start_pos, start_line, start_col, self.position - start_pos)
             },
             _ => {
                 self.read_char();
-                self.next_token()
+                self.lex_token()
             }
         }
     }
 
-    fn handle_url_function(&mut self) -> Token {
+    fn handle_url_function(&mut self) -> Token<'a> {
+        let start_position = self.position;
         let start_line = self.line;
         let start_column = self.column;
 
@@ -505,9 +721,10 @@ impl Lexer {
 
         if self.ch != Some('(') {
             return Token::new(TokenType::Identifier(url_identifier.clone()),
-                              start_line, start_column, url_identifier.len());
+                              start_position, start_line, start_column, url_identifier.len());
         }
 
+        let open_paren_position = self.position;
         self.read_char();
 
         self.skip_whitespace();
@@ -539,40 +756,45 @@ impl Lexer {
             self.read_char();
         }
 
-        let url_content = self.input[url_content_start..self.position].to_string();
+        let url_content = &self.input[url_content_start..self.position];
 
+        let close_paren_position = self.position;
         if self.ch == Some(')') {
             self.read_char();
         }
 
-        self.next_token_cache.push(Token::new(
+        self.buffer.push_back(Token::new(
             TokenType::OpenParen,
+            open_paren_position,
             start_line, start_column + url_identifier.len(),
             1
         ));
 
         if !url_content.is_empty() {
-            self.next_token_cache.push(Token::new(
-                TokenType::String(url_content.clone()),
+            self.buffer.push_back(Token::new(
+                TokenType::String(Cow::Borrowed(url_content)),
+                url_content_start,
                 start_line, start_column + url_identifier.len() + 1,
                 url_content.len()
             ));
         }
 
-        self.next_token_cache.push(Token::new(
+        self.buffer.push_back(Token::new(
             TokenType::CloseParen,
+            close_paren_position,
             self.line, self.column - 1,
             1
         ));
 
         Token::new(
             TokenType::Identifier(url_identifier.clone()),
+            start_position,
             start_line, start_column,
             url_identifier.len()
         )
     }
 
-    fn read_unicode_range(&mut self) -> Token {
+    fn read_unicode_range(&mut self) -> Token<'a> {
         let start_position = self.position;
         let start_column = self.column;
         let start_line = self.line;
@@ -580,8 +802,8 @@ impl Lexer {
         self.read_char();
 
         if self.ch != Some('+') {
-            return Token::new(TokenType::Identifier("U".to_string()),
-                              start_line, start_column, 1);
+            return Token::new(TokenType::Identifier(Cow::Borrowed("U")),
+                              start_position, start_line, start_column, 1);
         }
 
         self.read_char();
@@ -589,16 +811,16 @@ impl Lexer {
         while self.ch.is_some() {
             let ch = self.ch.unwrap();
 
-            if ch.is_digit(16) || ch == '-' || ch == '?' {
+            if ch.is_ascii_hexdigit() || ch == '-' || ch == '?' {
                 self.read_char();
             } else {
                 break;
             }
         }
 
-        let unicode_range = self.input[start_position..self.position].to_string();
-        Token::new(TokenType::UnicodeRange(unicode_range.clone()),
-                   start_line, start_column, unicode_range.len())
+        let unicode_range = &self.input[start_position..self.position];
+        Token::new(TokenType::UnicodeRange(Cow::Borrowed(unicode_range)),
+                   start_position, start_line, start_column, unicode_range.len())
     }
 
     fn skip_whitespace(&mut self) {
@@ -608,7 +830,7 @@ impl Lexer {
                 self.read_char();
             } else if ch == '/' && self.peek_char() == Some('*') {
                 self.skip_block_comment();
-            } else if ch == '/' && self.peek_char() == Some('/') {
+            } else if ch == '/' && self.peek_char() == Some('/') && self.dialect.has_line_comments() {
                 self.skip_line_comment();
             } else {
                 break;
@@ -630,6 +852,18 @@ impl Lexer {
         }
     }
 
+    fn read_block_comment(&mut self) -> Token<'a> {
+        let start_pos = self.position;
+        let start_line = self.line;
+        let start_col = self.column;
+
+        self.skip_block_comment();
+
+        let text = &self.input[start_pos + 2..self.position];
+        let text = text.strip_suffix("*/").unwrap_or(text);
+        Token::new(TokenType::Comment(Cow::Borrowed(text)), start_pos, start_line, start_col, self.position - start_pos)
+    }
+
     fn skip_line_comment(&mut self) {
         while self.ch.is_some() {
             let ch = self.ch.unwrap();
@@ -643,17 +877,13 @@ impl Lexer {
     fn read_escape(&mut self) -> Option<char> {
         self.read_char();
 
-        if self.ch.is_none() {
-            return None;
-        }
-
-        let ch = self.ch.unwrap();
+        let ch = self.ch?;
 
-        if ch.is_digit(16) {
+        if ch.is_ascii_hexdigit() {
             let start_position = self.position;
 
             let mut count = 0;
-            while self.ch.is_some() && self.ch.unwrap().is_digit(16) && count < 6 {
+            while self.ch.is_some() && self.ch.unwrap().is_ascii_hexdigit() && count < 6 {
                 self.read_char();
                 count += 1;
             }
@@ -666,37 +896,36 @@ impl Lexer {
 
             match u32::from_str_radix(hex_str, 16) {
                 Ok(code) => {
-                    match std::char::from_u32(code) {
-                        Some(c) => return Some(c),
-                        None => return None
-                    }
+                    std::char::from_u32(code)
                 },
-                Err(_) => return None
+                Err(_) => None
             }
         } else {
             let escaped_char = ch;
             self.read_char();
-            return Some(escaped_char);
+            Some(escaped_char)
         }
     }
 
-    fn read_identifier(&mut self) -> String {
-        let mut result = String::new();
+    /// Borrows the identifier from the input unless it contains escapes.
+    fn read_identifier(&mut self) -> Cow<'a, str> {
+        let input = self.input;
+        let start_position = self.position;
+        let mut decoded: Option<String> = None;
 
         if self.ch.is_some() {
             if self.ch == Some('\\') {
                 self.read_char();
 
                 if self.ch.is_none() {
-                    return result;
+                    return Cow::Borrowed("");
                 }
 
                 let escaped_char = self.ch.unwrap();
                 self.read_char();
-                
-                result.push(escaped_char);
+
+                decoded = Some(escaped_char.to_string());
             } else {
-                result.push(self.ch.unwrap());
                 self.read_char();
             }
         }
@@ -705,18 +934,24 @@ impl Lexer {
             let ch = self.ch.unwrap();
 
             if ch == '\\' {
+                let result = decoded.get_or_insert_with(|| input[start_position..self.position].to_string());
                 if let Some(escaped_char) = self.read_escape() {
                     result.push(escaped_char);
                 }
             } else if self.is_identifier_part(ch) {
-                result.push(ch);
+                if let Some(result) = &mut decoded {
+                    result.push(ch);
+                }
                 self.read_char();
             } else {
                 break;
             }
         }
 
-        result
+        match decoded {
+            Some(result) => Cow::Owned(result),
+            None => Cow::Borrowed(&input[start_position..self.position]),
+        }
     }
 
     fn read_number(&mut self) -> (f64, usize) {
@@ -726,25 +961,25 @@ impl Lexer {
             self.read_char();
         }
 
-        while self.ch.is_some() && self.ch.unwrap().is_digit(10) {
+        while self.ch.is_some() && self.ch.unwrap().is_ascii_digit() {
             self.read_char();
         }
 
         if self.ch == Some('.') {
             self.read_char();
 
-            while self.ch.is_some() && self.ch.unwrap().is_digit(10) {
+            while self.ch.is_some() && self.ch.unwrap().is_ascii_digit() {
                 self.read_char();
             }
         }
 
         let number_str = &self.input[start_position..self.position];
-        let number = number_str.parse::<f64>().unwrap_or_else(|_| 0.0);
+        let number = number_str.parse::<f64>().unwrap_or(0.0);
 
         (number, self.position - start_position)
     }
 
-    fn read_unit(&mut self) -> String {
+    fn read_unit(&mut self) -> Cow<'a, str> {
         let start_position = self.position;
 
         while self.ch.is_some() {
@@ -756,10 +991,10 @@ impl Lexer {
             }
         }
 
-        self.input[start_position..self.position].to_string()
+        Cow::Borrowed(&self.input[start_position..self.position])
     }
 
-    fn read_string(&mut self, quote_char: char) -> String {
+    fn read_string(&mut self, quote_char: char) -> Cow<'a, str> {
         let start_position = self.position;
         let mut escaped = false;
 
@@ -777,28 +1012,39 @@ impl Lexer {
             self.read_char();
         }
 
-        self.input[start_position..self.position].to_string()
+        Cow::Borrowed(&self.input[start_position..self.position])
     }
 
     fn is_identifier_part(&self, ch: char) -> bool {
-        ch.is_alphanumeric() || ch == '_' || ch == '-' || ch == '\\' ||
-            ch == '!' || ch == '#' || ch == '@' || ch == '$' || ch == '%' ||
-            ch == '&' || ch == '*' || ch == '~' || ch == '.' ||
-            ch > '\u{7F}'
+        ch.is_alphanumeric() || ch == '_' || ch == '-' || ch == '\\' || ch > '\u{7F}' ||
+            (self.dialect.has_extended_identifiers() && matches!(ch, '#' | '@' | '$' | '%' | '&' | '*' | '~' | '.'))
     }
 
     fn is_identifier_start(&self, ch: char) -> bool {
-        ch.is_alphabetic() || ch == '_' || ch == '-' || ch == '\\' ||
-            ch == '!' || ch == '#' || ch == '@' || ch == '$' || ch == '%' ||
-            ch == '&' || ch == '*' || ch == '~' ||
-            ch > '\u{7F}'
+        ch.is_alphabetic() || ch == '_' || ch == '-' || ch == '\\' || ch > '\u{7F}' ||
+            (self.dialect.has_extended_identifiers() && matches!(ch, '#' | '@' | '$' | '%' | '&' | '*' | '~'))
     }
 
     fn is_hex_digit(&self, ch: char) -> bool {
-        ch.is_digit(16)
+        ch.is_ascii_hexdigit()
     }
 
     fn is_digit_or_decimal(&self, ch: char) -> bool {
-        ch.is_digit(10) || ch == '.'
+        ch.is_ascii_digit() || ch == '.'
+    }
+}
+
+impl<'a> Iterator for Lexer<'a> {
+    type Item = Token<'a>;
+
+    /// Yields tokens up to, but not including, EOF.
+    fn next(&mut self) -> Option<Token<'a>> {
+        let token = self.next_token();
+
+        if token.token_type == TokenType::EOF {
+            None
+        } else {
+            Some(token)
+        }
     }
 }
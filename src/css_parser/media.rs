@@ -0,0 +1,536 @@
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::fmt;
+
+use crate::css_parser::ast::owned;
+
+/// Comma separated media queries, e.g. `screen and (min-width: 600px), print`.
+/// An empty list matches every device.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MediaQueryList<'a> {
+    pub queries: Vec<MediaQuery<'a>>,
+}
+
+impl MediaQueryList<'_> {
+    /// Whether any of the queries matches `device`.
+    pub fn evaluate(&self, device: &DeviceProfile) -> bool {
+        self.queries.is_empty() || self.queries.iter().any(|query| query.evaluate(device))
+    }
+
+    pub fn into_owned(self) -> MediaQueryList<'static> {
+        MediaQueryList {
+            queries: self.queries.into_iter().map(MediaQuery::into_owned).collect(),
+        }
+    }
+}
+
+impl fmt::Display for MediaQueryList<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, query) in self.queries.iter().enumerate() {
+            if i > 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "{}", query)?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MediaQualifier {
+    Not,  // negates the whole query
+    Only, // hides the query from legacy user agents, no effect otherwise
+}
+
+/// A single media query: `[not | only] <type> [and <condition>]` or a bare
+/// condition.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MediaQuery<'a> {
+    pub qualifier: Option<MediaQualifier>,
+    pub media_type: Option<Cow<'a, str>>, // None when the query is a bare condition
+    pub condition: Option<MediaCondition<'a>>,
+}
+
+impl MediaQuery<'_> {
+    /// Whether the query matches `device`. Queries that depend on unknown
+    /// features never match, not even when negated.
+    pub fn evaluate(&self, device: &DeviceProfile) -> bool {
+        let type_matches = self.media_type.as_ref().is_none_or(|media_type| {
+            media_type.eq_ignore_ascii_case("all") || media_type.eq_ignore_ascii_case(&device.media_type)
+        });
+
+        let result = match &self.condition {
+            Some(condition) if type_matches => condition.evaluate(device),
+            _ => Some(type_matches),
+        };
+
+        match result {
+            Some(matches) => matches != (self.qualifier == Some(MediaQualifier::Not)),
+            None => false,
+        }
+    }
+
+    pub fn into_owned(self) -> MediaQuery<'static> {
+        MediaQuery {
+            qualifier: self.qualifier,
+            media_type: self.media_type.map(owned),
+            condition: self.condition.map(MediaCondition::into_owned),
+        }
+    }
+}
+
+impl fmt::Display for MediaQuery<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.qualifier {
+            Some(MediaQualifier::Not) => write!(f, "not ")?,
+            Some(MediaQualifier::Only) => write!(f, "only ")?,
+            None => {}
+        }
+
+        match (&self.media_type, &self.condition) {
+            (Some(media_type), Some(condition)) => write!(f, "{} and {}", media_type, condition),
+            (Some(media_type), None) => write!(f, "{}", media_type),
+            (None, Some(condition)) => write!(f, "{}", condition),
+            (None, None) => Ok(()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum MediaCondition<'a> {
+    Feature(MediaFeature<'a>),
+    Not(Box<MediaCondition<'a>>),
+    And(Vec<MediaCondition<'a>>),
+    Or(Vec<MediaCondition<'a>>),
+    GeneralEnclosed(String), // parenthesized text that is not a known condition, never matches
+}
+
+impl MediaCondition<'_> {
+    /// Three-valued result, `None` meaning unknown.
+    pub fn evaluate(&self, device: &DeviceProfile) -> Option<bool> {
+        match self {
+            MediaCondition::Feature(feature) => feature.evaluate(device),
+            MediaCondition::Not(condition) => condition.evaluate(device).map(|matches| !matches),
+            MediaCondition::And(conditions) => evaluate_and(conditions.iter().map(|condition| condition.evaluate(device))),
+            MediaCondition::Or(conditions) => evaluate_or(conditions.iter().map(|condition| condition.evaluate(device))),
+            MediaCondition::GeneralEnclosed(_) => None,
+        }
+    }
+
+    pub fn into_owned(self) -> MediaCondition<'static> {
+        match self {
+            MediaCondition::Feature(feature) => MediaCondition::Feature(feature.into_owned()),
+            MediaCondition::Not(condition) => MediaCondition::Not(Box::new(condition.into_owned())),
+            MediaCondition::And(conditions) => MediaCondition::And(conditions.into_iter().map(MediaCondition::into_owned).collect()),
+            MediaCondition::Or(conditions) => MediaCondition::Or(conditions.into_iter().map(MediaCondition::into_owned).collect()),
+            MediaCondition::GeneralEnclosed(text) => MediaCondition::GeneralEnclosed(text),
+        }
+    }
+}
+
+impl Operand for MediaCondition<'_> {
+    fn is_parenthesized(&self) -> bool {
+        matches!(self, MediaCondition::Feature(_) | MediaCondition::GeneralEnclosed(_))
+    }
+}
+
+impl fmt::Display for MediaCondition<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MediaCondition::Feature(feature) => write!(f, "{}", feature),
+            MediaCondition::Not(condition) => {
+                write!(f, "not ")?;
+                condition.fmt_in_parens(f)
+            },
+            MediaCondition::And(conditions) => fmt_joined(f, conditions, " and "),
+            MediaCondition::Or(conditions) => fmt_joined(f, conditions, " or "),
+            MediaCondition::GeneralEnclosed(text) => write!(f, "{}", text),
+        }
+    }
+}
+
+/// `and` over three-valued results, `None` meaning unknown: false wins over
+/// unknown.
+pub(crate) fn evaluate_and(results: impl Iterator<Item = Option<bool>>) -> Option<bool> {
+    let results: Vec<_> = results.collect();
+    if results.contains(&Some(false)) {
+        Some(false)
+    } else if results.contains(&None) {
+        None
+    } else {
+        Some(true)
+    }
+}
+
+/// `or` over three-valued results: true wins over unknown.
+pub(crate) fn evaluate_or(results: impl Iterator<Item = Option<bool>>) -> Option<bool> {
+    let results: Vec<_> = results.collect();
+    if results.contains(&Some(true)) {
+        Some(true)
+    } else if results.contains(&None) {
+        None
+    } else {
+        Some(false)
+    }
+}
+
+/// A query that can be an operand of `not`, `and` and `or`.
+pub(crate) trait Operand: fmt::Display {
+    /// Whether the query is written with its own parentheses, e.g. a feature.
+    fn is_parenthesized(&self) -> bool;
+
+    /// Writes the query as an operand, adding parentheses where needed.
+    fn fmt_in_parens(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_parenthesized() {
+            write!(f, "{}", self)
+        } else {
+            write!(f, "({})", self)
+        }
+    }
+}
+
+/// Writes `operands` separated by `operator`, e.g. ` and `.
+pub(crate) fn fmt_joined<T: Operand>(f: &mut fmt::Formatter<'_>, operands: &[T], operator: &str) -> fmt::Result {
+    for (i, operand) in operands.iter().enumerate() {
+        if i > 0 {
+            write!(f, "{}", operator)?;
+        }
+        operand.fmt_in_parens(f)?;
+    }
+    Ok(())
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MediaComparison {
+    Lt, // <
+    Le, // <=
+    Gt, // >
+    Ge, // >=
+    Eq, // =
+}
+
+impl MediaComparison {
+    fn compare(self, left: f64, right: f64) -> bool {
+        match self {
+            MediaComparison::Lt => left < right,
+            MediaComparison::Le => left <= right,
+            MediaComparison::Gt => left > right,
+            MediaComparison::Ge => left >= right,
+            MediaComparison::Eq => left == right,
+        }
+    }
+}
+
+impl fmt::Display for MediaComparison {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MediaComparison::Lt => write!(f, "<"),
+            MediaComparison::Le => write!(f, "<="),
+            MediaComparison::Gt => write!(f, ">"),
+            MediaComparison::Ge => write!(f, ">="),
+            MediaComparison::Eq => write!(f, "="),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum MediaFeatureValue<'a> {
+    Number(f64),
+    Dimension(f64, Cow<'a, str>), // value and unit, e.g. 600px or 2dppx
+    Ratio(f64, f64),              // 16/9
+    Ident(Cow<'a, str>),
+}
+
+impl MediaFeatureValue<'_> {
+    pub fn into_owned(self) -> MediaFeatureValue<'static> {
+        match self {
+            MediaFeatureValue::Number(value) => MediaFeatureValue::Number(value),
+            MediaFeatureValue::Dimension(value, unit) => MediaFeatureValue::Dimension(value, owned(unit)),
+            MediaFeatureValue::Ratio(numerator, denominator) => MediaFeatureValue::Ratio(numerator, denominator),
+            MediaFeatureValue::Ident(name) => MediaFeatureValue::Ident(owned(name)),
+        }
+    }
+
+    /// Value in canonical units (px, dppx) so that values can be compared.
+    fn resolve(&self, font_size: f64) -> Option<Resolved<'_>> {
+        match self {
+            MediaFeatureValue::Number(value) => Some(Resolved::Number(*value)),
+            MediaFeatureValue::Ratio(numerator, denominator) => Some(Resolved::Number(numerator / denominator)),
+            MediaFeatureValue::Ident(name) => Some(Resolved::Ident(name)),
+            MediaFeatureValue::Dimension(value, unit) => {
+                let length = match unit.to_ascii_lowercase().as_str() {
+                    "px" => Some(1.0),
+                    "em" | "rem" => Some(font_size),
+                    "in" => Some(96.0),
+                    "cm" => Some(96.0 / 2.54),
+                    "mm" => Some(96.0 / 25.4),
+                    "q" => Some(96.0 / 101.6),
+                    "pt" => Some(96.0 / 72.0),
+                    "pc" => Some(16.0),
+                    _ => None,
+                };
+                let resolution = match unit.to_ascii_lowercase().as_str() {
+                    "dppx" | "x" => Some(1.0),
+                    "dpi" => Some(1.0 / 96.0),
+                    "dpcm" => Some(2.54 / 96.0),
+                    _ => None,
+                };
+
+                match (length, resolution) {
+                    (Some(factor), _) => Some(Resolved::Length(value * factor)),
+                    (_, Some(factor)) => Some(Resolved::Resolution(value * factor)),
+                    _ => None,
+                }
+            },
+        }
+    }
+}
+
+impl fmt::Display for MediaFeatureValue<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MediaFeatureValue::Number(value) => write!(f, "{}", value),
+            MediaFeatureValue::Dimension(value, unit) => write!(f, "{}{}", value, unit),
+            MediaFeatureValue::Ratio(numerator, denominator) => write!(f, "{}/{}", numerator, denominator),
+            MediaFeatureValue::Ident(name) => write!(f, "{}", name),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+enum Resolved<'v> {
+    Number(f64),     // numbers and ratios
+    Length(f64),     // px
+    Resolution(f64), // dppx
+    Ident(&'v str),
+}
+
+impl Resolved<'_> {
+    /// `self <comparison> other`, `None` when the values are not comparable.
+    fn compare(&self, comparison: MediaComparison, other: &Resolved) -> Option<bool> {
+        match (self, other) {
+            (Resolved::Number(left), Resolved::Number(right))
+            | (Resolved::Length(left), Resolved::Length(right))
+            | (Resolved::Resolution(left), Resolved::Resolution(right)) => Some(comparison.compare(*left, *right)),
+            // a unitless 0 is a valid length or resolution
+            (Resolved::Length(left) | Resolved::Resolution(left), Resolved::Number(right)) if *right == 0.0 => {
+                Some(comparison.compare(*left, *right))
+            },
+            (Resolved::Number(left), Resolved::Length(right) | Resolved::Resolution(right)) if *left == 0.0 => {
+                Some(comparison.compare(*left, *right))
+            },
+            (Resolved::Ident(left), Resolved::Ident(right)) if comparison == MediaComparison::Eq => {
+                Some(left.eq_ignore_ascii_case(right))
+            },
+            _ => None,
+        }
+    }
+}
+
+/// A media feature test in parentheses.
+#[derive(Debug, Clone, PartialEq)]
+pub enum MediaFeature<'a> {
+    Boolean(Cow<'a, str>), // (color)
+    Plain {                // (min-width: 600px)
+        name: Cow<'a, str>,
+        value: MediaFeatureValue<'a>,
+    },
+    Range {                // (400px <= width < 700px), either bound may be missing
+        name: Cow<'a, str>,
+        start: Option<(MediaFeatureValue<'a>, MediaComparison)>,
+        end: Option<(MediaComparison, MediaFeatureValue<'a>)>,
+    },
+}
+
+impl MediaFeature<'_> {
+    /// Three-valued result, `None` when the feature is unknown to `values` or
+    /// the values are not comparable.
+    pub fn evaluate(&self, values: &dyn FeatureValues) -> Option<bool> {
+        let font_size = values.font_size();
+
+        match self {
+            MediaFeature::Boolean(name) => {
+                let actual = values.feature(name)?;
+                Some(match actual.resolve(font_size)? {
+                    Resolved::Number(value) | Resolved::Length(value) | Resolved::Resolution(value) => value != 0.0,
+                    // `none` (pointer, forced-colors, ...) and `no-preference`
+                    // (prefers-reduced-motion, ...) are the values that evaluate
+                    // to false in a boolean context.
+                    Resolved::Ident(value) => !value.eq_ignore_ascii_case("none") && !value.eq_ignore_ascii_case("no-preference"),
+                })
+            },
+            MediaFeature::Plain { name, value } => {
+                let lowercase = name.to_ascii_lowercase();
+                let (name, comparison) = if let Some(name) = strip_range_prefix(&lowercase, "min-") {
+                    (name, MediaComparison::Ge)
+                } else if let Some(name) = strip_range_prefix(&lowercase, "max-") {
+                    (name, MediaComparison::Le)
+                } else {
+                    (lowercase.clone(), MediaComparison::Eq)
+                };
+
+                let actual = values.feature(&name)?;
+                actual.resolve(font_size)?.compare(comparison, &value.resolve(font_size)?)
+            },
+            MediaFeature::Range { name, start, end } => {
+                let actual = values.feature(name)?;
+                let actual = actual.resolve(font_size)?;
+                let mut result = true;
+
+                if let Some((value, comparison)) = start {
+                    result &= value.resolve(font_size)?.compare(*comparison, &actual)?;
+                }
+                if let Some((comparison, value)) = end {
+                    result &= actual.compare(*comparison, &value.resolve(font_size)?)?;
+                }
+
+                Some(result)
+            },
+        }
+    }
+
+    pub fn into_owned(self) -> MediaFeature<'static> {
+        match self {
+            MediaFeature::Boolean(name) => MediaFeature::Boolean(owned(name)),
+            MediaFeature::Plain { name, value } => MediaFeature::Plain { name: owned(name), value: value.into_owned() },
+            MediaFeature::Range { name, start, end } => MediaFeature::Range {
+                name: owned(name),
+                start: start.map(|(value, comparison)| (value.into_owned(), comparison)),
+                end: end.map(|(comparison, value)| (comparison, value.into_owned())),
+            },
+        }
+    }
+}
+
+impl fmt::Display for MediaFeature<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MediaFeature::Boolean(name) => write!(f, "({})", name),
+            MediaFeature::Plain { name, value } => write!(f, "({}: {})", name, value),
+            MediaFeature::Range { name, start, end } => {
+                write!(f, "(")?;
+                if let Some((value, comparison)) = start {
+                    write!(f, "{} {} ", value, comparison)?;
+                }
+                write!(f, "{}", name)?;
+                if let Some((comparison, value)) = end {
+                    write!(f, " {} {}", comparison, value)?;
+                }
+                write!(f, ")")
+            },
+        }
+    }
+}
+
+/// Strips `min-`/`max-` from `name`, keeping a vendor prefix in place
+/// (`-webkit-min-device-pixel-ratio` becomes `-webkit-device-pixel-ratio`).
+fn strip_range_prefix(name: &str, prefix: &str) -> Option<String> {
+    if let Some(rest) = name.strip_prefix(prefix) {
+        return Some(rest.to_string());
+    }
+
+    let vendor_end = name.strip_prefix('-')?.find('-')? + 2;
+    let rest = name[vendor_end..].strip_prefix(prefix)?;
+    Some(format!("{}{}", &name[..vendor_end], rest))
+}
+
+/// Source of the values media features are evaluated against: a device for
+/// `@media`, a query container for `@container`.
+pub trait FeatureValues {
+    /// Current value of the feature `name`, `None` for unknown features.
+    fn feature(&self, name: &str) -> Option<MediaFeatureValue<'static>>;
+
+    /// px per em/rem in queries.
+    fn font_size(&self) -> f64;
+}
+
+/// The device media queries are evaluated against. Viewport and resolution
+/// features are derived from the fields, everything else is looked up in
+/// `features`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DeviceProfile {
+    pub media_type: String, // screen, print, ...
+    pub width: f64,         // viewport width in CSS px
+    pub height: f64,        // viewport height in CSS px
+    pub resolution: f64,    // device pixels per CSS px
+    pub font_size: f64,     // px per em/rem in queries
+    pub features: HashMap<String, MediaFeatureValue<'static>>, // e.g. prefers-color-scheme: dark
+}
+
+impl DeviceProfile {
+    /// A color screen with a fine pointer and no user preferences.
+    pub fn screen(width: f64, height: f64) -> Self {
+        let features = [
+            ("color", MediaFeatureValue::Number(8.0)),
+            ("color-index", MediaFeatureValue::Number(0.0)),
+            ("monochrome", MediaFeatureValue::Number(0.0)),
+            ("grid", MediaFeatureValue::Number(0.0)),
+            ("scan", MediaFeatureValue::Ident("progressive".into())),
+            ("update", MediaFeatureValue::Ident("fast".into())),
+            ("hover", MediaFeatureValue::Ident("hover".into())),
+            ("any-hover", MediaFeatureValue::Ident("hover".into())),
+            ("pointer", MediaFeatureValue::Ident("fine".into())),
+            ("any-pointer", MediaFeatureValue::Ident("fine".into())),
+            ("prefers-color-scheme", MediaFeatureValue::Ident("light".into())),
+            ("prefers-contrast", MediaFeatureValue::Ident("no-preference".into())),
+            ("prefers-reduced-motion", MediaFeatureValue::Ident("no-preference".into())),
+            ("prefers-reduced-transparency", MediaFeatureValue::Ident("no-preference".into())),
+            ("forced-colors", MediaFeatureValue::Ident("none".into())),
+            ("display-mode", MediaFeatureValue::Ident("browser".into())),
+        ];
+
+        DeviceProfile {
+            media_type: "screen".to_string(),
+            width,
+            height,
+            resolution: 1.0,
+            font_size: 16.0,
+            features: features.into_iter().map(|(name, value)| (name.to_string(), value)).collect(),
+        }
+    }
+
+    pub fn with_resolution(mut self, resolution: f64) -> Self {
+        self.resolution = resolution;
+        self
+    }
+
+    /// Sets a feature such as `prefers-color-scheme` or `pointer`.
+    pub fn with_feature(mut self, name: &str, value: MediaFeatureValue<'static>) -> Self {
+        self.features.insert(name.to_ascii_lowercase(), value);
+        self
+    }
+
+    /// Current value of the feature `name`, `None` for unknown features.
+    pub fn feature(&self, name: &str) -> Option<MediaFeatureValue<'static>> {
+        let name = name.to_ascii_lowercase();
+
+        match name.as_str() {
+            "width" | "device-width" => Some(MediaFeatureValue::Dimension(self.width, "px".into())),
+            "height" | "device-height" => Some(MediaFeatureValue::Dimension(self.height, "px".into())),
+            "aspect-ratio" | "device-aspect-ratio" => Some(MediaFeatureValue::Ratio(self.width, self.height)),
+            "orientation" => {
+                let orientation = if self.height >= self.width { "portrait" } else { "landscape" };
+                Some(MediaFeatureValue::Ident(orientation.into()))
+            },
+            "resolution" => Some(MediaFeatureValue::Dimension(self.resolution, "dppx".into())),
+            "-webkit-device-pixel-ratio" => Some(MediaFeatureValue::Number(self.resolution)),
+            _ => self.features.get(&name).cloned(),
+        }
+    }
+}
+
+impl FeatureValues for DeviceProfile {
+    fn feature(&self, name: &str) -> Option<MediaFeatureValue<'static>> {
+        DeviceProfile::feature(self, name)
+    }
+
+    fn font_size(&self) -> f64 {
+        self.font_size
+    }
+}
+
+impl Default for DeviceProfile {
+    fn default() -> Self {
+        DeviceProfile::screen(1280.0, 720.0)
+    }
+}
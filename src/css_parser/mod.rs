@@ -1,3 +1,18 @@
 pub mod ast;
+pub mod component_value;
+pub mod container;
+pub mod counter_style;
+pub mod error;
+pub mod font_face;
+pub mod font_feature_values;
+pub mod import;
+pub mod keyframes;
+mod layer;
+pub mod namespace;
+pub mod page;
 pub mod parser;
+pub mod property;
 pub mod lexer;
+pub mod media;
+pub mod supports;
+pub mod tokenizer;
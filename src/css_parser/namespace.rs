@@ -0,0 +1,100 @@
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::fmt;
+
+use crate::css_parser::ast::{owned, AtRulePrelude, CssRule, Selector, Stylesheet};
+
+/// Prelude of a `@namespace` rule, e.g. `svg url(http://www.w3.org/2000/svg)`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct NamespaceRule<'a> {
+    pub prefix: Option<Cow<'a, str>>, // `None` declares the default namespace
+    pub url: Cow<'a, str>,
+}
+
+impl NamespaceRule<'_> {
+    pub fn into_owned(self) -> NamespaceRule<'static> {
+        NamespaceRule { prefix: self.prefix.map(owned), url: owned(self.url) }
+    }
+}
+
+impl fmt::Display for NamespaceRule<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(prefix) = &self.prefix {
+            write!(f, "{} ", prefix)?;
+        }
+        write!(f, "\"{}\"", self.url)
+    }
+}
+
+/// Namespace component of a type, universal or attribute selector.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum NamespacePrefix<'a> {
+    Any,                 // `*|`, any namespace or none
+    None,                // `|`, no namespace
+    Named(Cow<'a, str>), // `svg|`, declared by `@namespace`
+}
+
+impl NamespacePrefix<'_> {
+    pub fn into_owned(self) -> NamespacePrefix<'static> {
+        match self {
+            NamespacePrefix::Any => NamespacePrefix::Any,
+            NamespacePrefix::None => NamespacePrefix::None,
+            NamespacePrefix::Named(prefix) => NamespacePrefix::Named(owned(prefix)),
+        }
+    }
+}
+
+impl fmt::Display for NamespacePrefix<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            NamespacePrefix::Any => write!(f, "*|"),
+            NamespacePrefix::None => write!(f, "|"),
+            NamespacePrefix::Named(prefix) => write!(f, "{}|", prefix),
+        }
+    }
+}
+
+/// The namespaces declared by a stylesheet's `@namespace` rules.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Namespaces<'a> {
+    pub default: Option<Cow<'a, str>>,
+    pub prefixes: HashMap<Cow<'a, str>, Cow<'a, str>>,
+}
+
+impl Namespaces<'_> {
+    /// URI of a declared prefix.
+    pub fn get(&self, prefix: &str) -> Option<&str> {
+        self.prefixes.get(prefix).map(|url| url.as_ref())
+    }
+
+    /// Whether every named prefix in `selector` is declared, as selectors
+    /// with an undeclared prefix are invalid.
+    pub fn declares_all(&self, selector: &Selector<'_>) -> bool {
+        selector.groups.iter().flat_map(|group| &group.parts).all(|part| match part.namespace() {
+            Some(NamespacePrefix::Named(prefix)) => self.prefixes.contains_key(prefix),
+            _ => true,
+        })
+    }
+}
+
+impl<'a> Stylesheet<'a> {
+    /// Prefix to URI map of the top-level `@namespace` rules; a later rule
+    /// for the same prefix wins.
+    pub fn namespaces(&self) -> Namespaces<'a> {
+        let mut namespaces = Namespaces::default();
+
+        for rule in &self.rules {
+            let CssRule::At(at_rule) = rule else { continue };
+            let AtRulePrelude::Namespace(namespace) = &at_rule.prelude else { continue };
+
+            match &namespace.prefix {
+                Some(prefix) => {
+                    namespaces.prefixes.insert(prefix.clone(), namespace.url.clone());
+                },
+                None => namespaces.default = Some(namespace.url.clone()),
+            }
+        }
+
+        namespaces
+    }
+}
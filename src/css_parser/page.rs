@@ -0,0 +1,266 @@
+use std::borrow::Cow;
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+
+use crate::css_parser::ast::{owned, AtRulePrelude, AtRuleType, CssRule, Declaration, Stylesheet};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PagePseudoClass {
+    First, // first page of the document
+    Left,  // left-hand pages, the even pages in left-to-right documents
+    Right, // right-hand pages, starting with the first page
+    Blank, // pages left empty by a forced break
+}
+
+impl PagePseudoClass {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "first" => Some(PagePseudoClass::First),
+            "left" => Some(PagePseudoClass::Left),
+            "right" => Some(PagePseudoClass::Right),
+            "blank" => Some(PagePseudoClass::Blank),
+            _ => None,
+        }
+    }
+
+    fn matches(&self, page: &PageInfo) -> bool {
+        match self {
+            PagePseudoClass::First => page.index == 0,
+            PagePseudoClass::Left => !page.index.is_multiple_of(2),
+            PagePseudoClass::Right => page.index.is_multiple_of(2),
+            PagePseudoClass::Blank => page.blank,
+        }
+    }
+}
+
+impl fmt::Display for PagePseudoClass {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PagePseudoClass::First => write!(f, ":first"),
+            PagePseudoClass::Left => write!(f, ":left"),
+            PagePseudoClass::Right => write!(f, ":right"),
+            PagePseudoClass::Blank => write!(f, ":blank"),
+        }
+    }
+}
+
+/// One selector of an `@page` rule, e.g. `invoice:first`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PageSelector<'a> {
+    pub name: Option<Cow<'a, str>>, // named page, from the `page` property
+    pub pseudo_classes: Vec<PagePseudoClass>,
+}
+
+impl PageSelector<'_> {
+    pub fn matches(&self, page: &PageInfo) -> bool {
+        self.name.as_ref().is_none_or(|name| page.name.as_deref() == Some(name.as_ref()))
+            && self.pseudo_classes.iter().all(|pseudo_class| pseudo_class.matches(page))
+    }
+
+    /// Page selector specificity: the page name, then `:first` and `:blank`,
+    /// then `:left` and `:right`.
+    pub fn specificity(&self) -> (usize, usize, usize) {
+        let count = |classes: &[PagePseudoClass]| self.pseudo_classes.iter().filter(|class| classes.contains(class)).count();
+
+        (
+            usize::from(self.name.is_some()),
+            count(&[PagePseudoClass::First, PagePseudoClass::Blank]),
+            count(&[PagePseudoClass::Left, PagePseudoClass::Right]),
+        )
+    }
+
+    pub fn into_owned(self) -> PageSelector<'static> {
+        PageSelector {
+            name: self.name.map(owned),
+            pseudo_classes: self.pseudo_classes,
+        }
+    }
+}
+
+impl fmt::Display for PageSelector<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(name) = &self.name {
+            write!(f, "{}", name)?;
+        }
+        for pseudo_class in &self.pseudo_classes {
+            write!(f, "{}", pseudo_class)?;
+        }
+        Ok(())
+    }
+}
+
+/// The page-margin boxes, one at-rule each inside `@page`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MarginBox {
+    TopLeftCorner,
+    TopLeft,
+    TopCenter,
+    TopRight,
+    TopRightCorner,
+    RightTop,
+    RightMiddle,
+    RightBottom,
+    BottomRightCorner,
+    BottomRight,
+    BottomCenter,
+    BottomLeft,
+    BottomLeftCorner,
+    LeftBottom,
+    LeftMiddle,
+    LeftTop,
+}
+
+impl MarginBox {
+    pub const ALL: [MarginBox; 16] = [
+        MarginBox::TopLeftCorner,
+        MarginBox::TopLeft,
+        MarginBox::TopCenter,
+        MarginBox::TopRight,
+        MarginBox::TopRightCorner,
+        MarginBox::RightTop,
+        MarginBox::RightMiddle,
+        MarginBox::RightBottom,
+        MarginBox::BottomRightCorner,
+        MarginBox::BottomRight,
+        MarginBox::BottomCenter,
+        MarginBox::BottomLeft,
+        MarginBox::BottomLeftCorner,
+        MarginBox::LeftBottom,
+        MarginBox::LeftMiddle,
+        MarginBox::LeftTop,
+    ];
+
+    /// The box for an at-rule name such as `top-center`.
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|margin_box| margin_box.name().eq_ignore_ascii_case(name))
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            MarginBox::TopLeftCorner => "top-left-corner",
+            MarginBox::TopLeft => "top-left",
+            MarginBox::TopCenter => "top-center",
+            MarginBox::TopRight => "top-right",
+            MarginBox::TopRightCorner => "top-right-corner",
+            MarginBox::RightTop => "right-top",
+            MarginBox::RightMiddle => "right-middle",
+            MarginBox::RightBottom => "right-bottom",
+            MarginBox::BottomRightCorner => "bottom-right-corner",
+            MarginBox::BottomRight => "bottom-right",
+            MarginBox::BottomCenter => "bottom-center",
+            MarginBox::BottomLeft => "bottom-left",
+            MarginBox::BottomLeftCorner => "bottom-left-corner",
+            MarginBox::LeftBottom => "left-bottom",
+            MarginBox::LeftMiddle => "left-middle",
+            MarginBox::LeftTop => "left-top",
+        }
+    }
+}
+
+impl fmt::Display for MarginBox {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+/// A printed page that `@page` rules are matched against.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PageInfo {
+    pub index: usize,         // zero-based, page 0 is a right-hand page
+    pub name: Option<String>, // value of the `page` property, if any
+    pub blank: bool,
+}
+
+impl PageInfo {
+    pub fn new(index: usize) -> Self {
+        PageInfo { index, ..Self::default() }
+    }
+
+    pub fn with_name(mut self, name: &str) -> Self {
+        self.name = Some(name.to_string());
+        self
+    }
+}
+
+/// Effective declarations of one page after cascading all `@page` rules.
+#[derive(Debug, Default)]
+pub struct PageStyle<'s, 'a> {
+    pub declarations: Vec<&'s Declaration<'a>>,
+    pub margin_boxes: HashMap<MarginBox, Vec<&'s Declaration<'a>>>,
+}
+
+impl<'a> PageStyle<'_, 'a> {
+    /// The effective declaration of `property` on the page itself.
+    pub fn get(&self, property: &str) -> Option<&Declaration<'a>> {
+        self.declarations.iter().copied().find(|declaration| declaration.property.eq_ignore_ascii_case(property))
+    }
+}
+
+/// A declaration that applies to a page, with what decides its precedence.
+struct Candidate<'s, 'a> {
+    declaration: &'s Declaration<'a>,
+    specificity: (usize, usize, usize),
+    order: usize,
+}
+
+impl<'a> Stylesheet<'a> {
+    /// Cascades the `@page` rules matching `page`: `!important` declarations
+    /// win, then the more specific page selector, then the later rule. Only
+    /// the winning declaration of each property is kept. `@page` rules inside
+    /// conditional rules count whether or not the condition holds.
+    pub fn page_style<'s>(&'s self, page: &PageInfo) -> PageStyle<'s, 'a> {
+        let mut declarations = Vec::new();
+        let mut margin_boxes: HashMap<MarginBox, Vec<Candidate<'s, 'a>>> = HashMap::new();
+        let mut order = 0;
+
+        for page_rule in self.at_rules_of_type(AtRuleType::Page) {
+            let selectors = match &page_rule.prelude {
+                AtRulePrelude::Page(selectors) => selectors.as_slice(),
+                _ => &[],
+            };
+
+            // An empty selector list matches every page.
+            let specificity = if selectors.is_empty() {
+                Some((0, 0, 0))
+            } else {
+                selectors.iter().filter(|selector| selector.matches(page)).map(PageSelector::specificity).max()
+            };
+            let Some(specificity) = specificity else { continue };
+
+            for rule in &page_rule.rules {
+                let (target, blocks) = match rule {
+                    CssRule::Style(_) => (&mut declarations, std::slice::from_ref(rule)),
+                    CssRule::At(margin_rule) => match margin_rule.margin_box() {
+                        Some(margin_box) => (margin_boxes.entry(margin_box).or_default(), margin_rule.rules.as_slice()),
+                        None => continue,
+                    },
+                };
+
+                for declaration in blocks.iter().filter_map(CssRule::as_style).flat_map(|block| &block.declarations) {
+                    target.push(Candidate { declaration, specificity, order });
+                    order += 1;
+                }
+            }
+        }
+
+        PageStyle {
+            declarations: cascade(declarations),
+            margin_boxes: margin_boxes.into_iter().map(|(margin_box, candidates)| (margin_box, cascade(candidates))).collect(),
+        }
+    }
+}
+
+/// Winning declaration per property, in cascade order.
+fn cascade<'s, 'a>(mut candidates: Vec<Candidate<'s, 'a>>) -> Vec<&'s Declaration<'a>> {
+    candidates.sort_by_key(|candidate| (candidate.declaration.is_important, candidate.specificity, candidate.order));
+
+    let mut seen = HashSet::new();
+    let mut winners: Vec<_> = candidates
+        .iter()
+        .rev()
+        .filter(|candidate| seen.insert(candidate.declaration.property.to_ascii_lowercase()))
+        .map(|candidate| candidate.declaration)
+        .collect();
+    winners.reverse();
+    winners
+}
@@ -1,5 +1,16 @@
+use std::borrow::Cow;
 use crate::css_parser::ast::*;
+use crate::css_parser::component_value::{split_at_rule, to_css, trim_whitespace, ComponentValue};
+use crate::css_parser::container::{ContainerCondition, ContainerQuery, StyleQuery};
+use crate::css_parser::error::{Diagnostic, ParseError, ParseErrorKind, Recovery};
+use crate::css_parser::font_feature_values::FeatureType;
+use crate::css_parser::import::ImportRule;
+use crate::css_parser::keyframes::TimelineRange;
 use crate::css_parser::lexer::*;
+use crate::css_parser::media::*;
+use crate::css_parser::namespace::{NamespacePrefix, NamespaceRule};
+use crate::css_parser::page::{MarginBox, PagePseudoClass, PageSelector};
+use crate::css_parser::supports::SupportsCondition;
 
 fn is_color_name(name: &str) -> bool {
     let color_names = [
@@ -34,6 +45,45 @@ fn is_color_name(name: &str) -> bool {
     color_names.contains(&name.to_lowercase().as_str())
 }
 
+/// Boolean operator of a condition chain.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Operator {
+    And,
+    Or,
+}
+
+/// Strips the quotes the lexer keeps around a quoted `url()` argument.
+fn unquote(text: Cow<'_, str>) -> Cow<'_, str> {
+    fn strip(text: &str) -> &str {
+        let text = text.trim();
+        ['"', '\'']
+            .into_iter()
+            .find_map(|quote| text.strip_prefix(quote).and_then(|rest| rest.strip_suffix(quote)))
+            .unwrap_or(text)
+    }
+
+    match text {
+        Cow::Borrowed(text) => Cow::Borrowed(strip(text)),
+        Cow::Owned(text) => Cow::Owned(strip(&text).to_string()),
+    }
+}
+
+/// A font family name as written in a prelude: bare when it is a sequence of
+/// identifiers, quoted otherwise.
+fn quote_family(family: &str) -> String {
+    let is_identifier = |word: &str| {
+        !word.is_empty()
+            && !word.starts_with(|ch: char| ch.is_ascii_digit())
+            && word.chars().all(|ch| ch.is_alphanumeric() || ch == '-' || ch == '_')
+    };
+
+    if family.split(' ').all(is_identifier) {
+        family.to_string()
+    } else {
+        format!("\"{}\"", family.replace('"', "\\\""))
+    }
+}
+
 fn is_css_keyword(keyword: &str) -> bool {
     let keywords = [
         "inherit", "initial", "unset", "revert", "auto", "none", "normal", "bold",
@@ -51,103 +101,374 @@ fn is_css_keyword(keyword: &str) -> bool {
 
     keywords.contains(&keyword.to_lowercase().as_str())
 }
-pub struct CssParser {
-    lexer: Lexer,
-    current_token: Option<Token>,
+pub struct CssParser<'a> {
+    lexer: Lexer<'a>,
+    significant_whitespace: bool, // whitespace tokens are returned, e.g. inside selectors
+    in_style_rule: bool,          // inside a style rule's block, where rules may nest
+    block_depth: usize,
+    last_end: usize,              // end of the last token other than whitespace
+    last_line: usize,             // line that token ends on
+    recover: bool,
+    diagnostics: Vec<Diagnostic>,
 }
 
-impl CssParser {
-    pub fn new(input: String) -> Self {
-        let mut lexer = Lexer::new(input);
-        let current_token = Some(lexer.next_token());
+impl<'a> CssParser<'a> {
+    pub fn new(input: &'a str) -> Self {
+        Self::with_lexer(Lexer::new(input))
+    }
 
-        CssParser {
+    /// Parser over a configured lexer, e.g. one that preserves comments.
+    pub fn with_lexer(lexer: Lexer<'a>) -> Self {
+        let mut parser = CssParser {
             lexer,
-            current_token,
-        }
+            significant_whitespace: false,
+            in_style_rule: false,
+            block_depth: 0,
+            last_end: 0,
+            last_line: 1,
+            recover: false,
+            diagnostics: Vec::new(),
+        };
+        parser.fill_lookahead();
+        parser
     }
 
-    fn next_token(&mut self) -> Option<Token> {
-        let current = self.current_token.take();
-        if current.as_ref().map_or(false, |t| matches!(t.token_type, TokenType::EOF)) {
-            self.current_token = current.clone();
-        } else {
-            self.current_token = Some(self.lexer.next_token());
+    fn next_token(&mut self) -> Token<'a> {
+        while self.is_skipped_at(0) {
+            self.lexer.next_token();
+        }
+
+        let token = self.lexer.next_token();
+
+        match token.token_type {
+            TokenType::OpenBrace => self.block_depth += 1,
+            TokenType::CloseBrace => self.block_depth = self.block_depth.saturating_sub(1),
+            _ => {}
+        }
+
+        if !matches!(token.token_type, TokenType::Whitespace | TokenType::EOF) {
+            self.last_end = token.position + token.length;
+            self.last_line = token.line + self.lexer.input()[token.position..self.last_end].matches('\n').count();
         }
-        current
+
+        self.fill_lookahead();
+
+        token
     }
 
-    fn peek_token(&self) -> Option<&Token> {
-        self.current_token.as_ref()
+    /// Lexes up to the token `peek_token` returns, so that peeking does not
+    /// need mutable access.
+    fn fill_lookahead(&mut self) {
+        let mut n = 0;
+        while self.is_skipped_at(n) {
+            n += 1;
+        }
     }
 
-    fn expect_open_brace(&mut self) -> Result<(), String> {
-        if let Some(token) = self.next_token() {
-            match token.token_type {
-                TokenType::OpenBrace => Ok(()),
-                _ => Err(format!("Expected open brace, found {:?}", token.token_type)),
+    fn peek_token(&self) -> Option<&Token<'a>> {
+        let mut n = 0;
+        loop {
+            let token = self.lexer.buffered(n)?;
+            if !Self::is_skipped(token, self.significant_whitespace) {
+                return Some(token);
             }
-        } else {
-            Err("Unexpected end of input".to_string())
+            n += 1;
         }
     }
 
-    fn expect_close_brace(&mut self) -> Result<(), String> {
-        if let Some(token) = self.next_token() {
-            match token.token_type {
-                TokenType::CloseBrace => Ok(()),
-                _ => Err(format!("Expected close brace, found {:?}", token.token_type)),
+    /// Comments are never returned by `next_token`; they are picked up by
+    /// `leading_comments` and `trailing_comments` or dropped.
+    fn is_skipped(token: &Token<'a>, significant_whitespace: bool) -> bool {
+        match token.token_type {
+            TokenType::Comment(_) => true,
+            TokenType::Whitespace => !significant_whitespace,
+            _ => false,
+        }
+    }
+
+    fn is_skipped_at(&mut self, n: usize) -> bool {
+        let significant_whitespace = self.significant_whitespace;
+        Self::is_skipped(self.lexer.peek_nth(n), significant_whitespace)
+    }
+
+    /// Consumes the comments in front of the next token.
+    fn leading_comments(&mut self) -> Vec<Comment<'a>> {
+        let mut comments = Vec::new();
+
+        while self.is_skipped_at(0) {
+            if let Some(comment) = Self::comment(self.lexer.next_token()) {
+                comments.push(comment);
             }
-        } else {
-            Err("Unexpected end of input".to_string())
         }
+
+        comments
     }
 
-    fn expect_colon(&mut self) -> Result<(), String> {
-        if let Some(token) = self.next_token() {
-            match token.token_type {
-                TokenType::Colon => Ok(()),
-                _ => Err(format!("Expected colon, found {:?}", token.token_type)),
+    /// Consumes the comments that follow the last token on the same line.
+    fn trailing_comments(&mut self) -> Vec<Comment<'a>> {
+        let mut comments = Vec::new();
+        let line = self.last_line;
+
+        loop {
+            let mut n = 0;
+            while self.lexer.peek_nth(n).token_type == TokenType::Whitespace {
+                n += 1;
+            }
+
+            let token = self.lexer.peek_nth(n);
+            if !matches!(token.token_type, TokenType::Comment(_)) || token.line != line {
+                break;
             }
+
+            for _ in 0..n {
+                self.lexer.next_token();
+            }
+            comments.extend(Self::comment(self.lexer.next_token()));
+        }
+
+        self.fill_lookahead();
+        comments
+    }
+
+    /// Consumes the closing `}` of a block and returns the comments that
+    /// come after the block's last child.
+    fn close_block(&mut self) -> Result<Vec<Comment<'a>>, ParseError> {
+        let dangling = self.leading_comments();
+        self.expect_close_brace()?;
+        Ok(dangling)
+    }
+
+    fn comment(token: Token<'a>) -> Option<Comment<'a>> {
+        match token.token_type {
+            TokenType::Comment(text) => Some(Comment {
+                text,
+                span: Span { start: token.position, end: token.position + token.length, line: token.line, column: token.column },
+            }),
+            _ => None,
+        }
+    }
+
+    /// Runs `parse` and rewinds to the current token if it fails, so that an
+    /// alternative can be tried.
+    fn try_parse<T>(&mut self, parse: impl FnOnce(&mut Self) -> Result<T, ParseError>) -> Result<T, ParseError> {
+        let checkpoint = self.lexer.checkpoint();
+        let (block_depth, last_end, last_line, diagnostics) = (self.block_depth, self.last_end, self.last_line, self.diagnostics.len());
+
+        let result = parse(self);
+
+        if result.is_ok() {
+            self.lexer.commit(checkpoint);
         } else {
-            Err("Unexpected end of input".to_string())
+            self.lexer.restore(checkpoint);
+            self.block_depth = block_depth;
+            self.last_end = last_end;
+            self.last_line = last_line;
+            self.diagnostics.truncate(diagnostics);
+            self.fill_lookahead();
+        }
+
+        result
+    }
+
+    /// Whether the next token is the identifier `keyword`, ignoring case.
+    fn peek_keyword(&self, keyword: &str) -> bool {
+        self.peek_token().is_some_and(|token| {
+            matches!(&token.token_type, TokenType::Identifier(name) if name.eq_ignore_ascii_case(keyword))
+        })
+    }
+
+    fn peek_is(&self, token_type: &TokenType) -> bool {
+        self.peek_token().is_some_and(|token| &token.token_type == token_type)
+    }
+
+    /// Error for the next token, which was not `expected`.
+    fn unexpected_here(&self, kind: ParseErrorKind, expected: impl Into<String>) -> ParseError {
+        match self.peek_token() {
+            Some(token) => ParseError::unexpected(kind, expected, token),
+            None => self.eof_error(expected),
         }
     }
 
-    pub fn parse_stylesheet(&mut self) -> Result<Stylesheet, String> {
+    /// Whether the next tokens are the function `name(`, with no whitespace
+    /// before the parenthesis.
+    fn peek_function(&mut self, name: &str) -> bool {
+        let mut n = 0;
+        while self.is_skipped_at(n) {
+            n += 1;
+        }
+
+        matches!(&self.lexer.peek_nth(n).token_type, TokenType::Identifier(identifier) if identifier.eq_ignore_ascii_case(name))
+            && self.lexer.peek_nth(n + 1).token_type == TokenType::OpenParen
+    }
+
+    /// Runs `parse` with whitespace tokens visible to it.
+    fn with_significant_whitespace<T>(&mut self, parse: impl FnOnce(&mut Self) -> T) -> T {
+        let previous = std::mem::replace(&mut self.significant_whitespace, true);
+        let result = parse(self);
+        self.significant_whitespace = previous;
+        self.fill_lookahead();
+        result
+    }
+
+    /// Runs `parse` as the contents of a style rule, where nested rules and
+    /// relative selectors are allowed.
+    fn with_style_rule<T>(&mut self, parse: impl FnOnce(&mut Self) -> T) -> T {
+        let previous = std::mem::replace(&mut self.in_style_rule, true);
+        let result = parse(self);
+        self.in_style_rule = previous;
+        result
+    }
+
+    /// Empty span at the upcoming token.
+    fn start_span(&self) -> Span {
+        match self.peek_token() {
+            Some(token) => Span { start: token.position, end: token.position, line: token.line, column: token.column },
+            None => Span { start: self.last_end, end: self.last_end, ..Span::default() },
+        }
+    }
+
+    /// Extends `start` up to the end of the last consumed token.
+    fn end_span(&self, start: Span) -> Span {
+        Span { end: self.last_end.max(start.start), ..start }
+    }
+
+    fn error_here(&self, kind: ParseErrorKind, expected: impl Into<String>) -> ParseError {
+        match self.peek_token() {
+            Some(token) => ParseError::at(kind, expected, token.line, token.column, token.length),
+            None => ParseError::at(kind, expected, 0, 0, 0),
+        }
+    }
+
+    fn eof_error(&self, expected: impl Into<String>) -> ParseError {
+        self.error_here(ParseErrorKind::UnexpectedEof, expected)
+    }
+
+    fn expect_open_brace(&mut self) -> Result<(), ParseError> {
+        let token = self.next_token();
+        match token.token_type {
+            TokenType::OpenBrace => Ok(()),
+            _ => Err(ParseError::unexpected(ParseErrorKind::UnexpectedToken, "'{'", &token)),
+        }
+    }
+
+    fn expect_close_brace(&mut self) -> Result<(), ParseError> {
+        let token = self.next_token();
+        match token.token_type {
+            TokenType::CloseBrace => Ok(()),
+            TokenType::EOF if self.recover => {
+                let error = ParseError::unexpected(ParseErrorKind::UnexpectedEof, "'}'", &token);
+                self.diagnostics.push(Diagnostic { error, recovery: Recovery::ClosedBlock });
+                Ok(())
+            },
+            _ => Err(ParseError::unexpected(ParseErrorKind::UnexpectedToken, "'}'", &token)),
+        }
+    }
+
+    fn expect_colon(&mut self) -> Result<(), ParseError> {
+        let token = self.next_token();
+        match token.token_type {
+            TokenType::Colon => Ok(()),
+            _ => Err(ParseError::unexpected(ParseErrorKind::UnexpectedToken, "':'", &token)),
+        }
+    }
+
+    /// Parses the whole input, following the CSS Syntax error handling rules
+    /// instead of stopping at the first error: an invalid declaration is dropped
+    /// up to the next `;`, an invalid rule up to its matching `}`.
+    pub fn parse_stylesheet_with_recovery(&mut self) -> (Stylesheet<'a>, Vec<Diagnostic>) {
+        self.recover = true;
+
+        // Errors are turned into diagnostics while recovering, so this only
+        // fails if a rule could not be skipped; the rules before it are kept.
+        let mut rules = Vec::new();
+        if let Err(error) = self.parse_rules(&mut rules) {
+            self.diagnostics.push(Diagnostic { error, recovery: Recovery::SkippedRule });
+        }
+
+        self.recover = false;
+        let comments = self.leading_comments();
+        (Stylesheet { rules, comments }, std::mem::take(&mut self.diagnostics))
+    }
+
+    pub fn parse_stylesheet(&mut self) -> Result<Stylesheet<'a>, ParseError> {
         let mut rules = Vec::new();
-        let mut at_rules = Vec::new();
-        let start_time = std::time::Instant::now();
 
+        self.parse_rules(&mut rules)?;
+        Ok(Stylesheet { rules, comments: self.leading_comments() })
+    }
+
+    /// Parses top-level rules up to the end of input.
+    fn parse_rules(&mut self, rules: &mut Vec<CssRule<'a>>) -> Result<(), ParseError> {
         while self.peek_token().is_some() &&
             !matches!(self.peek_token().unwrap().token_type, TokenType::EOF) {
 
+            let depth = self.block_depth;
+
             if let Some(token) = self.peek_token() {
-                match &token.token_type {
-                    TokenType::AtSymbol => {
-                        let at_rule = self.parse_at_rule()?;
-                        at_rules.push(at_rule);
-                        continue;
-                    },
-                    _ => {}
+                // A `}` without a matching `{`, e.g. after a rule that was
+                // closed twice.
+                if token.token_type == TokenType::CloseBrace && self.recover {
+                    let error = ParseError::unexpected(ParseErrorKind::UnexpectedToken, "rule", token);
+                    self.diagnostics.push(Diagnostic { error, recovery: Recovery::SkippedRule });
+                    self.next_token();
+                    continue;
+                }
+
+                if token.token_type == TokenType::AtSymbol {
+                    let at_rule = self.parse_at_rule();
+                    if let Some(at_rule) = self.recover_rule(at_rule, depth, true)? {
+                        rules.push(CssRule::from(at_rule));
+                    }
+                    continue;
                 }
             }
 
-            let rule = self.parse_rule()?;
-            rules.push(rule);
+            let rule = self.parse_rule();
+            if let Some(rule) = self.recover_rule(rule, depth, false)? {
+                rules.push(CssRule::Style(rule));
+            }
         }
 
-        let elapsed = start_time.elapsed();
-        println!("Parsed {} rules and {} at-rules in {:?}", rules.len(), at_rules.len(), elapsed);
-
-        Ok(Stylesheet { rules, at_rules })
+        Ok(())
     }
 
-    fn parse_rule(&mut self) -> Result<Rule, String> {
-        if let Lexer { mode, .. } = &mut self.lexer {
-            *mode = LexerMode::Selector;
+    /// Fails unless all input has been consumed.
+    pub fn expect_eof(&mut self) -> Result<(), ParseError> {
+        self.skip_whitespace_tokens();
+
+        match self.peek_token() {
+            Some(token) if token.token_type != TokenType::EOF => {
+                Err(ParseError::unexpected(ParseErrorKind::UnexpectedToken, "end of input", token))
+            },
+            _ => Ok(()),
         }
+    }
+
+    pub fn parse_rule(&mut self) -> Result<Rule<'a>, ParseError> {
+        let leading = self.leading_comments();
+        let selectors = self.parse_selector_list()?;
+        let span = selectors[0].span;
+
+        self.expect_open_brace()?;
+        let (declarations, rules) = self.with_style_rule(|parser| parser.parse_style_block())?;
+        let dangling = self.close_block()?;
+        let span = self.end_span(span);
+
+        Ok(Rule {
+            selectors,
+            declarations,
+            rules,
+            span,
+            comments: Comments { leading, trailing: self.trailing_comments(), dangling },
+        })
+    }
+
+    /// Parses a comma separated list of selectors, e.g. `a, .b > c`.
+    pub fn parse_selector_list(&mut self) -> Result<Vec<Selector<'a>>, ParseError> {
+        self.with_significant_whitespace(|parser| parser.parse_selectors())
+    }
 
+    fn parse_selectors(&mut self) -> Result<Vec<Selector<'a>>, ParseError> {
         self.skip_whitespace_tokens();
 
         let first_selector = self.parse_selector()?;
@@ -171,20 +492,297 @@ impl CssParser {
             }
         }
 
-        if let Lexer { mode, .. } = &mut self.lexer {
-            *mode = LexerMode::Normal;
+        Ok(selectors)
+    }
+
+    /// Parses the contents of a declaration block without the surrounding
+    /// braces, e.g. the value of a `style` attribute.
+    pub fn parse_declaration_list(&mut self) -> Result<Vec<Declaration<'a>>, ParseError> {
+        self.parse_declarations()
+    }
+
+    /// Parses the value of `property` as it would appear after the colon.
+    /// Custom properties may have an empty value, and only font families
+    /// join unquoted words after a comma into one name.
+    pub fn parse_value_for_property(&mut self, property: &str) -> Result<Value<'a>, ParseError> {
+        if property.starts_with("--") && self.peek_token().is_some_and(|token| {
+            matches!(token.token_type, TokenType::Semicolon | TokenType::CloseBrace | TokenType::ExclamationMark | TokenType::EOF)
+        }) {
+            return Ok(self.spanned(ValueKind::Literal("".into()), self.start_span()));
         }
 
-        self.expect_open_brace()?;
-        let declarations = self.parse_declarations()?;
-        self.expect_close_brace()?;
+        let font_families = property.eq_ignore_ascii_case("font-family") || property.eq_ignore_ascii_case("font");
+        self.parse_value_possibly_list(font_families)
+    }
 
-        Ok(Rule {
-            selectors,
-            declarations,
+    /// Parses a comma separated media query list, e.g. `screen and (max-width: 959px), print`,
+    /// up to `{`, `;` or the end of input.
+    pub fn parse_media_query_list(&mut self) -> Result<MediaQueryList<'a>, ParseError> {
+        let mut queries = Vec::new();
+
+        if self.peek_token().is_some_and(|token| matches!(token.token_type, TokenType::OpenBrace | TokenType::Semicolon | TokenType::EOF)) {
+            return Ok(MediaQueryList { queries });
+        }
+
+        loop {
+            queries.push(self.parse_media_query()?);
+
+            if self.peek_is(&TokenType::Comma) {
+                self.next_token();
+            } else {
+                break;
+            }
+        }
+
+        Ok(MediaQueryList { queries })
+    }
+
+    /// Parses an `@supports` condition, e.g. `(display: grid) and (not selector(:has(a)))`.
+    pub fn parse_supports_condition(&mut self) -> Result<SupportsCondition<'a>, ParseError> {
+        if self.peek_keyword("not") {
+            self.next_token();
+            return Ok(SupportsCondition::Not(Box::new(self.parse_supports_in_parens()?)));
+        }
+
+        let (mut conditions, operator) = self.parse_condition_chain(true, ParseErrorKind::InvalidSupportsCondition, Self::parse_supports_in_parens)?;
+
+        Ok(match operator {
+            None => conditions.remove(0),
+            Some(Operator::And) => SupportsCondition::And(conditions),
+            Some(Operator::Or) => SupportsCondition::Or(conditions),
         })
     }
 
+    /// Parses `(...)`, `selector(...)`, `font-tech(...)` or `font-format(...)`.
+    /// Other balanced parentheses are kept as `SupportsCondition::GeneralEnclosed`.
+    fn parse_supports_in_parens(&mut self) -> Result<SupportsCondition<'a>, ParseError> {
+        let function = match self.peek_token().map(|token| &token.token_type) {
+            Some(TokenType::OpenParen) => None,
+            Some(TokenType::Identifier(name)) => Some(name.to_ascii_lowercase()),
+            _ => return Err(self.unexpected_here(ParseErrorKind::InvalidSupportsCondition, "'(' or a function")),
+        };
+
+        let parsed = self.try_parse(|parser| {
+            if function.is_some() {
+                parser.next_token();
+            }
+            parser.expect_open_paren()?;
+
+            let condition = match function.as_deref() {
+                None if parser.peek_is(&TokenType::OpenParen) || parser.peek_keyword("not") => parser.parse_supports_condition()?,
+                None => {
+                    let property = match parser.next_token() {
+                        Token { token_type: TokenType::Identifier(name), .. } => name,
+                        token => return Err(ParseError::unexpected(ParseErrorKind::ExpectedPropertyName, "property name", &token)),
+                    };
+                    parser.expect_colon()?;
+                    let value = parser.parse_raw_text(|token_type, depth| depth == 0 && *token_type == TokenType::CloseParen);
+                    SupportsCondition::Declaration { property, value }
+                },
+                Some("selector") => SupportsCondition::Selector(parser.parse_selector_list()?),
+                Some(name @ ("font-tech" | "font-format")) => {
+                    let keyword = match parser.next_token() {
+                        Token { token_type: TokenType::Identifier(keyword), .. } => keyword,
+                        token => return Err(ParseError::unexpected(ParseErrorKind::ExpectedIdentifier, "font technology or format", &token)),
+                    };
+                    if name == "font-tech" { SupportsCondition::FontTech(keyword) } else { SupportsCondition::FontFormat(keyword) }
+                },
+                Some(_) => return Err(parser.unexpected_here(ParseErrorKind::InvalidSupportsCondition, "supports condition")),
+            };
+
+            parser.expect_close_paren()?;
+            Ok(condition)
+        });
+
+        if parsed.is_ok() {
+            return parsed;
+        }
+
+        let mut text = String::new();
+        if let TokenType::Identifier(name) = self.next_token().token_type {
+            text.push_str(&name);
+            self.expect_open_paren()?;
+        }
+        text.push('(');
+        text.push_str(&self.parse_raw_text(|token_type, depth| depth == 0 && *token_type == TokenType::CloseParen));
+        text.push(')');
+        self.expect_close_paren()?;
+
+        Ok(SupportsCondition::GeneralEnclosed(text))
+    }
+
+    /// Parses an `@container` prelude, e.g. `card (inline-size > 30em)`.
+    pub fn parse_container_condition(&mut self) -> Result<ContainerCondition<'a>, ParseError> {
+        let mut name = None;
+
+        if let Some(TokenType::Identifier(identifier)) = self.peek_token().map(|token| &token.token_type) {
+            let is_keyword = ["not", "and", "or", "none"].iter().any(|keyword| identifier.eq_ignore_ascii_case(keyword));
+            let identifier = identifier.clone();
+
+            if !is_keyword && !self.peek_function(&identifier) {
+                self.next_token();
+                name = Some(identifier);
+            }
+        }
+
+        let query = if self.peek_token().is_some_and(|token| matches!(token.token_type, TokenType::OpenBrace | TokenType::Semicolon | TokenType::EOF)) {
+            None
+        } else {
+            Some(self.parse_container_query()?)
+        };
+
+        Ok(ContainerCondition { name, query })
+    }
+
+    fn parse_container_query(&mut self) -> Result<ContainerQuery<'a>, ParseError> {
+        if self.peek_keyword("not") {
+            self.next_token();
+            return Ok(ContainerQuery::Not(Box::new(self.parse_container_query_in_parens()?)));
+        }
+
+        let (mut queries, operator) = self.parse_condition_chain(true, ParseErrorKind::InvalidContainerQuery, Self::parse_container_query_in_parens)?;
+
+        Ok(match operator {
+            None => queries.remove(0),
+            Some(Operator::And) => ContainerQuery::And(queries),
+            Some(Operator::Or) => ContainerQuery::Or(queries),
+        })
+    }
+
+    /// Parses `style(...)`, a parenthesized query or a size feature. Anything
+    /// else in balanced parentheses is kept as `ContainerQuery::GeneralEnclosed`.
+    fn parse_container_query_in_parens(&mut self) -> Result<ContainerQuery<'a>, ParseError> {
+        if self.peek_function("style") {
+            self.next_token();
+            self.expect_open_paren()?;
+            let query = self.parse_style_query()?;
+            self.expect_close_paren()?;
+            return Ok(ContainerQuery::Style(query));
+        }
+
+        if !self.peek_is(&TokenType::OpenParen) {
+            return Err(self.unexpected_here(ParseErrorKind::InvalidContainerQuery, "'(' or 'style('"));
+        }
+
+        let parsed = self.try_parse(|parser| {
+            parser.next_token();
+
+            let query = if parser.peek_is(&TokenType::OpenParen) || parser.peek_keyword("not") || parser.peek_function("style") {
+                parser.parse_container_query()?
+            } else {
+                ContainerQuery::Size(parser.parse_media_feature()?)
+            };
+
+            parser.expect_close_paren()?;
+            Ok(query)
+        });
+
+        if parsed.is_ok() {
+            return parsed;
+        }
+
+        self.next_token();
+        let text = self.parse_raw_text(|token_type, depth| depth == 0 && *token_type == TokenType::CloseParen);
+        self.expect_close_paren()?;
+
+        Ok(ContainerQuery::GeneralEnclosed(format!("({})", text)))
+    }
+
+    /// Parses the inside of `style(...)`: a declaration, a custom property
+    /// name, or parenthesized style queries joined by `not`, `and` or `or`.
+    fn parse_style_query(&mut self) -> Result<StyleQuery<'a>, ParseError> {
+        if self.peek_keyword("not") {
+            self.next_token();
+            return Ok(StyleQuery::Not(Box::new(self.parse_style_in_parens()?)));
+        }
+
+        if !self.peek_is(&TokenType::OpenParen) {
+            return self.parse_style_feature();
+        }
+
+        let (mut queries, operator) = self.parse_condition_chain(true, ParseErrorKind::InvalidContainerQuery, Self::parse_style_in_parens)?;
+
+        Ok(match operator {
+            None => queries.remove(0),
+            Some(Operator::And) => StyleQuery::And(queries),
+            Some(Operator::Or) => StyleQuery::Or(queries),
+        })
+    }
+
+    fn parse_style_in_parens(&mut self) -> Result<StyleQuery<'a>, ParseError> {
+        self.expect_open_paren()?;
+        let query = self.parse_style_query()?;
+        self.expect_close_paren()?;
+        Ok(query)
+    }
+
+    fn parse_style_feature(&mut self) -> Result<StyleQuery<'a>, ParseError> {
+        let property = match self.next_token() {
+            Token { token_type: TokenType::Identifier(name), .. } => name,
+            token => return Err(ParseError::unexpected(ParseErrorKind::ExpectedPropertyName, "property name", &token)),
+        };
+
+        if !self.peek_is(&TokenType::Colon) {
+            return Ok(StyleQuery::Property(property));
+        }
+
+        self.next_token();
+        let value = self.parse_raw_text(|token_type, depth| depth == 0 && *token_type == TokenType::CloseParen);
+        Ok(StyleQuery::Declaration { property, value })
+    }
+
+    /// Passes `result` through in strict mode. While recovering, records the
+    /// error and skips the rest of the rule that started at `depth`.
+    fn recover_rule<T>(&mut self, result: Result<T, ParseError>, depth: usize, is_at_rule: bool) -> Result<Option<T>, ParseError> {
+        match result {
+            Ok(value) => Ok(Some(value)),
+            Err(error) if self.recover => {
+                self.diagnostics.push(Diagnostic { error, recovery: Recovery::SkippedRule });
+                self.skip_invalid(depth, is_at_rule);
+                Ok(None)
+            },
+            Err(error) => Err(error),
+        }
+    }
+
+    fn recover_declaration(&mut self, result: Result<Declaration<'a>, ParseError>, depth: usize) -> Result<Option<Declaration<'a>>, ParseError> {
+        match result {
+            Ok(declaration) => Ok(Some(declaration)),
+            Err(error) if self.recover => {
+                self.diagnostics.push(Diagnostic { error, recovery: Recovery::SkippedDeclaration });
+                self.skip_invalid(depth, true);
+                Ok(None)
+            },
+            Err(error) => Err(error),
+        }
+    }
+
+    /// Consumes tokens until the construct that started at block `depth` is over:
+    /// its block was closed, or a `;` was reached when `stop_at_semicolon` is set.
+    /// The `}` of the enclosing block is left in place.
+    fn skip_invalid(&mut self, depth: usize, stop_at_semicolon: bool) {
+        let mut nesting = 0usize;
+
+        while let Some(token) = self.peek_token() {
+            match token.token_type {
+                TokenType::EOF => break,
+                TokenType::CloseBrace if self.block_depth <= depth => break,
+                TokenType::Semicolon if stop_at_semicolon && nesting == 0 && self.block_depth == depth => {
+                    self.next_token();
+                    break;
+                },
+                TokenType::OpenParen | TokenType::OpenBracket => nesting += 1,
+                TokenType::CloseParen | TokenType::CloseBracket => nesting = nesting.saturating_sub(1),
+                _ => {}
+            }
+
+            let token = self.next_token();
+            if token.token_type == TokenType::CloseBrace && self.block_depth == depth {
+                break;
+            }
+        }
+    }
+
     fn skip_whitespace_tokens(&mut self) {
         while let Some(token) = self.peek_token() {
             if matches!(token.token_type, TokenType::Whitespace) {
@@ -195,16 +793,14 @@ impl CssParser {
         }
     }
 
-    fn parse_at_rule(&mut self) -> Result<AtRule, String> {
+    fn parse_at_rule(&mut self) -> Result<AtRule<'a>, ParseError> {
+        let leading = self.leading_comments();
+        let span = self.start_span();
         self.next_token();
 
-        let rule_name = if let Some(token) = self.next_token() {
-            match &token.token_type {
-                TokenType::Identifier(name) => name.clone(),
-                _ => return Err(format!("Expected identifier after @, found {:?}", token.token_type)),
-            }
-        } else {
-            return Err("Unexpected end of input after @".to_string());
+        let rule_name = match self.next_token() {
+            Token { token_type: TokenType::Identifier(name), .. } => name,
+            token => return Err(ParseError::unexpected(ParseErrorKind::ExpectedIdentifier, "at-rule name", &token)),
         };
 
         let rule_type = if rule_name.eq_ignore_ascii_case("media") {
@@ -227,6 +823,8 @@ impl CssParser {
             AtRuleType::Namespace
         } else if rule_name.eq_ignore_ascii_case("page") {
             AtRuleType::Page
+        } else if MarginBox::from_name(&rule_name).is_some() {
+            AtRuleType::PageMargin
         } else if rule_name.eq_ignore_ascii_case("counter-style") {
             AtRuleType::CounterStyle
         } else if rule_name.eq_ignore_ascii_case("property") {
@@ -235,132 +833,789 @@ impl CssParser {
             AtRuleType::Layer
         } else if rule_name.eq_ignore_ascii_case("font-feature-values") {
             AtRuleType::FontFeatureValues
+        } else if FeatureType::from_name(&rule_name).is_some() {
+            AtRuleType::FontFeatureType
         } else if rule_name.eq_ignore_ascii_case("viewport")
             || rule_name.eq_ignore_ascii_case("-ms-viewport")
             || rule_name.eq_ignore_ascii_case("-webkit-viewport")
             || rule_name.eq_ignore_ascii_case("-moz-viewport") {
             AtRuleType::Viewport
+        } else if rule_name.eq_ignore_ascii_case("container") {
+            AtRuleType::Container
+        } else if rule_name.eq_ignore_ascii_case("scope") {
+            AtRuleType::Scope
+        } else if rule_name.eq_ignore_ascii_case("starting-style") {
+            AtRuleType::StartingStyle
+        } else {
+            AtRuleType::Unknown(rule_name.clone())
+        };
+
+        if let AtRuleType::Unknown(_) = rule_type {
+            return Ok(self.parse_unknown_at_rule(rule_type, rule_name, span, leading));
+        }
+
+        let simple_at_rules = [
+            AtRuleType::Charset,
+            AtRuleType::Import,
+            AtRuleType::Namespace,
+        ];
+
+        // `@layer a, b;` only declares the layers, `@layer a { ... }` has a block.
+        let is_layer_statement = rule_type == AtRuleType::Layer && !self.peek_block_ahead();
+
+        if simple_at_rules.contains(&rule_type) || is_layer_statement {
+            let (query, prelude) = match rule_type {
+                AtRuleType::Import => {
+                    let import = self.parse_import_prelude()?;
+                    (import.to_string(), AtRulePrelude::Import(import))
+                },
+                AtRuleType::Layer => {
+                    let names = self.parse_layer_names()?;
+                    let query = names.iter().map(LayerName::to_string).collect::<Vec<_>>().join(", ");
+                    (query, AtRulePrelude::LayerStatement(names))
+                },
+                AtRuleType::Namespace => {
+                    let namespace = self.parse_namespace_prelude()?;
+                    (namespace.to_string(), AtRulePrelude::Namespace(namespace))
+                },
+                _ => (self.parse_prelude(true), AtRulePrelude::None),
+            };
+
+            if let Some(token) = self.peek_token() {
+                if token.token_type == TokenType::Semicolon {
+                    self.next_token();
+                }
+            }
+
+            let span = self.end_span(span);
+            let comments = Comments { leading, trailing: self.trailing_comments(), dangling: Vec::new() };
+            return Ok(AtRule { rule_type, name: rule_name, query, prelude, rules: Vec::new(), block: None, span, comments });
+        }
+
+        let (query, prelude) = match rule_type {
+            AtRuleType::Media => {
+                let media = self.parse_media_query_list()?;
+                (media.to_string(), AtRulePrelude::Media(media))
+            },
+            AtRuleType::Supports => {
+                let condition = self.parse_supports_condition()?;
+                (condition.to_string(), AtRulePrelude::Supports(condition))
+            },
+            AtRuleType::Container => {
+                let condition = self.parse_container_condition()?;
+                (condition.to_string(), AtRulePrelude::Container(condition))
+            },
+            AtRuleType::Layer => {
+                let name = if self.peek_is(&TokenType::OpenBrace) { LayerName::default() } else { self.parse_layer_name()? };
+                (name.to_string(), AtRulePrelude::LayerBlock(name))
+            },
+            AtRuleType::Scope => {
+                let scope = self.parse_scope_range()?;
+                (scope.to_string(), AtRulePrelude::Scope(scope))
+            },
+            AtRuleType::Page => {
+                let selectors = self.parse_page_selectors()?;
+                let query = selectors.iter().map(PageSelector::to_string).collect::<Vec<_>>().join(", ");
+                (query, AtRulePrelude::Page(selectors))
+            },
+            AtRuleType::PageMargin => {
+                let margin_box = MarginBox::from_name(&rule_name).expect("checked when classifying the at-rule");
+                (String::new(), AtRulePrelude::MarginBox(margin_box))
+            },
+            AtRuleType::FontFeatureValues => {
+                let families = self.parse_font_families()?;
+                let query = families.iter().map(|family| quote_family(family)).collect::<Vec<_>>().join(", ");
+                (query, AtRulePrelude::FontFamilies(families))
+            },
+            AtRuleType::FontFeatureType => {
+                let feature_type = FeatureType::from_name(&rule_name).expect("checked when classifying the at-rule");
+                (String::new(), AtRulePrelude::FeatureType(feature_type))
+            },
+            _ => (self.parse_prelude(false), AtRulePrelude::None),
+        };
+
+        self.expect_open_brace()?;
+
+        let mut rules = Vec::new();
+        let mut dangling = Vec::new();
+
+        match rule_type {
+            AtRuleType::FontFace
+            | AtRuleType::CounterStyle
+            | AtRuleType::PageMargin
+            | AtRuleType::FontFeatureType
+            | AtRuleType::Property
+            | AtRuleType::Viewport => {
+                let block_span = self.start_span();
+                let declarations = self.parse_declarations()?;
+                let block_span = self.end_span(block_span);
+
+                rules.push(CssRule::Style(Self::declaration_block(declarations, block_span)));
+
+                dangling = self.close_block()?;
+            },
+
+            // Declarations followed by the page-margin rules of `@page` or the
+            // feature-type blocks of `@font-feature-values`.
+            AtRuleType::Page | AtRuleType::FontFeatureValues => {
+                let block_span = self.start_span();
+                let mut declarations = Vec::new();
+                let mut margin_rules = Vec::new();
+
+                loop {
+                    while self.peek_is(&TokenType::Semicolon) {
+                        self.next_token();
+                    }
+
+                    let depth = self.block_depth;
+
+                    match self.peek_token().map(|token| &token.token_type) {
+                        None => return Err(self.eof_error("'}'")),
+                        Some(TokenType::CloseBrace | TokenType::EOF) => break,
+                        Some(TokenType::AtSymbol) => {
+                            let margin_rule = self.parse_at_rule();
+                            if let Some(margin_rule) = self.recover_rule(margin_rule, depth, true)? {
+                                margin_rules.push(CssRule::from(margin_rule));
+                            }
+                        },
+                        Some(_) => {
+                            if let Some(declaration) = self.parse_block_declaration(depth)? {
+                                declarations.push(declaration);
+                            }
+                        },
+                    }
+                }
+
+                let block_span = self.end_span(block_span);
+                rules.push(CssRule::Style(Self::declaration_block(declarations, block_span)));
+                rules.extend(margin_rules);
+
+                dangling = self.close_block()?;
+            },
+
+            // Conditional rules nested in a style rule hold declarations too,
+            // as does `@scope`, where they apply to the scoping root.
+            _ if self.in_style_rule || rule_type == AtRuleType::Scope => {
+                let block_span = self.start_span();
+                let (declarations, nested_rules) = self.with_style_rule(|parser| parser.parse_style_block())?;
+                let block_span = self.end_span(block_span);
+
+                if !declarations.is_empty() {
+                    rules.push(CssRule::Style(Self::declaration_block(declarations, block_span)));
+                }
+                rules.extend(nested_rules);
+
+                dangling = self.close_block()?;
+            },
+
+            AtRuleType::Keyframes => {
+                while let Some(token) = self.peek_token() {
+                    let depth = self.block_depth;
+
+                    match &token.token_type {
+                        TokenType::CloseBrace | TokenType::EOF => {
+                            dangling = self.close_block()?;
+                            break;
+                        },
+                        _ => {
+                            let rule = self.parse_keyframe_rule();
+                            if let Some(rule) = self.recover_rule(rule, depth, false)? {
+                                rules.push(CssRule::Style(rule));
+                            }
+                        }
+                    }
+                }
+            },
+
+            _ => {
+                while let Some(token) = self.peek_token() {
+                    let depth = self.block_depth;
+
+                    match &token.token_type {
+                        TokenType::CloseBrace | TokenType::EOF => {
+                            dangling = self.close_block()?;
+                            break;
+                        },
+                        TokenType::AtSymbol => {
+                            let nested_at_rule = self.parse_at_rule();
+                            if let Some(nested_at_rule) = self.recover_rule(nested_at_rule, depth, true)? {
+                                rules.push(CssRule::from(nested_at_rule));
+                            }
+                        },
+                        _ => {
+                            let rule = self.parse_rule();
+                            if let Some(rule) = self.recover_rule(rule, depth, false)? {
+                                rules.push(CssRule::Style(rule));
+                            }
+                        }
+                    }
+                }
+            }
+        }
+
+        let span = self.end_span(span);
+        let at_rule = AtRule {
+            rule_type,
+            name: rule_name,
+            query,
+            prelude,
+            rules,
+            block: None,
+            span,
+            comments: Comments { leading, trailing: self.trailing_comments(), dangling },
+        };
+
+        Ok(at_rule)
+    }
+
+    /// Keeps an at-rule the parser does not know, such as `@tailwind base;`,
+    /// as component values up to its `;` or the end of its block. It ends
+    /// early at the `}` of the enclosing block, which is left in place.
+    fn parse_unknown_at_rule(&mut self, rule_type: AtRuleType<'a>, name: Cow<'a, str>, span: Span, leading: Vec<Comment<'a>>) -> AtRule<'a> {
+        let start = self.last_end;
+        let depth = self.block_depth;
+        let mut nesting = 0usize;
+
+        while let Some(token) = self.peek_token() {
+            match token.token_type {
+                TokenType::EOF => break,
+                TokenType::CloseBrace if self.block_depth == depth => break,
+                TokenType::Semicolon if nesting == 0 && self.block_depth == depth => {
+                    self.next_token();
+                    break;
+                },
+                TokenType::OpenParen | TokenType::OpenBracket => nesting += 1,
+                TokenType::CloseParen | TokenType::CloseBracket => nesting = nesting.saturating_sub(1),
+                _ => {}
+            }
+
+            let token = self.next_token();
+            if token.token_type == TokenType::CloseBrace && self.block_depth == depth {
+                break;
+            }
+        }
+
+        let source = &self.lexer.input()[start..self.last_end.max(start)];
+        let (prelude, block) = split_at_rule(ComponentValue::parse_list(source));
+
+        AtRule {
+            rule_type,
+            name,
+            query: to_css(trim_whitespace(&prelude)),
+            prelude: AtRulePrelude::Unknown(prelude),
+            rules: Vec::new(),
+            block,
+            span: self.end_span(span),
+            comments: Comments { leading, trailing: self.trailing_comments(), dangling: Vec::new() },
+        }
+    }
+
+    /// Declarations that are not inside a style rule of their own, stored as
+    /// a rule without selectors.
+    fn declaration_block(declarations: Vec<Declaration<'a>>, span: Span) -> Rule<'a> {
+        Rule {
+            selectors: vec![Selector { groups: vec![], combinators: vec![], leading_combinator: None, span }],
+            declarations,
+            rules: Vec::new(),
+            span,
+            comments: Comments::default(),
+        }
+    }
+
+    /// Collects the at-rule prelude up to `{` (or `;` when `stop_at_semicolon`
+    /// is set) as normalized CSS text.
+    fn parse_prelude(&mut self, stop_at_semicolon: bool) -> String {
+        self.parse_raw_text(|token_type, _| stop_at_semicolon && *token_type == TokenType::Semicolon)
+    }
+
+    /// Collects tokens as normalized CSS text up to `{`, the end of input or
+    /// a token for which `stop` returns true, given the parenthesis depth.
+    fn parse_raw_text(&mut self, stop: impl Fn(&TokenType<'a>, usize) -> bool) -> String {
+        let mut prelude = String::new();
+        let mut previous: Option<TokenType> = None;
+        let mut depth = 0usize;
+
+        while let Some(token) = self.peek_token() {
+            match &token.token_type {
+                TokenType::OpenBrace | TokenType::EOF => break,
+                token_type if stop(token_type, depth) => break,
+                _ => {
+                    let token = self.next_token();
+                    match token.token_type {
+                        TokenType::OpenParen => depth += 1,
+                        TokenType::CloseParen => depth = depth.saturating_sub(1),
+                        _ => {}
+                    }
+
+                    let text = match &token.token_type {
+                        TokenType::Identifier(name) => name.to_string(),
+                        TokenType::String(text) => format!("\"{}\"", text),
+                        TokenType::Number(num) => num.to_string(),
+                        TokenType::Unit(unit) => unit.to_string(),
+                        TokenType::UnicodeRange(range) => range.to_string(),
+                        TokenType::Whitespace => continue,
+                        other => other.to_string(),
+                    };
+
+                    let attach = match (&previous, &token.token_type) {
+                        (None, _) => true,
+                        (Some(TokenType::OpenParen), _) => true,
+                        (_, TokenType::CloseParen | TokenType::Comma | TokenType::Colon | TokenType::Unit(_)) => true,
+                        (Some(TokenType::Identifier(name)), TokenType::OpenParen) => {
+                            !["and", "or", "not", "only"].contains(&name.to_lowercase().as_str())
+                        },
+                        _ => false,
+                    };
+
+                    if !attach {
+                        prelude.push(' ');
+                    }
+
+                    prelude.push_str(&text);
+                    previous = Some(token.token_type);
+                }
+            }
+        }
+
+        prelude
+    }
+
+    /// Parses operands joined by `and`, or by `or` when `allow_or` is set, as
+    /// in media, supports and container conditions. Mixing both operators
+    /// requires parentheses and fails with `kind`.
+    fn parse_condition_chain<T>(
+        &mut self,
+        allow_or: bool,
+        kind: ParseErrorKind,
+        mut parse_operand: impl FnMut(&mut Self) -> Result<T, ParseError>,
+    ) -> Result<(Vec<T>, Option<Operator>), ParseError> {
+        let mut operands = vec![parse_operand(self)?];
+        let mut operator = None;
+
+        loop {
+            let next = if self.peek_keyword("and") {
+                Operator::And
+            } else if self.peek_keyword("or") && allow_or {
+                Operator::Or
+            } else {
+                break;
+            };
+
+            if operator.is_some_and(|operator| operator != next) {
+                return Err(self.unexpected_here(kind, "parentheses around mixed 'and' and 'or'"));
+            }
+            operator = Some(next);
+
+            self.next_token();
+            operands.push(parse_operand(self)?);
+        }
+
+        Ok((operands, operator))
+    }
+
+    fn parse_import_prelude(&mut self) -> Result<ImportRule<'a>, ParseError> {
+        let url = self.parse_url_or_string()?;
+
+        let mut layer = None;
+        if self.peek_keyword("layer") {
+            self.next_token();
+
+            if self.peek_is(&TokenType::OpenParen) {
+                self.next_token();
+                layer = Some(self.parse_layer_name()?);
+                self.expect_close_paren()?;
+            } else {
+                layer = Some(LayerName::default());
+            }
+        }
+
+        let mut supports = None;
+        if self.peek_keyword("supports") {
+            self.next_token();
+            self.expect_open_paren()?;
+
+            // `supports()` takes either a condition or a bare declaration.
+            let condition = match self.try_parse(|parser| parser.parse_supports_condition()) {
+                Ok(condition) => condition,
+                Err(_) => {
+                    let property = match self.next_token() {
+                        Token { token_type: TokenType::Identifier(name), .. } => name,
+                        token => return Err(ParseError::unexpected(ParseErrorKind::InvalidSupportsCondition, "supports condition", &token)),
+                    };
+                    self.expect_colon()?;
+                    let value = self.parse_raw_text(|token_type, depth| depth == 0 && *token_type == TokenType::CloseParen);
+                    SupportsCondition::Declaration { property, value }
+                },
+            };
+
+            self.expect_close_paren()?;
+            supports = Some(condition);
+        }
+
+        let media = self.parse_media_query_list()?;
+
+        Ok(ImportRule { url, layer, supports, media })
+    }
+
+    /// Parses `url(...)` or a string, as in `@import` and `@namespace`.
+    fn parse_url_or_string(&mut self) -> Result<Cow<'a, str>, ParseError> {
+        match self.next_token() {
+            Token { token_type: TokenType::String(url), .. } => Ok(url),
+            Token { token_type: TokenType::Identifier(name), .. } if name.eq_ignore_ascii_case("url") => {
+                self.expect_open_paren()?;
+                let url = match self.peek_token().map(|token| &token.token_type) {
+                    Some(TokenType::String(url)) => {
+                        let url = unquote(url.clone());
+                        self.next_token();
+                        url
+                    },
+                    _ => Cow::Borrowed(""),
+                };
+                self.expect_close_paren()?;
+                Ok(url)
+            },
+            token => Err(ParseError::unexpected(ParseErrorKind::UnexpectedToken, "url or string", &token)),
+        }
+    }
+
+    /// Parses the prefix and URI of a `@namespace` rule.
+    fn parse_namespace_prelude(&mut self) -> Result<NamespaceRule<'a>, ParseError> {
+        let is_url = self.peek_function("url");
+        let prefix = match self.peek_token().map(|token| &token.token_type) {
+            Some(TokenType::Identifier(name)) if !is_url => {
+                let prefix = name.clone();
+                self.next_token();
+                Some(prefix)
+            },
+            _ => None,
+        };
+
+        let url = self.parse_url_or_string()?;
+        Ok(NamespaceRule { prefix, url })
+    }
+
+    /// Parses the comma separated selectors of an `@page` rule, e.g.
+    /// `invoice:first, :left`, up to `{`.
+    fn parse_page_selectors(&mut self) -> Result<Vec<PageSelector<'a>>, ParseError> {
+        let mut selectors = Vec::new();
+
+        while !self.peek_is(&TokenType::OpenBrace) {
+            if !selectors.is_empty() {
+                if !self.peek_is(&TokenType::Comma) {
+                    return Err(self.unexpected_here(ParseErrorKind::UnexpectedToken, "',' or '{'"));
+                }
+                self.next_token();
+            }
+
+            let mut selector = PageSelector::default();
+
+            if let Some(TokenType::Identifier(name)) = self.peek_token().map(|token| &token.token_type) {
+                selector.name = Some(name.clone());
+                self.next_token();
+            }
+
+            while self.peek_is(&TokenType::Colon) {
+                self.next_token();
+
+                let expected = "'first', 'left', 'right' or 'blank'";
+                let token = self.next_token();
+                let pseudo_class = match &token.token_type {
+                    TokenType::Identifier(name) => PagePseudoClass::from_name(name),
+                    _ => None,
+                };
+                selector.pseudo_classes.push(pseudo_class.ok_or_else(|| ParseError::unexpected(ParseErrorKind::InvalidSelector, expected, &token))?);
+            }
+
+            if selector == PageSelector::default() {
+                return Err(self.unexpected_here(ParseErrorKind::InvalidSelector, "page selector"));
+            }
+            selectors.push(selector);
+        }
+
+        Ok(selectors)
+    }
+
+    /// Parses the family names of `@font-feature-values`, e.g.
+    /// `Font One, "Font Two"`.
+    fn parse_font_families(&mut self) -> Result<Vec<Cow<'a, str>>, ParseError> {
+        let mut families = Vec::new();
+
+        loop {
+            let family = match self.peek_token().map(|token| &token.token_type) {
+                Some(TokenType::String(name)) => {
+                    let name = name.clone();
+                    self.next_token();
+                    name
+                },
+                // Unquoted names are sequences of identifiers.
+                Some(TokenType::Identifier(_)) => {
+                    let mut words = Vec::new();
+                    while let Some(TokenType::Identifier(word)) = self.peek_token().map(|token| &token.token_type) {
+                        words.push(word.clone());
+                        self.next_token();
+                    }
+                    if words.len() == 1 { words.remove(0) } else { Cow::Owned(words.join(" ")) }
+                },
+                _ => return Err(self.unexpected_here(ParseErrorKind::UnexpectedToken, "font family name")),
+            };
+            families.push(family);
+
+            if !self.peek_is(&TokenType::Comma) {
+                break;
+            }
+            self.next_token();
+        }
+
+        Ok(families)
+    }
+
+    /// Parses an `@scope` prelude: `(<scope-start>)? [to (<scope-end>)]?`.
+    fn parse_scope_range(&mut self) -> Result<ScopeRange<'a>, ParseError> {
+        let mut scope = ScopeRange::default();
+
+        if self.peek_is(&TokenType::OpenParen) {
+            self.next_token();
+            scope.start = self.parse_selector_list()?;
+            self.expect_close_paren()?;
+        }
+
+        if self.peek_keyword("to") {
+            self.next_token();
+            self.expect_open_paren()?;
+            scope.end = self.parse_selector_list()?;
+            self.expect_close_paren()?;
+        }
+
+        Ok(scope)
+    }
+
+    /// Parses the comma separated names of a `@layer` statement.
+    fn parse_layer_names(&mut self) -> Result<Vec<LayerName<'a>>, ParseError> {
+        let mut names = vec![self.parse_layer_name()?];
+
+        while self.peek_is(&TokenType::Comma) {
+            self.next_token();
+            names.push(self.parse_layer_name()?);
+        }
+
+        Ok(names)
+    }
+
+    /// Parses a dotted layer name such as `framework.base`.
+    fn parse_layer_name(&mut self) -> Result<LayerName<'a>, ParseError> {
+        let mut parts = Vec::new();
+
+        loop {
+            match self.next_token() {
+                Token { token_type: TokenType::Identifier(name), .. } => parts.push(name),
+                token => return Err(ParseError::unexpected(ParseErrorKind::ExpectedIdentifier, "layer name", &token)),
+            }
+
+            if self.peek_is(&TokenType::Dot) {
+                self.next_token();
+            } else {
+                return Ok(LayerName { parts });
+            }
+        }
+    }
+
+    fn parse_media_query(&mut self) -> Result<MediaQuery<'a>, ParseError> {
+        let mut qualifier = None;
+
+        if self.peek_keyword("not") {
+            self.next_token();
+
+            // `not (...)` negates a condition, `not screen` the whole query.
+            if self.peek_is(&TokenType::OpenParen) {
+                let condition = self.parse_media_in_parens()?;
+                return Ok(MediaQuery { qualifier: None, media_type: None, condition: Some(MediaCondition::Not(Box::new(condition))) });
+            }
+
+            qualifier = Some(MediaQualifier::Not);
+        } else if self.peek_keyword("only") {
+            self.next_token();
+            qualifier = Some(MediaQualifier::Only);
+        } else if !matches!(self.peek_token().map(|token| &token.token_type), Some(TokenType::Identifier(_))) {
+            let condition = self.parse_media_condition(true)?;
+            return Ok(MediaQuery { qualifier: None, media_type: None, condition: Some(condition) });
+        }
+
+        let media_type = match self.peek_token().map(|token| &token.token_type) {
+            Some(TokenType::Identifier(name)) if !["and", "or", "not", "only", "layer"].iter().any(|keyword| name.eq_ignore_ascii_case(keyword)) => {
+                let name = name.clone();
+                self.next_token();
+                name
+            },
+            _ => return Err(self.unexpected_here(ParseErrorKind::InvalidMediaQuery, "media type")),
+        };
+
+        let condition = if self.peek_keyword("and") {
+            self.next_token();
+            Some(self.parse_media_condition(false)?)
         } else {
-            AtRuleType::Unknown(rule_name.clone())
+            None
         };
 
-        let simple_at_rules = [
-            AtRuleType::Charset,
-            AtRuleType::Import,
-            AtRuleType::Namespace,
-        ];
+        Ok(MediaQuery { qualifier, media_type: Some(media_type), condition })
+    }
 
-        if simple_at_rules.contains(&rule_type) {
-            let mut query = String::new();
+    /// Parses `not (...)` or `(...)` joined by `and`, or by `or` when `allow_or`
+    /// is set. Mixing `and` and `or` requires parentheses.
+    fn parse_media_condition(&mut self, allow_or: bool) -> Result<MediaCondition<'a>, ParseError> {
+        if self.peek_keyword("not") {
+            self.next_token();
+            return Ok(MediaCondition::Not(Box::new(self.parse_media_in_parens()?)));
+        }
 
-            while let Some(token) = self.peek_token() {
-                match &token.token_type {
-                    TokenType::Semicolon => {
-                        self.next_token();
-                        break;
-                    },
-                    _ => {
-                        let token = self.next_token().unwrap();
-                        match &token.token_type {
-                            TokenType::String(text) => query.push_str(&format!("\"{}\"", text)),
-                            TokenType::Identifier(name) => query.push_str(name),
-                            _ => query.push_str(&format!("{} ", token.token_type)),
-                        }
-                    }
-                }
-            }
+        let (mut conditions, operator) = self.parse_condition_chain(allow_or, ParseErrorKind::InvalidMediaQuery, Self::parse_media_in_parens)?;
 
-            return Ok(AtRule { rule_type, name: rule_name, query: query.trim().to_string(), rules: Vec::new(), at_rules: Vec::new() });
+        Ok(match operator {
+            None => conditions.remove(0),
+            Some(Operator::And) => MediaCondition::And(conditions),
+            Some(Operator::Or) => MediaCondition::Or(conditions),
+        })
+    }
+
+    /// Parses a parenthesized condition or feature. Anything else in balanced
+    /// parentheses is kept as `MediaCondition::GeneralEnclosed`.
+    fn parse_media_in_parens(&mut self) -> Result<MediaCondition<'a>, ParseError> {
+        if !self.peek_is(&TokenType::OpenParen) {
+            return Err(self.unexpected_here(ParseErrorKind::InvalidMediaQuery, "'('"));
         }
 
-        let mut query = String::new();
+        let parsed = self.try_parse(|parser| {
+            parser.next_token();
 
-        while let Some(token) = self.peek_token() {
-            match &token.token_type {
-                TokenType::OpenBrace => break,
-                _ => {
-                    let token = self.next_token().unwrap();
-                    match &token.token_type {
-                        TokenType::String(text) => query.push_str(&format!("\"{}\"", text)),
-                        TokenType::Identifier(name) => query.push_str(name),
-                        _ => query.push_str(&format!("{} ", token.token_type)),
-                    }
-                }
-            }
+            let condition = if parser.peek_is(&TokenType::OpenParen) || parser.peek_keyword("not") {
+                parser.parse_media_condition(true)?
+            } else {
+                MediaCondition::Feature(parser.parse_media_feature()?)
+            };
+
+            parser.expect_close_paren()?;
+            Ok(condition)
+        });
+
+        if parsed.is_ok() {
+            return parsed;
         }
 
-        query = query.trim().to_string();
+        self.next_token();
+        let text = self.parse_raw_text(|token_type, depth| depth == 0 && *token_type == TokenType::CloseParen);
+        self.expect_close_paren()?;
 
-        self.expect_open_brace()?;
+        Ok(MediaCondition::GeneralEnclosed(format!("({})", text)))
+    }
 
-        let mut rules = Vec::new();
-        let mut nested_at_rules = Vec::new();
+    /// Parses the inside of `(min-width: 600px)`, `(color)` or a range such as
+    /// `(400px <= width < 700px)`.
+    fn parse_media_feature(&mut self) -> Result<MediaFeature<'a>, ParseError> {
+        if let Some(TokenType::Identifier(name)) = self.peek_token().map(|token| &token.token_type) {
+            let name = name.clone();
+            self.next_token();
 
-        match rule_type {
-            AtRuleType::FontFace | AtRuleType::Page | AtRuleType::Property | AtRuleType::Viewport => {
-                let declarations = self.parse_declarations()?;
+            if self.peek_is(&TokenType::CloseParen) {
+                return Ok(MediaFeature::Boolean(name));
+            }
 
-                let rule = Rule {
-                    selectors: vec![Selector { groups: vec![], combinators: vec![] }],
-                    declarations,
-                };
+            if self.peek_is(&TokenType::Colon) {
+                self.next_token();
+                let value = self.parse_media_feature_value()?;
+                return Ok(MediaFeature::Plain { name, value });
+            }
 
-                rules.push(rule);
+            let comparison = self.parse_media_comparison()?;
+            let value = self.parse_media_feature_value()?;
+            return Ok(MediaFeature::Range { name, start: None, end: Some((comparison, value)) });
+        }
 
-                self.expect_close_brace()?;
-            },
+        let start = self.parse_media_feature_value()?;
+        let start_comparison = self.parse_media_comparison()?;
 
-            AtRuleType::Keyframes => {
-                while let Some(token) = self.peek_token() {
-                    match &token.token_type {
-                        TokenType::CloseBrace => {
-                            self.next_token();
-                            break;
-                        },
-                        _ => {
-                            let rule = self.parse_keyframe_rule()?;
-                            rules.push(rule);
-                        }
-                    }
-                }
-            },
+        let name = match self.next_token() {
+            Token { token_type: TokenType::Identifier(name), .. } => name,
+            token => return Err(ParseError::unexpected(ParseErrorKind::InvalidMediaQuery, "media feature name", &token)),
+        };
 
-            _ => {
-                while let Some(token) = self.peek_token() {
-                    match &token.token_type {
-                        TokenType::CloseBrace => {
-                            self.next_token();
-                            break;
-                        },
-                        TokenType::AtSymbol => {
-                            let nested_at_rule = self.parse_at_rule()?;
-                            nested_at_rules.push(nested_at_rule);
-                        },
-                        _ => {
-                            let rule = self.parse_rule()?;
-                            rules.push(rule);
-                        }
-                    }
-                }
+        let end = if self.peek_token().is_some_and(|token| matches!(token.token_type, TokenType::LessThan | TokenType::GreaterThan | TokenType::Equals)) {
+            let end_comparison = self.parse_media_comparison()?;
+
+            // Both bounds must point the same way, e.g. `a < width <= b`.
+            let is_less = |comparison| matches!(comparison, MediaComparison::Lt | MediaComparison::Le);
+            if start_comparison == MediaComparison::Eq || end_comparison == MediaComparison::Eq || is_less(start_comparison) != is_less(end_comparison) {
+                return Err(self.unexpected_here(ParseErrorKind::InvalidMediaQuery, "matching range comparisons"));
             }
-        }
 
-        let at_rule = AtRule {
-            rule_type,
-            name: rule_name,
-            query,
-            rules,
-            at_rules: nested_at_rules,
+            Some((end_comparison, self.parse_media_feature_value()?))
+        } else {
+            None
         };
 
-        Ok(at_rule)
+        Ok(MediaFeature::Range { name, start: Some((start, start_comparison)), end })
+    }
+
+    fn parse_media_comparison(&mut self) -> Result<MediaComparison, ParseError> {
+        let comparison = match self.peek_token().map(|token| &token.token_type) {
+            Some(TokenType::LessThan) => MediaComparison::Lt,
+            Some(TokenType::GreaterThan) => MediaComparison::Gt,
+            Some(TokenType::Equals) => MediaComparison::Eq,
+            _ => return Err(self.unexpected_here(ParseErrorKind::InvalidMediaQuery, "':', '<', '>' or '='")),
+        };
+        self.next_token();
+
+        if comparison != MediaComparison::Eq && self.peek_is(&TokenType::Equals) {
+            self.next_token();
+            return Ok(if comparison == MediaComparison::Lt { MediaComparison::Le } else { MediaComparison::Ge });
+        }
+
+        Ok(comparison)
+    }
+
+    fn parse_media_feature_value(&mut self) -> Result<MediaFeatureValue<'a>, ParseError> {
+        let token = self.next_token();
+
+        match token.token_type {
+            TokenType::Identifier(name) => Ok(MediaFeatureValue::Ident(name)),
+            TokenType::Number(value) => {
+                if let Some(TokenType::Unit(unit)) = self.peek_token().map(|token| &token.token_type) {
+                    let unit = unit.clone();
+                    self.next_token();
+                    return Ok(MediaFeatureValue::Dimension(value, unit));
+                }
+
+                if self.peek_is(&TokenType::Slash) {
+                    self.next_token();
+                    return match self.next_token() {
+                        Token { token_type: TokenType::Number(denominator), .. } => Ok(MediaFeatureValue::Ratio(value, denominator)),
+                        token => Err(ParseError::unexpected(ParseErrorKind::ExpectedNumber, "ratio denominator", &token)),
+                    };
+                }
+
+                Ok(MediaFeatureValue::Number(value))
+            },
+            _ => Err(ParseError::unexpected(ParseErrorKind::InvalidMediaQuery, "media feature value", &token)),
+        }
     }
 
-    fn parse_selector(&mut self) -> Result<Selector, String> {
+    fn parse_selector(&mut self) -> Result<Selector<'a>, ParseError> {
         let mut groups = Vec::new();
         let mut combinators = Vec::new();
+        let start = self.start_span();
+
+        // Nested rules may start with a combinator, relative to the parent rule.
+        let leading_combinator = match self.peek_token().map(|token| &token.token_type) {
+            Some(TokenType::GreaterThan) if self.in_style_rule => Some(SelectorCombinator::Child),
+            Some(TokenType::Plus) if self.in_style_rule => Some(SelectorCombinator::AdjacentSibling),
+            Some(TokenType::Tilde) if self.in_style_rule => Some(SelectorCombinator::GeneralSibling),
+            _ => None,
+        };
+        if leading_combinator.is_some() {
+            self.next_token();
+            self.skip_whitespace_tokens();
+        }
 
         let first_group = self.parse_selector_group()?;
+        let span = if leading_combinator.is_some() { start } else { first_group.span };
         groups.push(first_group);
 
         while let Some(token) = self.peek_token() {
@@ -372,7 +1627,7 @@ impl CssParser {
                         match &next_token.token_type {
                             TokenType::Identifier(_) | TokenType::Dot | TokenType::Hash |
                             TokenType::Colon | TokenType::DoubleColon | TokenType::Asterisk |
-                            TokenType::OpenBracket => {
+                            TokenType::OpenBracket | TokenType::Ampersand => {
                                 combinators.push(SelectorCombinator::Descendant);
                                 let next_group = self.parse_selector_group()?;
                                 groups.push(next_group);
@@ -426,7 +1681,7 @@ impl CssParser {
                     let next_group = self.parse_selector_group()?;
                     groups.push(next_group);
                 },
-                TokenType::OpenBrace | TokenType::Comma => break,
+                TokenType::OpenBrace | TokenType::Comma | TokenType::CloseParen | TokenType::EOF => break,
                 _ => {
                     let next_group = self.parse_selector_group()?;
                     groups.push(next_group);
@@ -434,11 +1689,11 @@ impl CssParser {
             }
         }
 
-        Ok(Selector { groups, combinators })
+        Ok(Selector { groups, combinators, leading_combinator, span: self.end_span(span) })
     }
 
-    fn parse_selector_group(&mut self) -> Result<SelectorGroup, String> {
-        let mut parts = Vec::new();
+    fn parse_selector_group(&mut self) -> Result<SelectorGroup<'a>, ParseError> {
+        let mut parts: Vec<SelectorPart<'a>> = Vec::new();
         let mut found_part = false;
 
         while let Some(token) = self.peek_token() {
@@ -454,9 +1709,10 @@ impl CssParser {
                 },
                 TokenType::Identifier(_) | TokenType::Dot | TokenType::Hash |
                 TokenType::Colon | TokenType::DoubleColon | TokenType::Asterisk |
-                TokenType::OpenBracket => {
-                    let part = self.parse_selector_part(true)?;
-                    parts.push(part);
+                TokenType::OpenBracket | TokenType::Ampersand | TokenType::Pipe => {
+                    let span = self.start_span();
+                    let kind = self.parse_selector_part(true)?;
+                    parts.push(SelectorPart { kind, span: self.end_span(span) });
                     found_part = true;
                 },
                 _ => break,
@@ -464,13 +1720,15 @@ impl CssParser {
         }
 
         if parts.is_empty() {
-            return Err("Expected at least one selector part".to_string());
+            return Err(self.error_here(ParseErrorKind::InvalidSelector, "selector"));
         }
 
-        Ok(SelectorGroup { parts })
+        let span = Span { end: parts[parts.len() - 1].span.end, ..parts[0].span };
+
+        Ok(SelectorGroup { parts, span })
     }
 
-    fn parse_selector_part(&mut self, allow_element: bool) -> Result<SelectorPart, String> {
+    fn parse_selector_part(&mut self, allow_element: bool) -> Result<SelectorPartKind<'a>, ParseError> {
         if let Some(token) = self.peek_token() {
             match &token.token_type {
                 TokenType::Dot => {
@@ -480,108 +1738,112 @@ impl CssParser {
                         if matches!(next_token.token_type, TokenType::Backslash) {
                             self.next_token();
 
-                            if let Some(escaped_token) = self.next_token() {
-                                match &escaped_token.token_type {
-                                    TokenType::Identifier(name) => {
-                                        return Ok(SelectorPart::Class(format!("\\{}", name)));
-                                    },
-                                    TokenType::ExclamationMark => {
-                                        if let Some(after_exclamation) = self.next_token() {
-                                            if let TokenType::Identifier(name) = after_exclamation.token_type {
-                                                return Ok(SelectorPart::Class(format!("\\!{}", name)));
-                                            } else {
-                                                return Ok(SelectorPart::Class("\\!".to_string()));
-                                            }
-                                        } else {
-                                            return Ok(SelectorPart::Class("\\!".to_string()));
-                                        }
-                                    },
-                                    _ => {
-                                        let char_repr = format!("{}", escaped_token.token_type);
-                                        return Ok(SelectorPart::Class(format!("\\{}", char_repr)));
+                            let escaped_token = self.next_token();
+                            match &escaped_token.token_type {
+                                TokenType::Identifier(name) => {
+                                    return Ok(SelectorPartKind::Class(format!("\\{}", name).into()));
+                                },
+                                TokenType::ExclamationMark => {
+                                    if let TokenType::Identifier(name) = self.next_token().token_type {
+                                        return Ok(SelectorPartKind::Class(format!("\\!{}", name).into()));
+                                    } else {
+                                        return Ok(SelectorPartKind::Class("\\!".into()));
                                     }
+                                },
+                                _ => {
+                                    let char_repr = format!("{}", escaped_token.token_type);
+                                    return Ok(SelectorPartKind::Class(format!("\\{}", char_repr).into()));
                                 }
                             }
                         }
                     }
 
-                    match self.next_token() {
-                        Some(token) => {
-                            if let TokenType::Identifier(name) = token.token_type {
-                                Ok(SelectorPart::Class(name))
-                            } else {
-                                Err(format!("Expected identifier after dot, found {:?}", token.token_type))
-                            }
-                        },
-                        None => Err("Unexpected end of input after dot".to_string()),
+                    let token = self.next_token();
+                    if let TokenType::Identifier(name) = token.token_type {
+                        Ok(SelectorPartKind::Class(name))
+                    } else {
+                        Err(ParseError::unexpected(ParseErrorKind::ExpectedIdentifier, "class name", &token))
                     }
                 },
                 TokenType::Hash => {
                     self.next_token();
-                    match self.next_token() {
-                        Some(token) => {
-                            if let TokenType::Identifier(name) = token.token_type {
-                                Ok(SelectorPart::Id(name))
-                            } else {
-                                Err(format!("Expected identifier after hash, found {:?}", token.token_type))
-                            }
-                        },
-                        None => Err("Unexpected end of input after hash".to_string()),
+                    let token = self.next_token();
+                    if let TokenType::Identifier(name) = token.token_type {
+                        Ok(SelectorPartKind::Id(name))
+                    } else {
+                        Err(ParseError::unexpected(ParseErrorKind::ExpectedIdentifier, "id", &token))
                     }
                 },
                 TokenType::Colon => {
                     self.next_token();
-                    match self.next_token() {
-                        Some(token) => {
-                            if let TokenType::Identifier(name) = token.token_type {
-                                if let Some(peek_token) = self.peek_token() {
-                                    if matches!(peek_token.token_type, TokenType::OpenParen) {
-                                        self.next_token();
-                                        let args = self.parse_pseudo_class_arguments()?;
-                                        return Ok(SelectorPart::PseudoClassFunction(name, args));
-                                    }
-                                }
-                                Ok(SelectorPart::PseudoClass(name))
-                            } else {
-                                Err(format!("Expected identifier after colon, found {:?}", token.token_type))
+                    let token = self.next_token();
+                    if let TokenType::Identifier(name) = token.token_type {
+                        if let Some(peek_token) = self.peek_token() {
+                            if matches!(peek_token.token_type, TokenType::OpenParen) {
+                                self.next_token();
+                                let args = self.parse_pseudo_class_arguments()?;
+                                return Ok(SelectorPartKind::PseudoClassFunction(name, args));
                             }
-                        },
-                        None => Err("Unexpected end of input after colon".to_string()),
+                        }
+                        Ok(SelectorPartKind::PseudoClass(name))
+                    } else {
+                        Err(ParseError::unexpected(ParseErrorKind::ExpectedIdentifier, "pseudo-class name", &token))
                     }
                 },
                 TokenType::DoubleColon => {
                     self.next_token();
-                    match self.next_token() {
-                        Some(token) => {
-                            if let TokenType::Identifier(name) = token.token_type {
-                                Ok(SelectorPart::PseudoElement(name))
-                            } else {
-                                Err(format!("Expected identifier after double colon, found {:?}", token.token_type))
-                            }
-                        },
-                        None => Err("Unexpected end of input after double colon".to_string()),
+                    let token = self.next_token();
+                    if let TokenType::Identifier(name) = token.token_type {
+                        Ok(SelectorPartKind::PseudoElement(name))
+                    } else {
+                        Err(ParseError::unexpected(ParseErrorKind::ExpectedIdentifier, "pseudo-element name", &token))
                     }
                 },
                 TokenType::Asterisk => {
                     self.next_token();
-                    Ok(SelectorPart::Universal)
+                    if self.peek_is(&TokenType::Pipe) {
+                        self.next_token();
+                        return self.parse_namespaced_type(NamespacePrefix::Any);
+                    }
+                    Ok(SelectorPartKind::Universal(None))
+                },
+                TokenType::Pipe => {
+                    self.next_token();
+                    self.parse_namespaced_type(NamespacePrefix::None)
+                },
+                TokenType::Ampersand => {
+                    self.next_token();
+                    Ok(SelectorPartKind::Nesting)
                 },
                 TokenType::Identifier(name) if allow_element => {
                     let name = name.clone();
                     self.next_token();
-                    Ok(SelectorPart::Element(name))
+                    if self.peek_is(&TokenType::Pipe) {
+                        self.next_token();
+                        return self.parse_namespaced_type(NamespacePrefix::Named(name));
+                    }
+                    Ok(SelectorPartKind::Element(None, name))
                 },
                 TokenType::OpenBracket => {
                     self.parse_attribute_selector()
                 },
-                _ => Err(format!("Unexpected token in selector: {:?}", token.token_type)),
+                _ => Err(ParseError::unexpected(ParseErrorKind::InvalidSelector, "selector", token)),
             }
         } else {
-            Err("Unexpected end of input while parsing selector part".to_string())
+            Err(self.eof_error("selector"))
+        }
+    }
+
+    /// Parses the element name or `*` after a namespace prefix such as `svg|`.
+    fn parse_namespaced_type(&mut self, namespace: NamespacePrefix<'a>) -> Result<SelectorPartKind<'a>, ParseError> {
+        match self.next_token() {
+            Token { token_type: TokenType::Identifier(name), .. } => Ok(SelectorPartKind::Element(Some(namespace), name)),
+            Token { token_type: TokenType::Asterisk, .. } => Ok(SelectorPartKind::Universal(Some(namespace))),
+            token => Err(ParseError::unexpected(ParseErrorKind::InvalidSelector, "element name or '*'", &token)),
         }
     }
 
-    fn parse_pseudo_class_arguments(&mut self) -> Result<String, String> {
+    fn parse_pseudo_class_arguments(&mut self) -> Result<Cow<'a, str>, ParseError> {
         let mut args = String::new();
         let mut paren_depth = 1;
 
@@ -604,7 +1866,7 @@ impl CssParser {
                         }
                     },
                     _ => {
-                        let token = self.next_token().unwrap();
+                        let token = self.next_token();
                         match &token.token_type {
                             TokenType::Identifier(name) => args.push_str(name),
                             TokenType::Number(num) => args.push_str(&num.to_string()),
@@ -616,20 +1878,107 @@ impl CssParser {
                             TokenType::Minus => args.push('-'),
                             TokenType::Asterisk => args.push('*'),
                             TokenType::Comma => args.push_str(", "),
+                            TokenType::Whitespace if !args.ends_with(' ') => args.push(' '),
+                            TokenType::Whitespace => {},
                             _ => args.push_str(&format!("{}", token.token_type)),
                         }
                     }
                 }
             } else {
-                return Err("Unexpected end of input while parsing pseudo-class arguments".to_string());
+                return Err(self.eof_error("')'"));
+            }
+        }
+
+        Ok(args.trim().to_string().into())
+    }
+
+
+    /// Parses the contents of a style rule's block: declarations mixed with
+    /// nested style rules and at-rules. Declarations before the first nested
+    /// rule are returned on their own; later ones are kept in source order as
+    /// declaration blocks between the nested rules.
+    fn parse_style_block(&mut self) -> Result<(Vec<Declaration<'a>>, Vec<CssRule<'a>>), ParseError> {
+        let mut declarations = Vec::new();
+        let mut rules = Vec::new();
+        let mut trailing_declarations = Vec::new();
+
+        loop {
+            while self.peek_is(&TokenType::Semicolon) {
+                self.next_token();
+            }
+
+            let depth = self.block_depth;
+
+            match self.peek_token().map(|token| &token.token_type) {
+                None => return Err(self.eof_error("'}'")),
+                Some(TokenType::CloseBrace | TokenType::EOF) => break,
+                Some(TokenType::AtSymbol) => {
+                    let at_rule = self.parse_at_rule();
+                    if let Some(at_rule) = self.recover_rule(at_rule, depth, true)? {
+                        Self::flush_declarations(&mut rules, &mut trailing_declarations);
+                        rules.push(CssRule::from(at_rule));
+                    }
+                    continue;
+                },
+                Some(_) => {},
+            }
+
+            if self.peek_nested_rule() {
+                let rule = self.parse_rule();
+                if let Some(rule) = self.recover_rule(rule, depth, false)? {
+                    Self::flush_declarations(&mut rules, &mut trailing_declarations);
+                    rules.push(CssRule::Style(rule));
+                }
+            } else if let Some(declaration) = self.parse_block_declaration(depth)? {
+                if rules.is_empty() {
+                    declarations.push(declaration);
+                } else {
+                    trailing_declarations.push(declaration);
+                }
             }
         }
 
-        Ok(args.trim().to_string())
+        Self::flush_declarations(&mut rules, &mut trailing_declarations);
+        Ok((declarations, rules))
     }
 
+    /// Moves declarations that follow a nested rule into a declaration block
+    /// after it.
+    fn flush_declarations(rules: &mut Vec<CssRule<'a>>, declarations: &mut Vec<Declaration<'a>>) {
+        let (Some(first), Some(last)) = (declarations.first(), declarations.last()) else { return };
+        let span = Span { end: last.span.end, ..first.span };
+        rules.push(CssRule::Style(Self::declaration_block(std::mem::take(declarations), span)));
+    }
+
+    /// Whether the next item of a style block is a nested rule rather than a
+    /// declaration. Custom properties are always declarations.
+    fn peek_nested_rule(&mut self) -> bool {
+        let is_custom_property = self.peek_token().is_some_and(|token| {
+            matches!(&token.token_type, TokenType::Identifier(name) if name.starts_with("--"))
+        });
+
+        !is_custom_property && self.peek_block_ahead()
+    }
+
+    /// Whether a `{` comes before the `;` or `}` that would end the current
+    /// statement or declaration.
+    fn peek_block_ahead(&mut self) -> bool {
+        let mut nesting = 0usize;
+        for n in 0.. {
+            match self.lexer.peek_nth(n).token_type {
+                TokenType::OpenBrace if nesting == 0 => return true,
+                TokenType::Semicolon | TokenType::CloseBrace if nesting == 0 => return false,
+                TokenType::OpenParen | TokenType::OpenBracket => nesting += 1,
+                TokenType::CloseParen | TokenType::CloseBracket => nesting = nesting.saturating_sub(1),
+                TokenType::EOF => return false,
+                _ => {}
+            }
+        }
+
+        false
+    }
 
-    fn parse_declarations(&mut self) -> Result<Vec<Declaration>, String> {
+    fn parse_declarations(&mut self) -> Result<Vec<Declaration<'a>>, ParseError> {
         let mut declarations = Vec::new();
 
         loop {
@@ -642,36 +1991,55 @@ impl CssParser {
             }
 
             if let Some(token) = self.peek_token() {
-                if matches!(token.token_type, TokenType::CloseBrace) {
+                if matches!(token.token_type, TokenType::CloseBrace | TokenType::EOF) {
                     break;
                 }
             } else {
-                return Err("Unexpected end of input while parsing declarations".to_string());
+                return Err(self.eof_error("'}'"));
+            }
+
+            let depth = self.block_depth;
+            if let Some(declaration) = self.parse_block_declaration(depth)? {
+                declarations.push(declaration);
             }
+        }
 
-            let declaration = self.parse_declaration()?;
-            declarations.push(declaration);
+        Ok(declarations)
+    }
 
+    /// Parses one declaration of a block up to and including its `;`. While
+    /// recovering, an invalid declaration is skipped and `None` returned.
+    fn parse_block_declaration(&mut self, depth: usize) -> Result<Option<Declaration<'a>>, ParseError> {
+        let declaration = self.parse_declaration().and_then(|declaration| {
             match self.peek_token() {
-                Some(token) if matches!(token.token_type, TokenType::Semicolon) => {
-                    self.next_token();
-                },
-                Some(token) if matches!(token.token_type, TokenType::CloseBrace) => {
-                    break;
-                },
-                Some(token) => {
-                    return Err(format!("Expected semicolon or closing brace after declaration, found {:?}", token.token_type));
-                },
-                None => {
-                    return Err("Unexpected end of input while parsing declarations".to_string());
-                }
+                Some(token) if matches!(token.token_type, TokenType::Semicolon | TokenType::CloseBrace | TokenType::EOF) => Ok(declaration),
+                Some(token) => Err(ParseError::unexpected(ParseErrorKind::UnexpectedToken, "';' or '}'", token)),
+                None => Err(self.eof_error("'}'")),
             }
+        });
+
+        let Some(mut declaration) = self.recover_declaration(declaration, depth)? else { return Ok(None) };
+
+        if self.peek_token().is_some_and(|token| token.token_type == TokenType::Semicolon) {
+            self.next_token();
         }
+        declaration.comments.trailing = self.trailing_comments();
 
-        Ok(declarations)
+        Ok(Some(declaration))
     }
 
-    fn parse_value(&mut self) -> Result<Value, String> {
+    fn parse_value(&mut self) -> Result<Value<'a>, ParseError> {
+        let span = self.start_span();
+        let kind = self.parse_value_kind()?;
+        Ok(self.spanned(kind, span))
+    }
+
+    /// Wraps a value parsed since `start` with its span.
+    fn spanned(&self, kind: ValueKind<'a>, start: Span) -> Value<'a> {
+        Value { kind, span: self.end_span(start) }
+    }
+
+    fn parse_value_kind(&mut self) -> Result<ValueKind<'a>, ParseError> {
         if let Some(token) = self.peek_token() {
             match &token.token_type {
                 TokenType::Identifier(name) => {
@@ -689,24 +2057,24 @@ impl CssParser {
                     }
 
                     if is_color_name(&name_clone) {
-                        Ok(Value::Color(Color::Named(name_clone)))
+                        Ok(ValueKind::Color(Color::Named(name_clone)))
                     } else if is_css_keyword(&name_clone) {
-                        Ok(Value::Keyword(name_clone))
+                        Ok(ValueKind::Keyword(name_clone))
                     } else {
-                        Ok(Value::Literal(name_clone))
+                        Ok(ValueKind::Literal(name_clone))
                     }
                 },
                 TokenType::Number(_) => self.parse_number(),
                 TokenType::String(text) => {
                     let text_clone = text.clone();
                     self.next_token();
-                    Ok(Value::QuotedString(text_clone))
+                    Ok(ValueKind::QuotedString(text_clone))
                 },
                 TokenType::Hash => self.parse_hex_color(),
                 TokenType::UnicodeRange(range) => {
                     let range_clone = range.clone();
                     self.next_token();
-                    Ok(Value::Literal(range_clone))
+                    Ok(ValueKind::Literal(range_clone))
                 },
                 TokenType::Asterisk => {
                     self.next_token();
@@ -718,14 +2086,14 @@ impl CssParser {
 
                             if let Some(next) = self.peek_token() {
                                 if matches!(next.token_type, TokenType::OpenParen) {
-                                    return self.parse_function(format!("*{}", name_clone));
+                                    return self.parse_function(format!("*{}", name_clone).into());
                                 }
                             }
-                            return Ok(Value::Literal(format!("*{}", name_clone)));
+                            return Ok(ValueKind::Literal(format!("*{}", name_clone).into()));
                         }
                     }
 
-                    Ok(Value::Literal("*".to_string()))
+                    Ok(ValueKind::Literal("*".into()))
                 },
                 TokenType::LessThan => {
                     self.next_token();
@@ -749,217 +2117,209 @@ impl CssParser {
                         }
                     }
 
-                    Ok(Value::Literal(content))
+                    Ok(ValueKind::Literal(content.into()))
                 },
+                TokenType::EOF => Err(self.eof_error("value")),
                 _ => {
-                    let token = self.next_token().unwrap();
-                    Ok(Value::Literal(format!("{}", token.token_type)))
+                    let token = self.next_token();
+                    Ok(ValueKind::Literal(format!("{}", token.token_type).into()))
                 }
             }
         } else {
-            Err("Unexpected end of input while parsing value".to_string())
+            Err(self.eof_error("value"))
         }
     }
 
-    fn parse_attribute_selector(&mut self) -> Result<SelectorPart, String> {
+    fn parse_attribute_selector(&mut self) -> Result<SelectorPartKind<'a>, ParseError> {
         self.next_token();
 
-        let attr_name = match self.next_token() {
-            Some(token) => {
-                if let TokenType::Identifier(name) = token.token_type {
-                    name
-                } else {
-                    return Err(format!("Expected attribute name, found {:?}", token.token_type));
+        let mut namespace = match self.peek_token().map(|token| &token.token_type) {
+            Some(TokenType::Asterisk) => {
+                self.next_token();
+                if !self.peek_is(&TokenType::Pipe) {
+                    return Err(self.unexpected_here(ParseErrorKind::InvalidAttributeSelector, "'|' after '*'"));
                 }
+                self.next_token();
+                Some(NamespacePrefix::Any)
             },
-            None => return Err("Unexpected end of input while parsing attribute selector".to_string()),
+            Some(TokenType::Pipe) => {
+                self.next_token();
+                Some(NamespacePrefix::None)
+            },
+            _ => None,
         };
 
+        let mut attr_name = self.parse_attribute_name()?;
+
+        // `[xlink|href]` rather than the `|=` operator.
+        if namespace.is_none() && self.lexer.peek_nth(0).token_type == TokenType::Pipe && self.lexer.peek_nth(1).token_type != TokenType::Equals {
+            self.next_token();
+            namespace = Some(NamespacePrefix::Named(attr_name));
+            attr_name = self.parse_attribute_name()?;
+        }
+
         if let Some(token) = self.peek_token() {
             match &token.token_type {
                 TokenType::CloseBracket => {
                     self.next_token();
-                    return Ok(SelectorPart::AttributeSelector(attr_name, None));
+                    return Ok(SelectorPartKind::AttributeSelector(namespace, attr_name, None));
                 },
                 _ => {
-                    let operator = self.parse_attribute_operator()?;
-                    let value = self.parse_attribute_value()?;
-
-                    let mut case_sensitivity = None;
-
-                    if let Some(token) = self.peek_token() {
-                        if let TokenType::Identifier(modifier) = &token.token_type {
-                            if modifier == "i" {
-                                case_sensitivity = Some(CaseSensitivity::Insensitive);
-                                self.next_token();
-                            } else if modifier == "s" {
-                                case_sensitivity = Some(CaseSensitivity::Sensitive);
-                                self.next_token();
-                            }
-                        }
-                    }
-
-                    if let Some(token) = self.next_token() {
-                        if !matches!(token.token_type, TokenType::CloseBracket) {
-                            return Err(format!("Expected closing bracket, found {:?}", token.token_type));
-                        }
-                    } else {
-                        return Err("Unexpected end of input while parsing attribute selector".to_string());
-                    }
-
-                    return Ok(SelectorPart::AttributeSelector(attr_name, Some((operator, value, case_sensitivity))));
-                }
-            }
-        }
-
-        Err("Unexpected end of input while parsing attribute selector".to_string())
-    }
-
-    fn parse_attribute_operator(&mut self) -> Result<AttributeOperator, String> {
-        match self.next_token() {
-            Some(token) => {
-                match &token.token_type {
-                    TokenType::Equals => {
-                        Ok(AttributeOperator::Equals)
-                    },
-                    TokenType::Tilde => {
-                        if let Some(next) = self.next_token() {
-                            if matches!(next.token_type, TokenType::Equals) {
-                                Ok(AttributeOperator::Includes)
-                            } else {
-                                Err(format!("Expected = after ~, found {:?}", next.token_type))
-                            }
-                        } else {
-                            Err("Unexpected end of input after ~".to_string())
-                        }
-                    },
-                    TokenType::Pipe => {
-                        if let Some(next) = self.next_token() {
-                            if matches!(next.token_type, TokenType::Equals) {
-                                Ok(AttributeOperator::DashMatch)
-                            } else {
-                                Err(format!("Expected = after |, found {:?}", next.token_type))
-                            }
-                        } else {
-                            Err("Unexpected end of input after |".to_string())
-                        }
-                    },
-                    TokenType::Caret => {
-                        if let Some(next) = self.next_token() {
-                            if matches!(next.token_type, TokenType::Equals) {
-                                Ok(AttributeOperator::StartsWith)
-                            } else {
-                                Err(format!("Expected = after ^, found {:?}", next.token_type))
-                            }
-                        } else {
-                            Err("Unexpected end of input after ^".to_string())
-                        }
-                    },
-                    TokenType::Dollar => {
-                        if let Some(next) = self.next_token() {
-                            if matches!(next.token_type, TokenType::Equals) {
-                                Ok(AttributeOperator::EndsWith)
-                            } else {
-                                Err(format!("Expected = after $, found {:?}", next.token_type))
-                            }
-                        } else {
-                            Err("Unexpected end of input after $".to_string())
-                        }
-                    },
-                    TokenType::Asterisk => {
-                        if let Some(next) = self.next_token() {
-                            if matches!(next.token_type, TokenType::Equals) {
-                                Ok(AttributeOperator::Contains)
-                            } else {
-                                Err(format!("Expected = after *, found {:?}", next.token_type))
+                    let operator = self.parse_attribute_operator()?;
+                    let value = self.parse_attribute_value()?;
+
+                    let mut case_sensitivity = None;
+
+                    if let Some(token) = self.peek_token() {
+                        if let TokenType::Identifier(modifier) = &token.token_type {
+                            if modifier == "i" {
+                                case_sensitivity = Some(CaseSensitivity::Insensitive);
+                                self.next_token();
+                            } else if modifier == "s" {
+                                case_sensitivity = Some(CaseSensitivity::Sensitive);
+                                self.next_token();
                             }
-                        } else {
-                            Err("Unexpected end of input after *".to_string())
                         }
-                    },
-                    _ => Err(format!("Expected attribute operator, found {:?}", token.token_type)),
+                    }
+
+                    let token = self.next_token();
+                    if !matches!(token.token_type, TokenType::CloseBracket) {
+                        return Err(ParseError::unexpected(ParseErrorKind::InvalidAttributeSelector, "']'", &token));
+                    }
+
+                    return Ok(SelectorPartKind::AttributeSelector(namespace, attr_name, Some((operator, value, case_sensitivity))));
                 }
-            },
-            None => Err("Unexpected end of input while parsing attribute operator".to_string()),
+            }
         }
+
+        Err(self.eof_error("']'"))
     }
 
-    fn parse_attribute_value(&mut self) -> Result<String, String> {
+    fn parse_attribute_name(&mut self) -> Result<Cow<'a, str>, ParseError> {
         match self.next_token() {
-            Some(token) => {
-                match &token.token_type {
-                    TokenType::String(value) => Ok(value.clone()),
-                    TokenType::Identifier(value) => Ok(value.clone()),
-                    _ => Err(format!("Expected attribute value, found {:?}", token.token_type)),
+            Token { token_type: TokenType::Identifier(name), .. } => Ok(name),
+            token => Err(ParseError::unexpected(ParseErrorKind::InvalidAttributeSelector, "attribute name", &token)),
+        }
+    }
+
+    fn parse_attribute_operator(&mut self) -> Result<AttributeOperator, ParseError> {
+        let token = self.next_token();
+        match &token.token_type {
+            TokenType::Equals => {
+                Ok(AttributeOperator::Equals)
+            },
+            TokenType::Tilde => {
+                let next = self.next_token();
+                if matches!(next.token_type, TokenType::Equals) {
+                    Ok(AttributeOperator::Includes)
+                } else {
+                    Err(ParseError::unexpected(ParseErrorKind::InvalidAttributeSelector, "'=' after '~'", &next))
+                }
+            },
+            TokenType::Pipe => {
+                let next = self.next_token();
+                if matches!(next.token_type, TokenType::Equals) {
+                    Ok(AttributeOperator::DashMatch)
+                } else {
+                    Err(ParseError::unexpected(ParseErrorKind::InvalidAttributeSelector, "'=' after '|'", &next))
+                }
+            },
+            TokenType::Caret => {
+                let next = self.next_token();
+                if matches!(next.token_type, TokenType::Equals) {
+                    Ok(AttributeOperator::StartsWith)
+                } else {
+                    Err(ParseError::unexpected(ParseErrorKind::InvalidAttributeSelector, "'=' after '^'", &next))
+                }
+            },
+            TokenType::Dollar => {
+                let next = self.next_token();
+                if matches!(next.token_type, TokenType::Equals) {
+                    Ok(AttributeOperator::EndsWith)
+                } else {
+                    Err(ParseError::unexpected(ParseErrorKind::InvalidAttributeSelector, "'=' after '$'", &next))
+                }
+            },
+            TokenType::Asterisk => {
+                let next = self.next_token();
+                if matches!(next.token_type, TokenType::Equals) {
+                    Ok(AttributeOperator::Contains)
+                } else {
+                    Err(ParseError::unexpected(ParseErrorKind::InvalidAttributeSelector, "'=' after '*'", &next))
                 }
             },
-            None => Err("Unexpected end of input while parsing attribute value".to_string()),
+            _ => Err(ParseError::unexpected(ParseErrorKind::InvalidAttributeSelector, "attribute operator", &token)),
         }
     }
 
-    fn parse_number(&mut self) -> Result<Value, String> {
-        if let Some(token) = self.next_token() {
-            if let TokenType::Number(num) = token.token_type {
-                if let Some(next) = self.peek_token().cloned() {
-                    if let TokenType::Unit(unit_str) = &next.token_type {
-                        self.next_token();
-                        let unit = match unit_str.as_str() {
-                            "px" => Unit::Px,
-                            "em" => Unit::Em,
-                            "rem" => Unit::Rem,
-                            "%" => Unit::Percent,
-                            "vh" => Unit::Vh,
-                            "vw" => Unit::Vw,
-                            "pt" => Unit::Pt,
-                            "cm" => Unit::Cm,
-                            "mm" => Unit::Mm,
-                            "in" => Unit::In,
-                            "deg" => Unit::Deg,
-                            "rad" => Unit::Rad,
-                            "fr" => Unit::Fr,
-                            "s" => Unit::S,
-                            "ms" => Unit::Ms,
-                            _ => Unit::Other(unit_str.clone()),
-                        };
-                        return Ok(Value::Number(num, Some(unit)));
-                    }
-                }
+    fn parse_attribute_value(&mut self) -> Result<Cow<'a, str>, ParseError> {
+        let token = self.next_token();
+        match &token.token_type {
+            TokenType::String(value) => Ok(value.clone()),
+            TokenType::Identifier(value) => Ok(value.clone()),
+            _ => Err(ParseError::unexpected(ParseErrorKind::InvalidAttributeSelector, "attribute value", &token)),
+        }
+    }
 
-                Ok(Value::Number(num, None))
-            } else {
-                Err(format!("Expected number, found {:?}", token.token_type))
+    fn parse_number(&mut self) -> Result<ValueKind<'a>, ParseError> {
+        let token = self.next_token();
+        if let TokenType::Number(num) = token.token_type {
+            if let Some(next) = self.peek_token().cloned() {
+                if let TokenType::Unit(unit_str) = &next.token_type {
+                    self.next_token();
+                    let unit = match unit_str.as_ref() {
+                        "px" => Unit::Px,
+                        "em" => Unit::Em,
+                        "rem" => Unit::Rem,
+                        "%" => Unit::Percent,
+                        "vh" => Unit::Vh,
+                        "vw" => Unit::Vw,
+                        "pt" => Unit::Pt,
+                        "cm" => Unit::Cm,
+                        "mm" => Unit::Mm,
+                        "in" => Unit::In,
+                        "deg" => Unit::Deg,
+                        "rad" => Unit::Rad,
+                        "fr" => Unit::Fr,
+                        "s" => Unit::S,
+                        "ms" => Unit::Ms,
+                        _ => Unit::Other(unit_str.clone()),
+                    };
+                    return Ok(ValueKind::Number(num, Some(unit)));
+                }
             }
+
+            Ok(ValueKind::Number(num, None))
         } else {
-            Err("Unexpected end of input while parsing number".to_string())
+            Err(ParseError::unexpected(ParseErrorKind::ExpectedNumber, "number", &token))
         }
     }
 
-    fn parse_hex_color(&mut self) -> Result<Value, String> {
+    fn parse_hex_color(&mut self) -> Result<ValueKind<'a>, ParseError> {
         self.next_token();
 
-        if let Some(token) = self.next_token() {
-            match &token.token_type {
-                TokenType::Identifier(name) => {
-                    if name.chars().all(|c| c.is_digit(16)) {
-                        Ok(Value::Color(Color::Hex(format!("#{}", name))))
-                    } else {
-                        Ok(Value::Literal(format!("#{}", name)))
-                    }
-                },
-                _ => Err(format!("Expected identifier after #, found {:?}", token.token_type)),
-            }
-        } else {
-            Err("Unexpected end of input after #".to_string())
+        let token = self.next_token();
+        match &token.token_type {
+            TokenType::Identifier(name) => {
+                if name.chars().all(|c| c.is_ascii_hexdigit()) {
+                    Ok(ValueKind::Color(Color::Hex(format!("#{}", name).into())))
+                } else {
+                    Ok(ValueKind::Literal(format!("#{}", name).into()))
+                }
+            },
+            _ => Err(ParseError::unexpected(ParseErrorKind::ExpectedIdentifier, "hex color", &token)),
         }
     }
 
-    fn parse_function(&mut self, name: String) -> Result<Value, String> {
+    fn parse_function(&mut self, name: Cow<'a, str>) -> Result<ValueKind<'a>, ParseError> {
         self.expect_open_paren()?;
 
         if name.to_lowercase() == "url" {
+            let span = self.start_span();
             let url_value = self.parse_url_argument()?;
+            let argument = self.spanned(ValueKind::Literal(url_value), span);
             self.expect_close_paren()?;
-            return Ok(Value::Function(name, vec![Value::Literal(url_value)]));
+            return Ok(ValueKind::Function(name, vec![argument]));
         }
 
         let name_lower = name.to_lowercase();
@@ -995,7 +2355,7 @@ impl CssParser {
             "color-mix", "palette-mix"
         ];
 
-        if color_functions.contains(&name.as_str()) {
+        if color_functions.contains(&name.as_ref()) {
             return self.parse_color_function(name);
         }
 
@@ -1004,18 +2364,19 @@ impl CssParser {
         if let Some(token) = self.peek_token() {
             if matches!(token.token_type, TokenType::CloseParen) {
                 self.next_token();
-                return Ok(Value::Function(name, arguments));
+                return Ok(ValueKind::Function(name, arguments));
             }
         }
 
         if let Some(token) = self.peek_token() {
             if matches!(token.token_type, TokenType::LessThan) {
+                let span = self.start_span();
                 let mut content = String::new();
 
                 let mut paren_depth = 1;
 
                 while paren_depth > 0 && self.peek_token().is_some() {
-                    let token = self.next_token().unwrap();
+                    let token = self.next_token();
                     match token.token_type {
                         TokenType::OpenParen => {
                             paren_depth += 1;
@@ -1035,7 +2396,7 @@ impl CssParser {
                     }
                 }
 
-                return Ok(Value::Function(name, vec![Value::Literal(content)]));
+                return Ok(ValueKind::Function(name, vec![self.spanned(ValueKind::Literal(content.into()), span)]));
             }
         }
 
@@ -1061,15 +2422,16 @@ impl CssParser {
             }
         }
 
-        Ok(Value::Function(name, arguments))
+        Ok(ValueKind::Function(name, arguments))
     }
 
-    fn parse_gradient_function(&mut self, name: String) -> Result<Value, String> {
+    fn parse_gradient_function(&mut self, name: Cow<'a, str>) -> Result<ValueKind<'a>, ParseError> {
         let mut arguments = Vec::new();
 
         if let Some(token) = self.peek_token() {
             if let TokenType::Identifier(id) = &token.token_type {
                 if id.to_lowercase() == "to" {
+                    let span = self.start_span();
                     self.next_token();
 
                     let mut direction = String::from("to");
@@ -1091,13 +2453,10 @@ impl CssParser {
                         }
                     }
 
-                    arguments.push(Value::Literal(direction));
+                    arguments.push(self.spanned(ValueKind::Literal(direction.into()), span));
 
-                    if let Some(token) = self.peek_token() {
-                        if matches!(token.token_type, TokenType::Comma) {
-                            self.next_token();
-                            arguments.push(Value::Literal(",".to_string()));
-                        }
+                    if self.peek_is(&TokenType::Comma) {
+                        arguments.push(self.parse_separator());
                     }
                 }
             }
@@ -1110,7 +2469,7 @@ impl CssParser {
                     break;
                 }
             } else {
-                return Err("Unexpected end of input in gradient function".to_string());
+                return Err(self.eof_error("')'"));
             }
 
             let color = self.parse_gradient_color_stop()?;
@@ -1134,18 +2493,16 @@ impl CssParser {
                 }
             }
 
-            if let Some(token) = self.peek_token() {
-                if matches!(token.token_type, TokenType::Comma) {
-                    self.next_token();
-                    arguments.push(Value::Literal(",".to_string()));
-                }
+            if self.peek_is(&TokenType::Comma) {
+                arguments.push(self.parse_separator());
             }
         }
 
-        Ok(Value::Function(name, arguments))
+        Ok(ValueKind::Function(name, arguments))
     }
 
-    fn parse_gradient_color_stop(&mut self) -> Result<Value, String> {
+    fn parse_gradient_color_stop(&mut self) -> Result<Value<'a>, ParseError> {
+        let span = self.start_span();
         let color = self.parse_value()?;
 
         let mut positions = Vec::new();
@@ -1174,13 +2531,13 @@ impl CssParser {
             for position in positions {
                 args.push(position);
             }
-            Ok(Value::Function("color-stop".to_string(), args))
+            Ok(self.spanned(ValueKind::Function("color-stop".into(), args), span))
         } else {
             Ok(color)
         }
     }
 
-    fn parse_css_math_function(&mut self, name: String) -> Result<Value, String> {
+    fn parse_css_math_function(&mut self, name: Cow<'a, str>) -> Result<ValueKind<'a>, ParseError> {
         let mut arguments = Vec::new();
 
         loop {
@@ -1197,25 +2554,25 @@ impl CssParser {
                         self.next_token();
                         break;
                     },
-                    _ => return Err(format!("Expected comma or closing parenthesis in math function, found {:?}", token.token_type)),
+                    _ => return Err(ParseError::unexpected(ParseErrorKind::InvalidFunction, "',' or ')'", token)),
                 }
             } else {
-                return Err("Unexpected end of input in math function".to_string());
+                return Err(self.eof_error("')'"));
             }
         }
 
         if arguments.is_empty() {
-            return Err(format!("Function {} requires at least one argument", name));
+            return Err(self.error_here(ParseErrorKind::InvalidFunction, format!("at least one argument to {}()", name)));
         }
 
         if name.to_lowercase() == "clamp" && arguments.len() != 3 {
-            return Err("clamp() function requires exactly 3 arguments: minimum, preferred, and maximum".to_string());
+            return Err(self.error_here(ParseErrorKind::InvalidFunction, "3 arguments to clamp()"));
         }
 
-        Ok(Value::Calc(CalcExpression::Function(name, arguments)))
+        Ok(ValueKind::Calc(CalcExpression::Function(name, arguments)))
     }
 
-    fn parse_space_separated_function(&mut self, name: String) -> Result<Value, String> {
+    fn parse_space_separated_function(&mut self, name: Cow<'a, str>) -> Result<ValueKind<'a>, ParseError> {
         let mut values = Vec::new();
 
         while let Some(token) = self.peek_token() {
@@ -1229,25 +2586,21 @@ impl CssParser {
         }
 
         if values.is_empty() {
-            return Err("Expected at least one value in function".to_string());
+            return Err(self.error_here(ParseErrorKind::InvalidFunction, format!("at least one argument to {}()", name)));
         }
 
-        Ok(Value::Function(name, values))
+        Ok(ValueKind::Function(name, values))
     }
 
-    fn parse_url_argument(&mut self) -> Result<String, String> {
-        match self.next_token() {
-            Some(token) => {
-                match token.token_type {
-                    TokenType::String(text) => Ok(text),
-                    _ => Err(format!("Expected string in url(), found {:?}", token.token_type))
-                }
-            },
-            None => Err("Unexpected end of input while parsing url()".to_string())
+    fn parse_url_argument(&mut self) -> Result<Cow<'a, str>, ParseError> {
+        let token = self.next_token();
+        match token.token_type {
+            TokenType::String(text) => Ok(text),
+            _ => Err(ParseError::unexpected(ParseErrorKind::InvalidFunction, "url", &token))
         }
     }
 
-    fn parse_calc_function(&mut self) -> Result<Value, String> {
+    fn parse_calc_function(&mut self) -> Result<ValueKind<'a>, ParseError> {
         if let Some(token) = self.peek_token() {
             if matches!(token.token_type, TokenType::LessThan) {
                 let mut content = String::from("calc(");
@@ -1257,7 +2610,7 @@ impl CssParser {
                 content.push('<');
 
                 while paren_depth > 0 && self.peek_token().is_some() {
-                    let token = self.next_token().unwrap();
+                    let token = self.next_token();
                     match token.token_type {
                         TokenType::OpenParen => {
                             paren_depth += 1;
@@ -1265,11 +2618,7 @@ impl CssParser {
                         },
                         TokenType::CloseParen => {
                             paren_depth -= 1;
-                            if paren_depth > 0 {
-                                content.push(')');
-                            } else {
-                                content.push(')');
-                            }
+                            content.push(')');
                         },
                         TokenType::LessThan => content.push('<'),
                         TokenType::GreaterThan => content.push('>'),
@@ -1286,106 +2635,98 @@ impl CssParser {
                     }
                 }
 
-                return Ok(Value::Literal(content));
+                return Ok(ValueKind::Literal(content.into()));
             }
         }
 
         let expression = self.parse_calc_expression()?;
         self.expect_close_paren()?;
-        Ok(Value::Calc(expression))
+        Ok(ValueKind::Calc(expression))
     }
 
-    fn parse_calc_expression(&mut self) -> Result<CalcExpression, String> {
+    fn parse_calc_expression(&mut self) -> Result<CalcExpression<'a>, ParseError> {
         self.parse_calc_add_sub()
     }
 
-    fn parse_calc_add_sub(&mut self) -> Result<CalcExpression, String> {
+    fn parse_calc_add_sub(&mut self) -> Result<CalcExpression<'a>, ParseError> {
         let mut left = self.parse_calc_mul_div()?;
 
-        loop {
-            if let Some(token) = self.peek_token() {
-                match &token.token_type {
-                    TokenType::Plus => {
-                        self.next_token();
-                        let right = self.parse_calc_mul_div()?;
-                        left = CalcExpression::BinaryOperation(
-                            Box::new(left),
-                            CalcOperator::Add,
-                            Box::new(right)
-                        );
-                    },
-                    TokenType::Minus => {
-                        self.next_token();
-                        let right = self.parse_calc_mul_div()?;
-                        left = CalcExpression::BinaryOperation(
-                            Box::new(left),
-                            CalcOperator::Subtract,
-                            Box::new(right)
-                        );
-                    },
-                    TokenType::Identifier(name) if name == "-" => {
-                        self.next_token();
-                        let right = self.parse_calc_mul_div()?;
-                        left = CalcExpression::BinaryOperation(
-                            Box::new(left),
-                            CalcOperator::Subtract,
-                            Box::new(right)
-                        );
-                    },
-                    _ => break,
-                }
-            } else {
-                break;
+        while let Some(token) = self.peek_token() {
+            match &token.token_type {
+                TokenType::Plus => {
+                    self.next_token();
+                    let right = self.parse_calc_mul_div()?;
+                    left = CalcExpression::BinaryOperation(
+                        Box::new(left),
+                        CalcOperator::Add,
+                        Box::new(right)
+                    );
+                },
+                TokenType::Minus => {
+                    self.next_token();
+                    let right = self.parse_calc_mul_div()?;
+                    left = CalcExpression::BinaryOperation(
+                        Box::new(left),
+                        CalcOperator::Subtract,
+                        Box::new(right)
+                    );
+                },
+                TokenType::Identifier(name) if name == "-" => {
+                    self.next_token();
+                    let right = self.parse_calc_mul_div()?;
+                    left = CalcExpression::BinaryOperation(
+                        Box::new(left),
+                        CalcOperator::Subtract,
+                        Box::new(right)
+                    );
+                },
+                _ => break,
             }
         }
 
         Ok(left)
     }
 
-    fn parse_calc_mul_div(&mut self) -> Result<CalcExpression, String> {
+    fn parse_calc_mul_div(&mut self) -> Result<CalcExpression<'a>, ParseError> {
         let mut left = self.parse_calc_primary()?;
 
-        loop {
-            if let Some(token) = self.peek_token() {
-                match &token.token_type {
-                    TokenType::Asterisk => {
-                        self.next_token();
-                        let right = self.parse_calc_primary()?;
-                        left = CalcExpression::BinaryOperation(
-                            Box::new(left),
-                            CalcOperator::Multiply,
-                            Box::new(right)
-                        );
-                    },
-                    TokenType::Slash => {
-                        self.next_token();
-                        let right = self.parse_calc_primary()?;
-                        left = CalcExpression::BinaryOperation(
-                            Box::new(left),
-                            CalcOperator::Divide,
-                            Box::new(right)
-                        );
-                    },
-                    TokenType::Identifier(name) if name == "/" => {
-                        self.next_token();
-                        let right = self.parse_calc_primary()?;
-                        left = CalcExpression::BinaryOperation(
-                            Box::new(left),
-                            CalcOperator::Divide,
-                            Box::new(right)
-                        );
-                    },
-                    _ => break,
-                }
-            } else {
-                break;
+        while let Some(token) = self.peek_token() {
+            match &token.token_type {
+                TokenType::Asterisk => {
+                    self.next_token();
+                    let right = self.parse_calc_primary()?;
+                    left = CalcExpression::BinaryOperation(
+                        Box::new(left),
+                        CalcOperator::Multiply,
+                        Box::new(right)
+                    );
+                },
+                TokenType::Slash => {
+                    self.next_token();
+                    let right = self.parse_calc_primary()?;
+                    left = CalcExpression::BinaryOperation(
+                        Box::new(left),
+                        CalcOperator::Divide,
+                        Box::new(right)
+                    );
+                },
+                TokenType::Identifier(name) if name == "/" => {
+                    self.next_token();
+                    let right = self.parse_calc_primary()?;
+                    left = CalcExpression::BinaryOperation(
+                        Box::new(left),
+                        CalcOperator::Divide,
+                        Box::new(right)
+                    );
+                },
+                _ => break,
             }
         }
 
         Ok(left)
     }
 
-    fn parse_calc_primary(&mut self) -> Result<CalcExpression, String> {
+    fn parse_calc_primary(&mut self) -> Result<CalcExpression<'a>, ParseError> {
         if let Some(token) = self.peek_token() {
             match &token.token_type {
                 TokenType::Number(_) => {
@@ -1437,7 +2778,7 @@ impl CssParser {
                                         self.next_token();
                                         break;
                                     },
-                                    _ => return Err(format!("Expected comma or closing parenthesis, found {:?}", token.token_type)),
+                                    _ => return Err(ParseError::unexpected(ParseErrorKind::UnexpectedToken, "',' or ')'", token)),
                                 }
                             }
 
@@ -1451,14 +2792,11 @@ impl CssParser {
                     self.next_token();
                     let expr = self.parse_calc_expression()?;
 
-                    if let Some(token) = self.next_token() {
-                        if matches!(token.token_type, TokenType::CloseParen) {
-                            return Ok(CalcExpression::Parenthesized(Box::new(expr)));
-                        } else {
-                            return Err(format!("Expected closing parenthesis, found {:?}", token.token_type));
-                        }
+                    let token = self.next_token();
+                    if matches!(token.token_type, TokenType::CloseParen) {
+                        Ok(CalcExpression::Parenthesized(Box::new(expr)))
                     } else {
-                        return Err("Unexpected end of input while parsing parenthesized expression".to_string());
+                        Err(ParseError::unexpected(ParseErrorKind::UnexpectedToken, "')'", &token))
                     }
                 },
                 TokenType::Plus => {
@@ -1475,67 +2813,60 @@ impl CssParser {
                         Box::new(expr)
                     ))
                 },
-                _ => Err(format!("Unexpected token in calc expression: {:?}", token.token_type)),
+                _ => Err(ParseError::unexpected(ParseErrorKind::InvalidFunction, "calc expression", token)),
             }
         } else {
-            Err("Unexpected end of input while parsing calc expression".to_string())
+            Err(self.eof_error("calc expression"))
         }
     }
 
-    fn parse_number_with_unit(&mut self) -> Result<(f64, Option<Unit>), String> {
-        if let Some(token) = self.next_token() {
-            if let TokenType::Number(num) = token.token_type {
-                if let Some(next) = self.peek_token().cloned() {
-                    if let TokenType::Unit(unit_str) = &next.token_type {
-                        self.next_token();
-                        let unit = match unit_str.as_str() {
-                            "px" => Unit::Px,
-                            "em" => Unit::Em,
-                            "rem" => Unit::Rem,
-                            "%" => Unit::Percent,
-                            "vh" => Unit::Vh,
-                            "vw" => Unit::Vw,
-                            "pt" => Unit::Pt,
-                            "cm" => Unit::Cm,
-                            "mm" => Unit::Mm,
-                            "in" => Unit::In,
-                            "deg" => Unit::Deg,
-                            "rad" => Unit::Rad,
-                            "fr" => Unit::Fr,
-                            "s" => Unit::S,
-                            "ms" => Unit::Ms,
-                            _ => Unit::Other(unit_str.clone()),
-                        };
-                        return Ok((num, Some(unit)));
-                    }
+    fn parse_number_with_unit(&mut self) -> Result<(f64, Option<Unit<'a>>), ParseError> {
+        let token = self.next_token();
+        if let TokenType::Number(num) = token.token_type {
+            if let Some(next) = self.peek_token().cloned() {
+                if let TokenType::Unit(unit_str) = &next.token_type {
+                    self.next_token();
+                    let unit = match unit_str.as_ref() {
+                        "px" => Unit::Px,
+                        "em" => Unit::Em,
+                        "rem" => Unit::Rem,
+                        "%" => Unit::Percent,
+                        "vh" => Unit::Vh,
+                        "vw" => Unit::Vw,
+                        "pt" => Unit::Pt,
+                        "cm" => Unit::Cm,
+                        "mm" => Unit::Mm,
+                        "in" => Unit::In,
+                        "deg" => Unit::Deg,
+                        "rad" => Unit::Rad,
+                        "fr" => Unit::Fr,
+                        "s" => Unit::S,
+                        "ms" => Unit::Ms,
+                        _ => Unit::Other(unit_str.clone()),
+                    };
+                    return Ok((num, Some(unit)));
                 }
-
-                Ok((num, None))
-            } else {
-                Err(format!("Expected number, found {:?}", token.token_type))
             }
+
+            Ok((num, None))
         } else {
-            Err("Unexpected end of input while parsing number".to_string())
+            Err(ParseError::unexpected(ParseErrorKind::ExpectedNumber, "number", &token))
         }
     }
 
-    fn parse_var_name(&mut self) -> Result<String, String> {
+    fn parse_var_name(&mut self) -> Result<Cow<'a, str>, ParseError> {
         self.next_token();
 
         self.expect_open_paren()?;
 
-        let variable_name = match self.next_token() {
-            Some(token) => {
-                if let TokenType::Identifier(name) = token.token_type {
-                    if !name.starts_with("--") {
-                        return Err(format!("Variable name must start with --, found {}", name));
-                    }
-                    name
-                } else {
-                    return Err(format!("Expected variable name, found {:?}", token.token_type));
-                }
-            },
-            None => return Err("Unexpected end of input while parsing var function".to_string()),
+        let token = self.next_token();
+        let variable_name = if let TokenType::Identifier(name) = &token.token_type {
+            if !name.starts_with("--") {
+                return Err(ParseError::unexpected(ParseErrorKind::InvalidVariableName, "custom property name", &token));
+            }
+            name.clone()
+        } else {
+            return Err(ParseError::unexpected(ParseErrorKind::InvalidVariableName, "custom property name", &token));
         };
 
         let mut paren_depth = 1;
@@ -1557,7 +2888,7 @@ impl CssParser {
                     }
                 }
             } else {
-                return Err("Unexpected end of input while parsing var function".to_string());
+                return Err(self.eof_error("')'"));
             }
         }
 
@@ -1566,22 +2897,20 @@ impl CssParser {
         Ok(variable_name)
     }
 
-    fn parse_color_function(&mut self, function_name: String) -> Result<Value, String> {
+    fn parse_color_function(&mut self, function_name: Cow<'a, str>) -> Result<ValueKind<'a>, ParseError> {
         let special_functions = ["color-mix", "palette-mix"];
         let is_special = special_functions.contains(&function_name.to_lowercase().as_str());
 
         let mut components = Vec::new();
 
         if is_special {
-            if let Some(token) = self.next_token() {
-                match &token.token_type {
-                    TokenType::Identifier(word) if word.to_lowercase() == "in" => {
-                        components.push(Value::Literal("in".to_string()));
-                    },
-                    _ => return Err(format!("Expected 'in' after {}, found {:?}", function_name, token.token_type)),
-                }
-            } else {
-                return Err(format!("Unexpected end of input after {}", function_name));
+            let span = self.start_span();
+            let token = self.next_token();
+            match &token.token_type {
+                TokenType::Identifier(word) if word.to_lowercase() == "in" => {
+                    components.push(self.spanned(ValueKind::Literal("in".into()), span));
+                },
+                _ => return Err(ParseError::unexpected(ParseErrorKind::InvalidFunction, "'in'", &token)),
             }
 
             if let Some(token) = self.peek_token() {
@@ -1590,10 +2919,10 @@ impl CssParser {
                         let space = self.parse_value()?;
                         components.push(space);
                     },
-                    _ => return Err(format!("Expected color space name, found {:?}", token.token_type)),
+                    _ => return Err(ParseError::unexpected(ParseErrorKind::InvalidFunction, "color space", token)),
                 }
             } else {
-                return Err("Unexpected end of input while parsing color space".to_string());
+                return Err(self.eof_error("color space"));
             }
 
             while let Some(token) = self.peek_token() {
@@ -1616,12 +2945,11 @@ impl CssParser {
 
             if let Some(token) = self.peek_token() {
                 if !matches!(token.token_type, TokenType::Comma) {
-                    return Err(format!("Expected comma after color space, found {:?}", token.token_type));
+                    return Err(ParseError::unexpected(ParseErrorKind::InvalidFunction, "',' after color space", token));
                 }
-                self.next_token();
-                components.push(Value::Literal(",".to_string()));
+                components.push(self.parse_separator());
             } else {
-                return Err("Unexpected end of input after color space".to_string());
+                return Err(self.eof_error("','"));
             }
 
             let color1 = self.parse_color_argument()?;
@@ -1636,12 +2964,11 @@ impl CssParser {
 
             if let Some(token) = self.peek_token() {
                 if !matches!(token.token_type, TokenType::Comma) {
-                    return Err(format!("Expected comma after first color, found {:?}", token.token_type));
+                    return Err(ParseError::unexpected(ParseErrorKind::InvalidFunction, "',' after first color", token));
                 }
-                self.next_token();
-                components.push(Value::Literal(",".to_string()));
+                components.push(self.parse_separator());
             } else {
-                return Err("Unexpected end of input after first color".to_string());
+                return Err(self.eof_error("','"));
             }
 
             let color2 = self.parse_color_argument()?;
@@ -1656,11 +2983,11 @@ impl CssParser {
 
             if let Some(token) = self.peek_token() {
                 if !matches!(token.token_type, TokenType::CloseParen) {
-                    return Err(format!("Expected closing parenthesis, found {:?}", token.token_type));
+                    return Err(ParseError::unexpected(ParseErrorKind::UnexpectedToken, "')'", token));
                 }
                 self.next_token();
             } else {
-                return Err("Unexpected end of input while parsing color-mix".to_string());
+                return Err(self.eof_error("')'"));
             }
         } else {
             let mut has_slash = false;
@@ -1673,16 +3000,14 @@ impl CssParser {
                     },
                     TokenType::Slash => {
                         if has_slash {
-                            return Err("Multiple slashes in color function not allowed".to_string());
+                            return Err(ParseError::unexpected(ParseErrorKind::InvalidFunction, "a single '/' in color function", token));
                         }
                         has_slash = true;
-                        self.next_token();
-                        components.push(Value::Literal("/".to_string()));
+                        components.push(self.parse_separator());
                         continue;
                     },
                     TokenType::Comma => {
-                        self.next_token();
-                        components.push(Value::Literal(",".to_string()));
+                        components.push(self.parse_separator());
                         continue;
                     },
                     _ => {
@@ -1693,10 +3018,11 @@ impl CssParser {
             }
         }
 
-        Ok(Value::Function(function_name, components))
+        Ok(ValueKind::Function(function_name, components))
     }
 
-    fn parse_color_argument(&mut self) -> Result<Value, String> {
+    fn parse_color_argument(&mut self) -> Result<Value<'a>, ParseError> {
+        let span = self.start_span();
         let value = if let Some(token) = self.peek_token() {
             match &token.token_type {
                 TokenType::Identifier(name) if name.to_lowercase() == "color-mix"
@@ -1706,23 +3032,26 @@ impl CssParser {
 
                     if let Some(token) = self.peek_token() {
                         if !matches!(token.token_type, TokenType::OpenParen) {
-                            return Err(format!("Expected opening parenthesis after {}, found {:?}", name_clone, token.token_type));
+                            return Err(ParseError::unexpected(ParseErrorKind::UnexpectedToken, "'('", token));
                         }
                         self.next_token();
                     }
 
-                    self.parse_color_function(name_clone)?
+                    let kind = self.parse_color_function(name_clone)?;
+                    self.spanned(kind, span)
                 },
                 _ => self.parse_value()?,
             }
         } else {
-            return Err("Unexpected end of input while parsing color argument".to_string());
+            return Err(self.eof_error("color"));
         };
 
         Ok(value)
     }
 
-    fn parse_keyframe_rule(&mut self) -> Result<Rule, String> {
+    fn parse_keyframe_rule(&mut self) -> Result<Rule<'a>, ParseError> {
+        let leading = self.leading_comments();
+        let span = self.start_span();
         let mut selectors = Vec::new();
         let first_selector = self.parse_keyframe_selector()?;
         selectors.push(first_selector);
@@ -1739,76 +3068,92 @@ impl CssParser {
 
         self.expect_open_brace()?;
         let declarations = self.parse_declarations()?;
-        self.expect_close_brace()?;
+        let dangling = self.close_block()?;
+        let span = self.end_span(span);
 
         Ok(Rule {
             selectors,
             declarations,
+            rules: Vec::new(),
+            span,
+            comments: Comments { leading, trailing: self.trailing_comments(), dangling },
         })
     }
 
-    fn parse_keyframe_selector(&mut self) -> Result<Selector, String> {
-        let mut group = SelectorGroup { parts: Vec::new() };
+    /// Parses `from`, `to`, a percentage or a timeline range with a
+    /// percentage, e.g. `cover 50%`, into a selector that is written back as
+    /// it was given. Percentages must be between 0% and 100%.
+    fn parse_keyframe_selector(&mut self) -> Result<Selector<'a>, ParseError> {
+        let span = self.start_span();
+        let Some(token) = self.peek_token() else { return Err(self.eof_error("keyframe selector")) };
 
-        if let Some(token) = self.peek_token() {
-            match &token.token_type {
-                TokenType::Identifier(name) if name.to_lowercase() == "from" || name.to_lowercase() == "to" => {
-                    let name_clone = name.clone();
-                    self.next_token();
-                    group.parts.push(SelectorPart::Element(name_clone));
-                },
-                TokenType::Number(num) => {
-                    let number = *num;
-                    self.next_token();
+        let text: Cow<'a, str> = match &token.token_type {
+            TokenType::Identifier(name) if name.eq_ignore_ascii_case("from") || name.eq_ignore_ascii_case("to") => {
+                let name = name.clone();
+                self.next_token();
+                name
+            },
+            // A named timeline range of a scroll-driven animation, e.g. `cover 50%`.
+            TokenType::Identifier(name) if TimelineRange::from_name(name).is_some() => {
+                let name = name.clone();
+                self.next_token();
+                format!("{} {}", name, self.parse_keyframe_percentage()?).into()
+            },
+            TokenType::Number(_) => self.parse_keyframe_percentage()?.into(),
+            _ => return Err(ParseError::unexpected(ParseErrorKind::InvalidKeyframeSelector, "'from', 'to', percentage or timeline range", token)),
+        };
 
-                    if let Some(token) = self.peek_token() {
-                        if let TokenType::Unit(unit) = &token.token_type {
-                            if unit == "%" {
-                                self.next_token();
-                                group.parts.push(SelectorPart::Element(format!("{}%", number)));
-                            } else {
-                                return Err(format!("Expected % unit in keyframe selector, found {:?}", token.token_type));
-                            }
-                        } else {
-                            return Err(format!("Expected % unit in keyframe selector, found {:?}", token.token_type));
-                        }
-                    } else {
-                        return Err("Unexpected end of input after number in keyframe selector".to_string());
-                    }
-                },
-                _ => return Err(format!("Expected 'from', 'to', or percentage value, found {:?}", token.token_type)),
-            }
-        } else {
-            return Err("Unexpected end of input while parsing keyframe selector".to_string());
-        }
+        let span = self.end_span(span);
+        let group = SelectorGroup { parts: vec![SelectorPart { kind: SelectorPartKind::Element(None, text), span }], span };
 
         Ok(Selector {
             groups: vec![group],
             combinators: Vec::new(),
+            leading_combinator: None,
+            span,
         })
     }
 
-    fn parse_var_function(&mut self) -> Result<Value, String> {
-        self.expect_open_paren()?;
+    /// A percentage from 0% to 100% in a keyframe selector, e.g. `50%`.
+    fn parse_keyframe_percentage(&mut self) -> Result<String, ParseError> {
+        let number = match self.peek_token() {
+            Some(token @ Token { token_type: TokenType::Number(number), .. }) if !(0.0..=100.0).contains(number) => {
+                return Err(ParseError::unexpected(ParseErrorKind::InvalidKeyframeSelector, "percentage from 0% to 100%", token));
+            },
+            Some(Token { token_type: TokenType::Number(number), .. }) => *number,
+            Some(_) => return Err(self.unexpected_here(ParseErrorKind::InvalidKeyframeSelector, "percentage")),
+            None => return Err(self.eof_error("percentage")),
+        };
+        self.next_token();
 
-        let variable_name = match self.next_token() {
-            Some(token) => {
-                if let TokenType::Identifier(name) = token.token_type {
-                    if !name.starts_with("--") {
-                        return Err(format!("Variable name must start with --, found {}", name));
-                    }
-                    name
-                } else {
-                    return Err(format!("Expected variable name, found {:?}", token.token_type));
-                }
+        match self.peek_token() {
+            Some(Token { token_type: TokenType::Unit(unit), .. }) if unit == "%" => {
+                self.next_token();
+                Ok(format!("{}%", number))
             },
-            None => return Err("Unexpected end of input while parsing var function".to_string()),
+            Some(token) => Err(ParseError::unexpected(ParseErrorKind::InvalidKeyframeSelector, "'%'", token)),
+            None => Err(self.eof_error("'%'")),
+        }
+    }
+
+    fn parse_var_function(&mut self) -> Result<ValueKind<'a>, ParseError> {
+        self.expect_open_paren()?;
+
+        let token = self.next_token();
+        let variable_name = if let TokenType::Identifier(name) = &token.token_type {
+            if !name.starts_with("--") {
+                return Err(ParseError::unexpected(ParseErrorKind::InvalidVariableName, "custom property name", &token));
+            }
+            name.clone()
+        } else {
+            return Err(ParseError::unexpected(ParseErrorKind::InvalidVariableName, "custom property name", &token));
         };
 
         let fallback = if let Some(token) = self.peek_token() {
             if matches!(token.token_type, TokenType::Comma) {
                 self.next_token();
 
+                let span = self.start_span();
                 let mut paren_depth = 1;
                 let mut fallback_values = Vec::new();
 
@@ -1834,16 +3179,16 @@ impl CssParser {
                             }
                         }
                     } else {
-                        return Err("Unexpected end of input while parsing var function fallback".to_string());
+                        return Err(self.eof_error("')'"));
                     }
                 }
 
                 if fallback_values.len() > 1 {
-                    Some(Box::new(Value::List(fallback_values)))
+                    Some(Box::new(self.spanned(ValueKind::List(fallback_values), span)))
                 } else if fallback_values.len() == 1 {
                     Some(Box::new(fallback_values.remove(0)))
                 } else {
-                    Some(Box::new(Value::Literal("".to_string())))
+                    Some(Box::new(self.spanned(ValueKind::Literal("".into()), span)))
                 }
             } else {
                 None
@@ -1854,14 +3199,16 @@ impl CssParser {
 
         self.expect_close_paren()?;
 
-        Ok(Value::VarFunction(variable_name, fallback))
+        Ok(ValueKind::VarFunction(variable_name, fallback))
     }
 
-    fn parse_function_argument(&mut self) -> Result<Value, String> {
+    fn parse_function_argument(&mut self) -> Result<Value<'a>, ParseError> {
         self.parse_value()
     }
 
-    fn parse_declaration(&mut self) -> Result<Declaration, String> {
+    fn parse_declaration(&mut self) -> Result<Declaration<'a>, ParseError> {
+        let leading = self.leading_comments();
+        let span = self.start_span();
         let mut is_custom_property = false;
         if let Some(token) = self.peek_token() {
             if let TokenType::Identifier(name) = &token.token_type {
@@ -1871,38 +3218,38 @@ impl CssParser {
             }
         }
 
-        let property = match self.next_token() {
-            Some(token) => {
-                if let TokenType::Identifier(name) = token.token_type {
-                    name
-                } else {
-                    return Err(format!("Expected property name, found {:?}", token.token_type));
-                }
-            },
-            None => return Err("Unexpected end of input while parsing property".to_string()),
+        // `*property: value` is an old IE hack.
+        let star_hack = self.lexer.dialect().allows_hacks() &&
+            self.peek_token().is_some_and(|token| token.token_type == TokenType::Asterisk);
+        if star_hack {
+            self.next_token();
+        }
+
+        let token = self.next_token();
+        let property = if let TokenType::Identifier(name) = token.token_type {
+            if star_hack { Cow::Owned(format!("*{}", name)) } else { name }
+        } else {
+            return Err(ParseError::unexpected(ParseErrorKind::ExpectedPropertyName, "property name", &token));
         };
 
         self.expect_colon()?;
 
-        let value = self.parse_value_possibly_list()?;
+        let value = self.parse_value_for_property(&property)?;
 
         let mut is_important = false;
         if let Some(token) = self.peek_token() {
             if matches!(token.token_type, TokenType::ExclamationMark) {
                 self.next_token();
 
-                if let Some(token) = self.next_token() {
-                    if let TokenType::Identifier(name) = token.token_type {
-                        if name.to_lowercase() == "important" {
-                            is_important = true;
-                        } else {
-                            return Err(format!("Expected 'important' after '!', found {}", name));
-                        }
+                let token = self.next_token();
+                if let TokenType::Identifier(name) = &token.token_type {
+                    if name.eq_ignore_ascii_case("important") {
+                        is_important = true;
                     } else {
-                        return Err(format!("Expected 'important' after '!', found {:?}", token.token_type));
+                        return Err(ParseError::unexpected(ParseErrorKind::InvalidImportant, "'important'", &token));
                     }
                 } else {
-                    return Err("Unexpected end of input after '!'".to_string());
+                    return Err(ParseError::unexpected(ParseErrorKind::InvalidImportant, "'important'", &token));
                 }
             }
         }
@@ -1912,123 +3259,106 @@ impl CssParser {
             value,
             is_custom_property,
             is_important,
+            span: self.end_span(span),
+            comments: Comments { leading, ..Comments::default() },
         })
     }
 
     // TODO handle cases like font-family: Fira Code, Fira Mono, Menlo, Consolas, DejaVu Sans Mono, monospace; correctly
-    fn parse_value_possibly_list(&mut self) -> Result<Value, String> {
+    fn parse_value_possibly_list(&mut self, font_families: bool) -> Result<Value<'a>, ParseError> {
+        let span = self.start_span();
         let first_value = self.parse_value()?;
 
         let mut values = vec![first_value];
         let mut current_unquoted_string = String::new();
         let mut building_unquoted_font = false;
 
-        loop {
-            if let Some(token) = self.peek_token() {
-                match &token.token_type {
-                    TokenType::Semicolon | TokenType::CloseBrace | TokenType::ExclamationMark => {
-                        if building_unquoted_font && !current_unquoted_string.is_empty() {
-                            if let Some(last) = values.last_mut() {
-                                if let Value::Literal(name) = last {
-                                    *name = current_unquoted_string.trim().to_string();
-                                }
-                            }
-                        }
-                        break;
-                    },
-                    TokenType::Comma => {
-                        if building_unquoted_font && !current_unquoted_string.is_empty() {
-                            if let Some(last) = values.last_mut() {
-                                if let Value::Literal(name) = last {
-                                    *name = current_unquoted_string.trim().to_string();
-                                }
-                            }
-                            building_unquoted_font = false;
-                            current_unquoted_string.clear();
-                        }
-
-                        self.next_token();
+        while let Some(token) = self.peek_token() {
+            match &token.token_type {
+                TokenType::Semicolon | TokenType::CloseBrace | TokenType::ExclamationMark | TokenType::EOF => {
+                    if building_unquoted_font && !current_unquoted_string.is_empty() {
+                        self.join_font_family(values.last_mut(), &current_unquoted_string);
+                    }
+                    break;
+                },
+                TokenType::Comma => {
+                    if building_unquoted_font && !current_unquoted_string.is_empty() {
+                        self.join_font_family(values.last_mut(), &current_unquoted_string);
+                        building_unquoted_font = false;
+                        current_unquoted_string.clear();
+                    }
 
-                        values.push(Value::Literal(",".to_string()));
+                    values.push(self.parse_separator());
 
-                        let next_value = self.parse_value()?;
-                        values.push(next_value);
+                    let next_value = self.parse_value()?;
+                    values.push(next_value);
 
-                        if let Value::Literal(_) = values.last().unwrap() {
-                            building_unquoted_font = true;
-                            if let Value::Literal(name) = values.last().unwrap() {
-                                current_unquoted_string = name.clone();
-                            }
-                        }
-                    },
-                    TokenType::Identifier(ident) => {
-                        if building_unquoted_font {
-                            current_unquoted_string.push(' ');
-                            current_unquoted_string.push_str(ident);
-                            self.next_token();
-                        } else {
-                            let result = self.parse_value();
-                            match result {
-                                Ok(next_value) => {
-                                    values.push(next_value);
-                                },
-                                Err(_) => {
-                                    break;
-                                }
-                            }
-                        }
+                    if let Some(ValueKind::Literal(name)) = values.last().map(|value| &value.kind).filter(|_| font_families) {
+                        building_unquoted_font = true;
+                        current_unquoted_string = name.to_string();
+                    }
+                },
+                TokenType::Identifier(ident)
+                    if building_unquoted_font => {
+                        current_unquoted_string.push(' ');
+                        current_unquoted_string.push_str(ident);
+                        self.next_token();
                     },
-                    _ => {
-                        let result = self.parse_value();
-                        match result {
-                            Ok(next_value) => {
-                                values.push(next_value);
-                            },
-                            Err(_) => {
-                                break;
-                            }
+                _ => {
+                    let result = self.parse_value();
+                    match result {
+                        Ok(next_value) => {
+                            values.push(next_value);
+                        },
+                        Err(_) => {
+                            break;
                         }
                     }
                 }
-            } else {
-                break;
             }
         }
 
         if building_unquoted_font && !current_unquoted_string.is_empty() {
-            if let Some(last) = values.last_mut() {
-                if let Value::Literal(name) = last {
-                    *name = current_unquoted_string.trim().to_string();
-                }
-            }
+            self.join_font_family(values.last_mut(), &current_unquoted_string);
         }
 
         if values.len() == 1 {
             Ok(values.remove(0))
         } else {
-            Ok(Value::List(values))
+            Ok(self.spanned(ValueKind::List(values), span))
         }
     }
 
-    fn expect_open_paren(&mut self) -> Result<(), String> {
-        if let Some(token) = self.next_token() {
-            match token.token_type {
-                TokenType::OpenParen => Ok(()),
-                _ => Err(format!("Expected opening parenthesis, found {:?}", token.token_type)),
+    /// Consumes a `,` or `/` between values and keeps it as a literal.
+    fn parse_separator(&mut self) -> Value<'a> {
+        let span = self.start_span();
+        let token = self.next_token();
+        self.spanned(ValueKind::Literal(token.token_type.to_string().into()), span)
+    }
+
+    /// Replaces the first word of an unquoted font family with all of its words.
+    fn join_font_family(&self, value: Option<&mut Value<'a>>, words: &str) {
+        if let Some(value) = value {
+            if let ValueKind::Literal(name) = &mut value.kind {
+                *name = words.trim().to_string().into();
+                value.span = self.end_span(value.span);
             }
-        } else {
-            Err("Unexpected end of input".to_string())
         }
     }
 
-    fn expect_close_paren(&mut self) -> Result<(), String> {
-        if let Some(token) = self.next_token() {
-            match token.token_type {
-                TokenType::CloseParen => Ok(()),
-                _ => Err(format!("Expected closing parenthesis, found {:?}", token.token_type)),
-            }
-        } else {
-            Err("Unexpected end of input".to_string())
+    fn expect_open_paren(&mut self) -> Result<(), ParseError> {
+        let token = self.next_token();
+        match token.token_type {
+            TokenType::OpenParen => Ok(()),
+            _ => Err(ParseError::unexpected(ParseErrorKind::UnexpectedToken, "'('", &token)),
+        }
+    }
+
+    fn expect_close_paren(&mut self) -> Result<(), ParseError> {
+        let token = self.next_token();
+        match token.token_type {
+            TokenType::CloseParen => Ok(()),
+            _ => Err(ParseError::unexpected(ParseErrorKind::UnexpectedToken, "')'", &token)),
         }
     }
 }
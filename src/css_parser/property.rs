@@ -0,0 +1,507 @@
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::fmt;
+
+use crate::css_parser::ast::{comma_separated, owned, AtRule, AtRuleType, CalcExpression, CalcOperator, Color, CssRule, Declaration, Stylesheet, Unit, Value, ValueKind};
+use crate::css_parser::error::PropertyError;
+
+const CSS_WIDE_KEYWORDS: [&str; 5] = ["inherit", "initial", "unset", "revert", "revert-layer"];
+
+const RELATIVE_LENGTH_UNITS: [&str; 36] = [
+    "em", "rem", "ex", "rex", "ch", "rch", "ic", "ric", "cap", "rcap", "lh", "rlh",
+    "vw", "vh", "vi", "vb", "vmin", "vmax", "svw", "svh", "lvw", "lvh", "dvw", "dvh",
+    "svmin", "svmax", "lvmin", "lvmax", "dvmin", "dvmax", "cqw", "cqh", "cqi", "cqb", "cqmin", "cqmax",
+];
+
+const ABSOLUTE_LENGTH_UNITS: [&str; 7] = ["px", "cm", "mm", "q", "in", "pt", "pc"];
+
+const COLOR_FUNCTIONS: [&str; 12] = ["rgb", "rgba", "hsl", "hsla", "hwb", "lab", "lch", "oklab", "oklch", "color", "color-mix", "light-dark"];
+
+const IMAGE_FUNCTIONS: [&str; 10] = [
+    "url", "linear-gradient", "radial-gradient", "conic-gradient", "repeating-linear-gradient",
+    "repeating-radial-gradient", "repeating-conic-gradient", "image", "image-set", "cross-fade",
+];
+
+const TRANSFORM_FUNCTIONS: [&str; 21] = [
+    "matrix", "matrix3d", "translate", "translate3d", "translatex", "translatey", "translatez",
+    "scale", "scale3d", "scalex", "scaley", "scalez", "rotate", "rotate3d", "rotatex", "rotatey",
+    "rotatez", "skew", "skewx", "skewy", "perspective",
+];
+
+/// A registered custom property from an `@property` rule.
+#[derive(Debug, Clone)]
+pub struct PropertyRule<'a> {
+    pub name: Cow<'a, str>, // --gap
+    pub syntax: PropertySyntax,
+    pub inherits: bool,
+    pub initial_value: Option<Value<'a>>, // only optional for the universal syntax
+}
+
+impl<'a> PropertyRule<'a> {
+    /// Reads and validates the descriptors of an `@property` rule: `syntax`
+    /// and `inherits` are required, and `initial-value` unless the syntax is
+    /// `*`. The initial value must match the syntax without being a CSS-wide
+    /// keyword and be computationally independent, so no relative lengths or
+    /// `var()`.
+    pub fn from_at_rule(at_rule: &AtRule<'a>) -> Result<Self, PropertyError> {
+        let name = at_rule.query.trim();
+        if at_rule.rule_type != AtRuleType::Property || !name.starts_with("--") || name.len() < 3 {
+            return Err(PropertyError::InvalidName(name.to_string()));
+        }
+
+        let declarations: Vec<_> = at_rule.style_rules().flat_map(|rule| &rule.declarations).collect();
+        let descriptor = |descriptor: &str| declarations.iter().rev().find(|declaration| declaration.property.eq_ignore_ascii_case(descriptor));
+        let missing = |descriptor: &'static str| PropertyError::MissingDescriptor { property: name.to_string(), descriptor };
+
+        let syntax = match &descriptor("syntax").ok_or_else(|| missing("syntax"))?.value.kind {
+            ValueKind::QuotedString(syntax) => PropertySyntax::parse(syntax),
+            kind => Err(kind.to_string()),
+        }
+        .map_err(|syntax| PropertyError::InvalidSyntax { property: name.to_string(), syntax })?;
+
+        let inherits = match descriptor("inherits").ok_or_else(|| missing("inherits"))?.value.to_string().to_ascii_lowercase().as_str() {
+            "true" => true,
+            "false" => false,
+            _ => return Err(missing("inherits")),
+        };
+
+        let initial_value = descriptor("initial-value").map(|declaration| declaration.value.clone());
+        match &initial_value {
+            None if syntax != PropertySyntax::Universal => return Err(missing("initial-value")),
+            Some(value) if is_css_wide_keyword(value) || !syntax.matches(value) => {
+                return Err(PropertyError::InvalidInitialValue { property: name.to_string(), value: value.to_string() });
+            },
+            Some(value) if !is_computationally_independent(value) => {
+                return Err(PropertyError::ComputationallyDependent { property: name.to_string(), value: value.to_string() });
+            },
+            _ => {},
+        }
+
+        Ok(PropertyRule { name: Cow::Owned(name.to_string()), syntax, inherits, initial_value })
+    }
+
+    pub fn into_owned(self) -> PropertyRule<'static> {
+        PropertyRule {
+            name: owned(self.name),
+            syntax: self.syntax,
+            inherits: self.inherits,
+            initial_value: self.initial_value.map(Value::into_owned),
+        }
+    }
+}
+
+/// Value of the `syntax` descriptor, e.g. `"<length> | auto"`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum PropertySyntax {
+    Universal,                          // *, any value
+    Alternatives(Vec<SyntaxComponent>), // separated by |
+}
+
+impl PropertySyntax {
+    /// Parses a syntax string; the error is the rejected string.
+    pub fn parse(syntax: &str) -> Result<Self, String> {
+        let syntax = syntax.trim();
+        if syntax == "*" {
+            return Ok(PropertySyntax::Universal);
+        }
+
+        syntax
+            .split('|')
+            .map(|component| SyntaxComponent::parse(component.trim()))
+            .collect::<Option<Vec<_>>>()
+            .map(PropertySyntax::Alternatives)
+            .ok_or_else(|| syntax.to_string())
+    }
+
+    /// Whether `value` is valid for the syntax. CSS-wide keywords are valid
+    /// for every syntax.
+    pub fn matches(&self, value: &Value) -> bool {
+        match self {
+            PropertySyntax::Universal => true,
+            PropertySyntax::Alternatives(components) => is_css_wide_keyword(value) || components.iter().any(|component| component.matches(value)),
+        }
+    }
+}
+
+fn is_css_wide_keyword(value: &Value) -> bool {
+    matches!(&value.kind, ValueKind::Literal(keyword) | ValueKind::Keyword(keyword)
+        if CSS_WIDE_KEYWORDS.iter().any(|css_wide| keyword.eq_ignore_ascii_case(css_wide)))
+}
+
+impl fmt::Display for PropertySyntax {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PropertySyntax::Universal => write!(f, "*"),
+            PropertySyntax::Alternatives(components) => {
+                for (i, component) in components.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, " | ")?;
+                    }
+                    write!(f, "{}", component)?;
+                }
+                Ok(())
+            },
+        }
+    }
+}
+
+/// One alternative of a syntax string, e.g. `<length>+`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SyntaxComponent {
+    pub kind: SyntaxType,
+    pub multiplier: Option<Multiplier>,
+}
+
+impl SyntaxComponent {
+    fn parse(text: &str) -> Option<Self> {
+        let (name, multiplier) = match text.chars().last()? {
+            '+' => (&text[..text.len() - 1], Some(Multiplier::Space)),
+            '#' => (&text[..text.len() - 1], Some(Multiplier::Comma)),
+            _ => (text, None),
+        };
+
+        let kind = match name.strip_prefix('<').and_then(|name| name.strip_suffix('>')) {
+            Some(data_type) => SyntaxType::from_name(data_type)?,
+            None => {
+                let valid = !name.is_empty()
+                    && !name.starts_with(|ch: char| ch.is_ascii_digit())
+                    && name.chars().all(|ch| ch.is_alphanumeric() || ch == '-' || ch == '_')
+                    && !CSS_WIDE_KEYWORDS.iter().chain(&["default"]).any(|keyword| name.eq_ignore_ascii_case(keyword));
+                if !valid {
+                    return None;
+                }
+                SyntaxType::Ident(name.to_string())
+            },
+        };
+
+        // A transform list is already a list.
+        if kind == SyntaxType::TransformList && multiplier.is_some() {
+            return None;
+        }
+
+        Some(SyntaxComponent { kind, multiplier })
+    }
+
+    fn matches(&self, value: &Value) -> bool {
+        let items = value.items();
+
+        match self.multiplier {
+            None if self.kind == SyntaxType::TransformList => items.iter().all(|item| SyntaxType::TransformFunction.matches(item)),
+            None => items.len() == 1 && self.kind.matches(items[0]),
+            Some(Multiplier::Space) => items.iter().all(|item| self.kind.matches(item)),
+            Some(Multiplier::Comma) => comma_separated(value).iter().all(|group| group.len() == 1 && self.kind.matches(group[0])),
+        }
+    }
+}
+
+impl fmt::Display for SyntaxComponent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.kind {
+            SyntaxType::Ident(name) => write!(f, "{}", name)?,
+            kind => write!(f, "<{}>", kind.name())?,
+        }
+
+        match self.multiplier {
+            Some(Multiplier::Space) => write!(f, "+"),
+            Some(Multiplier::Comma) => write!(f, "#"),
+            None => Ok(()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Multiplier {
+    Space, // +, a space-separated list
+    Comma, // #, a comma-separated list
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum SyntaxType {
+    Length,
+    Number,
+    Percentage,
+    LengthPercentage,
+    Color,
+    Image,
+    Url,
+    Integer,
+    Angle,
+    Time,
+    Resolution,
+    TransformFunction,
+    CustomIdent,
+    TransformList,
+    String,
+    Ident(String), // a literal keyword such as `auto`
+}
+
+impl SyntaxType {
+    fn from_name(name: &str) -> Option<Self> {
+        let kind = match name {
+            "length" => SyntaxType::Length,
+            "number" => SyntaxType::Number,
+            "percentage" => SyntaxType::Percentage,
+            "length-percentage" => SyntaxType::LengthPercentage,
+            "color" => SyntaxType::Color,
+            "image" => SyntaxType::Image,
+            "url" => SyntaxType::Url,
+            "integer" => SyntaxType::Integer,
+            "angle" => SyntaxType::Angle,
+            "time" => SyntaxType::Time,
+            "resolution" => SyntaxType::Resolution,
+            "transform-function" => SyntaxType::TransformFunction,
+            "custom-ident" => SyntaxType::CustomIdent,
+            "transform-list" => SyntaxType::TransformList,
+            "string" => SyntaxType::String,
+            _ => return None,
+        };
+        Some(kind)
+    }
+
+    fn name(&self) -> &str {
+        match self {
+            SyntaxType::Length => "length",
+            SyntaxType::Number => "number",
+            SyntaxType::Percentage => "percentage",
+            SyntaxType::LengthPercentage => "length-percentage",
+            SyntaxType::Color => "color",
+            SyntaxType::Image => "image",
+            SyntaxType::Url => "url",
+            SyntaxType::Integer => "integer",
+            SyntaxType::Angle => "angle",
+            SyntaxType::Time => "time",
+            SyntaxType::Resolution => "resolution",
+            SyntaxType::TransformFunction => "transform-function",
+            SyntaxType::CustomIdent => "custom-ident",
+            SyntaxType::TransformList => "transform-list",
+            SyntaxType::String => "string",
+            SyntaxType::Ident(name) => name,
+        }
+    }
+
+    /// Whether a single component value is of this type.
+    fn matches(&self, value: &Value) -> bool {
+        let function_name = match &value.kind {
+            ValueKind::Function(name, _) => Some(name.to_ascii_lowercase()),
+            _ => None,
+        };
+        let is_function = |names: &[&str]| function_name.as_deref().is_some_and(|name| names.contains(&name));
+
+        match self {
+            SyntaxType::Length => matches!(dimension(value), Some(Dimension::Length)) || is_zero(value),
+            SyntaxType::Number => matches!(dimension(value), Some(Dimension::Number)),
+            SyntaxType::Integer => match &value.kind {
+                ValueKind::Number(number, None | Some(Unit::None)) => number.fract() == 0.0,
+                kind => matches!(kind, ValueKind::Calc(_)) && dimension(value) == Some(Dimension::Number),
+            },
+            SyntaxType::Percentage => matches!(dimension(value), Some(Dimension::Percentage)),
+            SyntaxType::LengthPercentage => {
+                matches!(dimension(value), Some(Dimension::Length | Dimension::Percentage | Dimension::LengthPercentage)) || is_zero(value)
+            },
+            SyntaxType::Angle => matches!(dimension(value), Some(Dimension::Angle)) || is_zero(value),
+            SyntaxType::Time => matches!(dimension(value), Some(Dimension::Time)),
+            SyntaxType::Resolution => matches!(dimension(value), Some(Dimension::Resolution)),
+            SyntaxType::Color => match &value.kind {
+                ValueKind::Color(_) => true,
+                ValueKind::Literal(name) | ValueKind::Keyword(name) => name.eq_ignore_ascii_case("currentcolor"),
+                _ => is_function(&COLOR_FUNCTIONS),
+            },
+            SyntaxType::Image => is_function(&IMAGE_FUNCTIONS),
+            SyntaxType::Url => is_function(&["url"]),
+            SyntaxType::TransformFunction => is_function(&TRANSFORM_FUNCTIONS),
+            SyntaxType::TransformList => SyntaxComponent { kind: SyntaxType::TransformList, multiplier: None }.matches(value),
+            SyntaxType::CustomIdent => match &value.kind {
+                ValueKind::Literal(name) | ValueKind::Keyword(name) | ValueKind::Color(Color::Named(name)) => {
+                    !name.starts_with(|ch: char| ch.is_ascii_digit() || ch == '"' || ch == '\'' || ch == ',')
+                        && !CSS_WIDE_KEYWORDS.iter().chain(&["default"]).any(|keyword| name.eq_ignore_ascii_case(keyword))
+                },
+                _ => false,
+            },
+            SyntaxType::String => matches!(value.kind, ValueKind::QuotedString(_)),
+            SyntaxType::Ident(ident) => match &value.kind {
+                ValueKind::Literal(name) | ValueKind::Keyword(name) | ValueKind::Color(Color::Named(name)) => name == ident,
+                _ => false,
+            },
+        }
+    }
+}
+
+/// Type of a numeric value, after resolving `calc()`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Dimension {
+    Number,
+    Length,
+    Percentage,
+    LengthPercentage, // calc() mixing both
+    Angle,
+    Time,
+    Resolution,
+}
+
+fn dimension(value: &Value) -> Option<Dimension> {
+    match &value.kind {
+        ValueKind::Number(_, unit) => unit_dimension(unit.as_ref()),
+        ValueKind::Calc(expression) => calc_dimension(expression),
+        _ => None,
+    }
+}
+
+fn unit_dimension(unit: Option<&Unit>) -> Option<Dimension> {
+    let unit = match unit {
+        None | Some(Unit::None) => return Some(Dimension::Number),
+        Some(unit) => unit.to_string().to_ascii_lowercase(),
+    };
+
+    match unit.as_str() {
+        "%" => Some(Dimension::Percentage),
+        "deg" | "rad" | "grad" | "turn" => Some(Dimension::Angle),
+        "s" | "ms" => Some(Dimension::Time),
+        "dpi" | "dpcm" | "dppx" | "x" => Some(Dimension::Resolution),
+        unit if RELATIVE_LENGTH_UNITS.contains(&unit) || ABSOLUTE_LENGTH_UNITS.contains(&unit) => Some(Dimension::Length),
+        _ => None,
+    }
+}
+
+/// Type of a `calc()` expression, `None` if it mixes incompatible types or
+/// refers to a variable.
+fn calc_dimension(expression: &CalcExpression) -> Option<Dimension> {
+    let sum = |left: Dimension, right: Dimension| match (left, right) {
+        (left, right) if left == right => Some(left),
+        (Dimension::Length | Dimension::Percentage | Dimension::LengthPercentage, Dimension::Length | Dimension::Percentage | Dimension::LengthPercentage) => {
+            Some(Dimension::LengthPercentage)
+        },
+        _ => None,
+    };
+
+    match expression {
+        CalcExpression::Number(_, unit) => unit_dimension(unit.as_ref()),
+        CalcExpression::Variable(_) => None,
+        CalcExpression::Parenthesized(expression) => calc_dimension(expression),
+        CalcExpression::Function(name, args) if ["min", "max", "clamp"].iter().any(|function| name.eq_ignore_ascii_case(function)) => {
+            let mut dimensions = args.iter().map(calc_dimension);
+            let first = dimensions.next()??;
+            dimensions.try_fold(first, |total, dimension| sum(total, dimension?))
+        },
+        CalcExpression::Function(..) => None,
+        CalcExpression::BinaryOperation(left, operator, right) => {
+            let (left, right) = (calc_dimension(left)?, calc_dimension(right)?);
+            match operator {
+                CalcOperator::Add | CalcOperator::Subtract => sum(left, right),
+                CalcOperator::Multiply if left == Dimension::Number => Some(right),
+                CalcOperator::Multiply | CalcOperator::Divide if right == Dimension::Number => Some(left),
+                _ => None,
+            }
+        },
+    }
+}
+
+fn is_zero(value: &Value) -> bool {
+    matches!(value.kind, ValueKind::Number(number, None | Some(Unit::None)) if number == 0.0)
+}
+
+fn is_computationally_independent(value: &Value) -> bool {
+    fn independent_calc(expression: &CalcExpression) -> bool {
+        match expression {
+            CalcExpression::Number(_, unit) => unit.as_ref().is_none_or(|unit| !is_relative(unit)),
+            CalcExpression::Variable(_) => false,
+            CalcExpression::Parenthesized(expression) => independent_calc(expression),
+            CalcExpression::Function(_, args) => args.iter().all(independent_calc),
+            CalcExpression::BinaryOperation(left, _, right) => independent_calc(left) && independent_calc(right),
+        }
+    }
+
+    match &value.kind {
+        ValueKind::Number(_, unit) => unit.as_ref().is_none_or(|unit| !is_relative(unit)),
+        ValueKind::VarFunction(..) => false,
+        ValueKind::Calc(expression) => independent_calc(expression),
+        ValueKind::Function(_, args) | ValueKind::List(args) => args.iter().all(is_computationally_independent),
+        _ => true,
+    }
+}
+
+fn is_relative(unit: &Unit) -> bool {
+    RELATIVE_LENGTH_UNITS.contains(&unit.to_string().to_ascii_lowercase().as_str())
+}
+
+/// Whether the value can only be checked once `var()` is substituted.
+fn contains_var(value: &Value) -> bool {
+    fn calc_contains_var(expression: &CalcExpression) -> bool {
+        match expression {
+            CalcExpression::Variable(_) => true,
+            CalcExpression::Number(..) => false,
+            CalcExpression::Parenthesized(expression) => calc_contains_var(expression),
+            CalcExpression::Function(_, args) => args.iter().any(calc_contains_var),
+            CalcExpression::BinaryOperation(left, _, right) => calc_contains_var(left) || calc_contains_var(right),
+        }
+    }
+
+    match &value.kind {
+        ValueKind::VarFunction(..) => true,
+        ValueKind::Calc(expression) => calc_contains_var(expression),
+        ValueKind::Function(_, args) | ValueKind::List(args) => args.iter().any(contains_var),
+        _ => false,
+    }
+}
+
+/// The custom properties registered by the `@property` rules of a stylesheet.
+#[derive(Debug, Default)]
+pub struct PropertyRegistry<'a> {
+    properties: HashMap<String, PropertyRule<'a>>,
+    errors: Vec<PropertyError>, // @property rules that were ignored
+}
+
+impl<'a> PropertyRegistry<'a> {
+    /// Registers every valid `@property` rule, including those inside
+    /// conditional rules; of several rules for one property the last wins.
+    pub fn from_stylesheet(stylesheet: &Stylesheet<'a>) -> Self {
+        let mut registry = PropertyRegistry::default();
+        for at_rule in stylesheet.at_rules_of_type(AtRuleType::Property) {
+            match PropertyRule::from_at_rule(at_rule) {
+                Ok(property) => registry.register(property),
+                Err(error) => registry.errors.push(error),
+            }
+        }
+        registry
+    }
+
+    pub fn register(&mut self, property: PropertyRule<'a>) {
+        self.properties.insert(property.name.to_string(), property);
+    }
+
+    pub fn get(&self, name: &str) -> Option<&PropertyRule<'a>> {
+        self.properties.get(name)
+    }
+
+    /// Why the invalid `@property` rules of the stylesheet were ignored.
+    pub fn errors(&self) -> &[PropertyError] {
+        &self.errors
+    }
+
+    /// Checks a declaration of a registered custom property against its
+    /// syntax. Other declarations, and values using `var()`, always pass.
+    pub fn check_declaration(&self, declaration: &Declaration) -> Result<(), PropertyError> {
+        let Some(property) = self.get(&declaration.property) else { return Ok(()) };
+
+        if contains_var(&declaration.value) || property.syntax.matches(&declaration.value) {
+            Ok(())
+        } else {
+            Err(PropertyError::ValueMismatch {
+                property: declaration.property.to_string(),
+                value: declaration.value.to_string(),
+                syntax: property.syntax.to_string(),
+                span: declaration.value.span,
+            })
+        }
+    }
+
+    /// Checks every declaration of a registered custom property in the
+    /// stylesheet, including nested rules, in source order.
+    pub fn check_stylesheet(&self, stylesheet: &Stylesheet) -> Vec<PropertyError> {
+        stylesheet
+            .descendants()
+            .filter_map(CssRule::as_style)
+            .flat_map(|rule| &rule.declarations)
+            .filter_map(|declaration| self.check_declaration(declaration).err())
+            .collect()
+    }
+}
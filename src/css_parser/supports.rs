@@ -0,0 +1,176 @@
+use std::borrow::Cow;
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+
+use crate::css_parser::ast::{owned, Selector, SelectorPartKind};
+
+/// Condition of an `@supports` rule, e.g. `(display: grid) and selector(:has(a))`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum SupportsCondition<'a> {
+    Not(Box<SupportsCondition<'a>>),
+    And(Vec<SupportsCondition<'a>>),
+    Or(Vec<SupportsCondition<'a>>),
+    Declaration {                 // (display: grid)
+        property: Cow<'a, str>,
+        value: String,            // normalized value text
+    },
+    Selector(Vec<Selector<'a>>),  // selector(:has(> img))
+    FontTech(Cow<'a, str>),       // font-tech(color-COLRv1)
+    FontFormat(Cow<'a, str>),     // font-format(woff2)
+    GeneralEnclosed(String),      // anything else in parentheses, never supported
+}
+
+impl SupportsCondition<'_> {
+    /// Whether the condition holds for an engine described by `features`.
+    pub fn evaluate(&self, features: &dyn FeatureSupport) -> bool {
+        match self {
+            SupportsCondition::Not(condition) => !condition.evaluate(features),
+            SupportsCondition::And(conditions) => conditions.iter().all(|condition| condition.evaluate(features)),
+            SupportsCondition::Or(conditions) => conditions.iter().any(|condition| condition.evaluate(features)),
+            SupportsCondition::Declaration { property, value } => features.supports_declaration(property, value),
+            SupportsCondition::Selector(selectors) => selectors.iter().all(|selector| features.supports_selector(selector)),
+            SupportsCondition::FontTech(tech) => features.supports_font_tech(tech),
+            SupportsCondition::FontFormat(format) => features.supports_font_format(format),
+            SupportsCondition::GeneralEnclosed(_) => false,
+        }
+    }
+
+    pub fn into_owned(self) -> SupportsCondition<'static> {
+        match self {
+            SupportsCondition::Not(condition) => SupportsCondition::Not(Box::new(condition.into_owned())),
+            SupportsCondition::And(conditions) => SupportsCondition::And(conditions.into_iter().map(SupportsCondition::into_owned).collect()),
+            SupportsCondition::Or(conditions) => SupportsCondition::Or(conditions.into_iter().map(SupportsCondition::into_owned).collect()),
+            SupportsCondition::Declaration { property, value } => SupportsCondition::Declaration { property: owned(property), value },
+            SupportsCondition::Selector(selectors) => SupportsCondition::Selector(selectors.into_iter().map(Selector::into_owned).collect()),
+            SupportsCondition::FontTech(tech) => SupportsCondition::FontTech(owned(tech)),
+            SupportsCondition::FontFormat(format) => SupportsCondition::FontFormat(owned(format)),
+            SupportsCondition::GeneralEnclosed(text) => SupportsCondition::GeneralEnclosed(text),
+        }
+    }
+
+    /// Writes the condition, wrapping `not`, `and` and `or` in parentheses.
+    fn fmt_in_parens(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SupportsCondition::Not(_) | SupportsCondition::And(_) | SupportsCondition::Or(_) => write!(f, "({})", self),
+            _ => write!(f, "{}", self),
+        }
+    }
+}
+
+impl fmt::Display for SupportsCondition<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SupportsCondition::Not(condition) => {
+                write!(f, "not ")?;
+                condition.fmt_in_parens(f)
+            },
+            SupportsCondition::And(conditions) | SupportsCondition::Or(conditions) => {
+                let operator = if matches!(self, SupportsCondition::And(_)) { " and " } else { " or " };
+                for (i, condition) in conditions.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, "{}", operator)?;
+                    }
+                    condition.fmt_in_parens(f)?;
+                }
+                Ok(())
+            },
+            SupportsCondition::Declaration { property, value } => write!(f, "({}: {})", property, value),
+            SupportsCondition::Selector(selectors) => {
+                write!(f, "selector(")?;
+                for (i, selector) in selectors.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{}", selector)?;
+                }
+                write!(f, ")")
+            },
+            SupportsCondition::FontTech(tech) => write!(f, "font-tech({})", tech),
+            SupportsCondition::FontFormat(format) => write!(f, "font-format({})", format),
+            SupportsCondition::GeneralEnclosed(text) => write!(f, "{}", text),
+        }
+    }
+}
+
+/// Answers the individual feature tests of an `@supports` condition.
+pub trait FeatureSupport {
+    fn supports_declaration(&self, property: &str, value: &str) -> bool;
+    fn supports_selector(&self, selector: &Selector<'_>) -> bool;
+    fn supports_font_tech(&self, tech: &str) -> bool;
+    fn supports_font_format(&self, format: &str) -> bool;
+}
+
+/// `FeatureSupport` backed by explicit lists, e.g. for a target engine.
+/// Names are compared case-insensitively.
+#[derive(Debug, Clone, Default)]
+pub struct SupportTable {
+    properties: HashMap<String, HashSet<String>>, // property to supported values, empty for any value
+    pseudo_selectors: HashSet<String>,            // `:has`, `::backdrop`, ...
+    font_techs: HashSet<String>,
+    font_formats: HashSet<String>,
+}
+
+impl SupportTable {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Supports `property` with any of `values`, or with any value at all if
+    /// `values` is empty.
+    pub fn with_property(mut self, property: &str, values: &[&str]) -> Self {
+        let supported = self.properties.entry(property.to_ascii_lowercase()).or_default();
+        supported.extend(values.iter().map(|value| value.to_ascii_lowercase()));
+        self
+    }
+
+    /// Supports a pseudo-class or pseudo-element, given with its colons.
+    pub fn with_selector(mut self, pseudo: &str) -> Self {
+        self.pseudo_selectors.insert(pseudo.to_ascii_lowercase());
+        self
+    }
+
+    pub fn with_font_tech(mut self, tech: &str) -> Self {
+        self.font_techs.insert(tech.to_ascii_lowercase());
+        self
+    }
+
+    pub fn with_font_format(mut self, format: &str) -> Self {
+        self.font_formats.insert(format.to_ascii_lowercase());
+        self
+    }
+}
+
+impl FeatureSupport for SupportTable {
+    fn supports_declaration(&self, property: &str, value: &str) -> bool {
+        // Custom properties accept any value.
+        if property.starts_with("--") {
+            return true;
+        }
+
+        match self.properties.get(&property.to_ascii_lowercase()) {
+            Some(values) => values.is_empty() || values.contains(&value.to_ascii_lowercase()),
+            None => false,
+        }
+    }
+
+    /// Type, class, id and attribute selectors are always supported, pseudo
+    /// selectors only when listed.
+    fn supports_selector(&self, selector: &Selector<'_>) -> bool {
+        selector.groups.iter().flat_map(|group| &group.parts).all(|part| {
+            let pseudo = match &part.kind {
+                SelectorPartKind::PseudoClass(name) | SelectorPartKind::PseudoClassFunction(name, _) => format!(":{}", name),
+                SelectorPartKind::PseudoElement(name) => format!("::{}", name),
+                _ => return true,
+            };
+            self.pseudo_selectors.contains(&pseudo.to_ascii_lowercase())
+        })
+    }
+
+    fn supports_font_tech(&self, tech: &str) -> bool {
+        self.font_techs.contains(&tech.to_ascii_lowercase())
+    }
+
+    fn supports_font_format(&self, format: &str) -> bool {
+        self.font_formats.contains(&format.to_ascii_lowercase())
+    }
+}
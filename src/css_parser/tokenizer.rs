@@ -0,0 +1,647 @@
+use std::fmt;
+
+/// Token types of CSS Syntax Level 3 (§4). Unlike `lexer::TokenType` these
+/// follow the specification exactly, including the error tokens.
+#[derive(Debug, Clone, PartialEq)]
+pub enum CssTokenType {
+    Ident(String),
+    Function(String),            // name without the '('
+    AtKeyword(String),           // name without the '@'
+    Hash { value: String, is_id: bool },
+    String(String),
+    BadString,
+    Url(String),
+    BadUrl,
+    Delim(char),
+    Number { value: f64, is_integer: bool },
+    Percentage(f64),
+    Dimension { value: f64, is_integer: bool, unit: String },
+    Whitespace,
+    Comment(String),             // text between `/*` and `*/`, only from `Tokenizer::keeping_comments`
+    CDO,                         // <!--
+    CDC,                         // -->
+    Colon,
+    Semicolon,
+    Comma,
+    OpenBracket,
+    CloseBracket,
+    OpenParen,
+    CloseParen,
+    OpenBrace,
+    CloseBrace,
+    EOF,
+}
+
+pub(crate) fn write_escaped(f: &mut fmt::Formatter<'_>, value: &str, quote: Option<char>) -> fmt::Result {
+    for ch in value.chars() {
+        match ch {
+            '\\' => write!(f, "\\\\")?,
+            '\n' => write!(f, "\\a ")?,
+            c if Some(c) == quote => write!(f, "\\{}", c)?,
+            c => write!(f, "{}", c)?,
+        }
+    }
+    Ok(())
+}
+
+/// Writes the token back as CSS text.
+impl fmt::Display for CssTokenType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CssTokenType::Ident(name) => write_escaped(f, name, None),
+            CssTokenType::Function(name) => {
+                write_escaped(f, name, None)?;
+                write!(f, "(")
+            },
+            CssTokenType::AtKeyword(name) => {
+                write!(f, "@")?;
+                write_escaped(f, name, None)
+            },
+            CssTokenType::Hash { value, .. } => {
+                write!(f, "#")?;
+                write_escaped(f, value, None)
+            },
+            CssTokenType::String(value) => {
+                write!(f, "\"")?;
+                write_escaped(f, value, Some('"'))?;
+                write!(f, "\"")
+            },
+            CssTokenType::BadString => writeln!(f, "\""),
+            CssTokenType::Url(value) => {
+                write!(f, "url(")?;
+                write_escaped(f, value, Some(')'))?;
+                write!(f, ")")
+            },
+            CssTokenType::BadUrl => write!(f, "url(()"),
+            CssTokenType::Delim(ch) => write!(f, "{}", ch),
+            CssTokenType::Number { value, .. } => write!(f, "{}", value),
+            CssTokenType::Percentage(value) => write!(f, "{}%", value),
+            CssTokenType::Dimension { value, unit, .. } => {
+                write!(f, "{}", value)?;
+                write_escaped(f, unit, None)
+            },
+            CssTokenType::Whitespace => write!(f, " "),
+            CssTokenType::Comment(text) => write!(f, "/*{}*/", text),
+            CssTokenType::CDO => write!(f, "<!--"),
+            CssTokenType::CDC => write!(f, "-->"),
+            CssTokenType::Colon => write!(f, ":"),
+            CssTokenType::Semicolon => write!(f, ";"),
+            CssTokenType::Comma => write!(f, ","),
+            CssTokenType::OpenBracket => write!(f, "["),
+            CssTokenType::CloseBracket => write!(f, "]"),
+            CssTokenType::OpenParen => write!(f, "("),
+            CssTokenType::CloseParen => write!(f, ")"),
+            CssTokenType::OpenBrace => write!(f, "{{"),
+            CssTokenType::CloseBrace => write!(f, "}}"),
+            CssTokenType::EOF => Ok(()),
+        }
+    }
+}
+
+/// Token with its location in the original, unpreprocessed input. `position`
+/// and `length` are in bytes, `line` and `column` are 1-based.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CssToken {
+    pub token_type: CssTokenType,
+    pub position: usize,
+    pub line: usize,
+    pub column: usize,
+    pub length: usize,
+}
+
+/// Tokenizer following CSS Syntax Level 3. Comments are consumed without
+/// producing tokens, unless kept with `keeping_comments`, and unknown
+/// characters become `Delim` tokens, so the token stream covers the whole
+/// input.
+pub struct Tokenizer {
+    // Preprocessed code points (§3.3) with the byte offset they start at:
+    // CR LF, CR and FF become LF, NULL becomes U+FFFD.
+    chars: Vec<(usize, char)>,
+    input_length: usize,
+    index: usize,
+    line: usize,
+    column: usize,
+    keep_comments: bool,
+}
+
+impl Tokenizer {
+    pub fn new(input: &str) -> Self {
+        let mut chars = Vec::with_capacity(input.len());
+        let mut iter = input.char_indices().peekable();
+
+        while let Some((offset, ch)) = iter.next() {
+            let ch = match ch {
+                '\r' => {
+                    if let Some((_, '\n')) = iter.peek() {
+                        iter.next();
+                    }
+                    '\n'
+                },
+                '\u{C}' => '\n',
+                '\0' => '\u{FFFD}',
+                c => c,
+            };
+            chars.push((offset, ch));
+        }
+
+        Tokenizer {
+            chars,
+            input_length: input.len(),
+            index: 0,
+            line: 1,
+            column: 1,
+            keep_comments: false,
+        }
+    }
+
+    /// Tokenizer that yields comments as `Comment` tokens.
+    pub fn keeping_comments(input: &str) -> Self {
+        Tokenizer { keep_comments: true, ..Tokenizer::new(input) }
+    }
+
+    fn peek_nth(&self, n: usize) -> Option<char> {
+        self.chars.get(self.index + n).map(|(_, ch)| *ch)
+    }
+
+    fn offset(&self) -> usize {
+        self.chars.get(self.index).map_or(self.input_length, |(offset, _)| *offset)
+    }
+
+    fn consume(&mut self) -> Option<char> {
+        let ch = self.peek_nth(0)?;
+        self.index += 1;
+
+        if ch == '\n' {
+            self.line += 1;
+            self.column = 1;
+        } else {
+            self.column += 1;
+        }
+
+        Some(ch)
+    }
+
+    pub fn next_token(&mut self) -> CssToken {
+        if !self.keep_comments {
+            self.consume_comments();
+        }
+
+        let position = self.offset();
+        let line = self.line;
+        let column = self.column;
+
+        let token_type = self.consume_token();
+
+        CssToken {
+            token_type,
+            position,
+            line,
+            column,
+            length: self.offset() - position,
+        }
+    }
+
+    /// Consumes all tokens up to and including EOF.
+    pub fn tokenize(mut self) -> Vec<CssToken> {
+        let mut tokens = Vec::new();
+
+        loop {
+            let token = self.next_token();
+            let is_eof = token.token_type == CssTokenType::EOF;
+            tokens.push(token);
+
+            if is_eof {
+                return tokens;
+            }
+        }
+    }
+
+    fn consume_comments(&mut self) {
+        while self.starts_comment() {
+            self.consume_comment();
+        }
+    }
+
+    fn starts_comment(&self) -> bool {
+        self.peek_nth(0) == Some('/') && self.peek_nth(1) == Some('*')
+    }
+
+    /// Consumes a comment and returns its text; an unterminated comment runs
+    /// to the end of the input.
+    fn consume_comment(&mut self) -> String {
+        let mut text = String::new();
+        self.consume();
+        self.consume();
+
+        loop {
+            match self.consume() {
+                Some('*') if self.peek_nth(0) == Some('/') => {
+                    self.consume();
+                    return text;
+                },
+                Some(ch) => text.push(ch),
+                None => return text,
+            }
+        }
+    }
+
+    fn consume_token(&mut self) -> CssTokenType {
+        let ch = match self.peek_nth(0) {
+            Some(ch) => ch,
+            None => return CssTokenType::EOF,
+        };
+
+        match ch {
+            c if is_whitespace(c) => {
+                while self.peek_nth(0).is_some_and(is_whitespace) {
+                    self.consume();
+                }
+                CssTokenType::Whitespace
+            },
+            '"' | '\'' => {
+                self.consume();
+                self.consume_string(ch)
+            },
+            '#' => {
+                self.consume();
+
+                if self.peek_nth(0).is_some_and(is_ident_char) || self.starts_with_valid_escape(0) {
+                    let is_id = self.starts_ident_sequence(0);
+                    let value = self.consume_ident_sequence();
+                    CssTokenType::Hash { value, is_id }
+                } else {
+                    CssTokenType::Delim('#')
+                }
+            },
+            '(' => self.single(CssTokenType::OpenParen),
+            ')' => self.single(CssTokenType::CloseParen),
+            '+' | '.' => {
+                if self.starts_number(0) {
+                    self.consume_numeric()
+                } else {
+                    self.single(CssTokenType::Delim(ch))
+                }
+            },
+            ',' => self.single(CssTokenType::Comma),
+            '-' => {
+                if self.starts_number(0) {
+                    self.consume_numeric()
+                } else if self.peek_nth(1) == Some('-') && self.peek_nth(2) == Some('>') {
+                    self.consume();
+                    self.consume();
+                    self.single(CssTokenType::CDC)
+                } else if self.starts_ident_sequence(0) {
+                    self.consume_ident_like()
+                } else {
+                    self.single(CssTokenType::Delim('-'))
+                }
+            },
+            ':' => self.single(CssTokenType::Colon),
+            ';' => self.single(CssTokenType::Semicolon),
+            '<' => {
+                if self.peek_nth(1) == Some('!') && self.peek_nth(2) == Some('-') && self.peek_nth(3) == Some('-') {
+                    for _ in 0..3 {
+                        self.consume();
+                    }
+                    self.single(CssTokenType::CDO)
+                } else {
+                    self.single(CssTokenType::Delim('<'))
+                }
+            },
+            '@' => {
+                self.consume();
+
+                if self.starts_ident_sequence(0) {
+                    CssTokenType::AtKeyword(self.consume_ident_sequence())
+                } else {
+                    CssTokenType::Delim('@')
+                }
+            },
+            '/' if self.starts_comment() => CssTokenType::Comment(self.consume_comment()),
+            '[' => self.single(CssTokenType::OpenBracket),
+            '\\' => {
+                if self.starts_with_valid_escape(0) {
+                    self.consume_ident_like()
+                } else {
+                    self.single(CssTokenType::Delim('\\'))
+                }
+            },
+            ']' => self.single(CssTokenType::CloseBracket),
+            '{' => self.single(CssTokenType::OpenBrace),
+            '}' => self.single(CssTokenType::CloseBrace),
+            c if c.is_ascii_digit() => self.consume_numeric(),
+            c if is_ident_start(c) => self.consume_ident_like(),
+            c => self.single(CssTokenType::Delim(c)),
+        }
+    }
+
+    fn single(&mut self, token_type: CssTokenType) -> CssTokenType {
+        self.consume();
+        token_type
+    }
+
+    // §4.3.8
+    fn starts_with_valid_escape(&self, n: usize) -> bool {
+        self.peek_nth(n) == Some('\\') && self.peek_nth(n + 1) != Some('\n')
+    }
+
+    // §4.3.9
+    fn starts_ident_sequence(&self, n: usize) -> bool {
+        match self.peek_nth(n) {
+            Some('-') => {
+                self.peek_nth(n + 1).is_some_and(|c| is_ident_start(c) || c == '-') || self.starts_with_valid_escape(n + 1)
+            },
+            Some('\\') => self.starts_with_valid_escape(n),
+            Some(c) => is_ident_start(c),
+            None => false,
+        }
+    }
+
+    // §4.3.10
+    fn starts_number(&self, n: usize) -> bool {
+        let is_digit = |offset: usize| self.peek_nth(n + offset).is_some_and(|c| c.is_ascii_digit());
+
+        match self.peek_nth(n) {
+            Some('+') | Some('-') => is_digit(1) || (self.peek_nth(n + 1) == Some('.') && is_digit(2)),
+            Some('.') => is_digit(1),
+            Some(c) => c.is_ascii_digit(),
+            None => false,
+        }
+    }
+
+    // §4.3.7, called after the backslash has been consumed.
+    fn consume_escape(&mut self) -> char {
+        match self.consume() {
+            Some(c) if c.is_ascii_hexdigit() => {
+                let mut code = c.to_digit(16).unwrap_or(0);
+
+                for _ in 0..5 {
+                    match self.peek_nth(0).and_then(|c| c.to_digit(16)) {
+                        Some(digit) => {
+                            code = code * 16 + digit;
+                            self.consume();
+                        },
+                        None => break,
+                    }
+                }
+
+                if self.peek_nth(0).is_some_and(is_whitespace) {
+                    self.consume();
+                }
+
+                match char::from_u32(code) {
+                    Some(ch) if code != 0 => ch,
+                    _ => '\u{FFFD}',
+                }
+            },
+            Some(c) => c,
+            None => '\u{FFFD}',
+        }
+    }
+
+    // §4.3.11
+    fn consume_ident_sequence(&mut self) -> String {
+        let mut result = String::new();
+
+        loop {
+            match self.peek_nth(0) {
+                Some(c) if is_ident_char(c) => {
+                    self.consume();
+                    result.push(c);
+                },
+                Some('\\') if self.starts_with_valid_escape(0) => {
+                    self.consume();
+                    result.push(self.consume_escape());
+                },
+                _ => return result,
+            }
+        }
+    }
+
+    // §4.3.12
+    fn consume_number(&mut self) -> (f64, bool) {
+        let mut repr = String::new();
+        let mut is_integer = true;
+
+        if let Some(sign @ ('+' | '-')) = self.peek_nth(0) {
+            self.consume();
+            repr.push(sign);
+        }
+
+        self.consume_digits(&mut repr);
+
+        if self.peek_nth(0) == Some('.') && self.peek_nth(1).is_some_and(|c| c.is_ascii_digit()) {
+            self.consume();
+            repr.push('.');
+            is_integer = false;
+            self.consume_digits(&mut repr);
+        }
+
+        if let Some(e @ ('e' | 'E')) = self.peek_nth(0) {
+            let digit_at = |n: usize| self.peek_nth(n).is_some_and(|c| c.is_ascii_digit());
+            let has_sign = matches!(self.peek_nth(1), Some('+' | '-'));
+
+            if digit_at(1) || (has_sign && digit_at(2)) {
+                self.consume();
+                repr.push(e);
+
+                if has_sign {
+                    repr.push(self.consume().unwrap_or('+'));
+                }
+
+                is_integer = false;
+                self.consume_digits(&mut repr);
+            }
+        }
+
+        (repr.parse::<f64>().unwrap_or(0.0), is_integer)
+    }
+
+    fn consume_digits(&mut self, repr: &mut String) {
+        while let Some(c) = self.peek_nth(0).filter(|c| c.is_ascii_digit()) {
+            self.consume();
+            repr.push(c);
+        }
+    }
+
+    // §4.3.3
+    fn consume_numeric(&mut self) -> CssTokenType {
+        let (value, is_integer) = self.consume_number();
+
+        if self.starts_ident_sequence(0) {
+            let unit = self.consume_ident_sequence();
+            CssTokenType::Dimension { value, is_integer, unit }
+        } else if self.peek_nth(0) == Some('%') {
+            self.consume();
+            CssTokenType::Percentage(value)
+        } else {
+            CssTokenType::Number { value, is_integer }
+        }
+    }
+
+    // §4.3.4
+    fn consume_ident_like(&mut self) -> CssTokenType {
+        let name = self.consume_ident_sequence();
+
+        if self.peek_nth(0) != Some('(') {
+            return CssTokenType::Ident(name);
+        }
+
+        self.consume();
+
+        if !name.eq_ignore_ascii_case("url") {
+            return CssTokenType::Function(name);
+        }
+
+        while self.peek_nth(0).is_some_and(is_whitespace) && self.peek_nth(1).is_some_and(is_whitespace) {
+            self.consume();
+        }
+
+        let next = if self.peek_nth(0).is_some_and(is_whitespace) { self.peek_nth(1) } else { self.peek_nth(0) };
+
+        if matches!(next, Some('"' | '\'')) {
+            CssTokenType::Function(name)
+        } else {
+            self.consume_url()
+        }
+    }
+
+    // §4.3.5
+    fn consume_string(&mut self, ending: char) -> CssTokenType {
+        let mut value = String::new();
+
+        loop {
+            match self.peek_nth(0) {
+                None => return CssTokenType::String(value),
+                Some(c) if c == ending => {
+                    self.consume();
+                    return CssTokenType::String(value);
+                },
+                Some('\n') => return CssTokenType::BadString,
+                Some('\\') => {
+                    self.consume();
+
+                    match self.peek_nth(0) {
+                        None => {},
+                        Some('\n') => {
+                            self.consume();
+                        },
+                        Some(_) => value.push(self.consume_escape()),
+                    }
+                },
+                Some(c) => {
+                    self.consume();
+                    value.push(c);
+                },
+            }
+        }
+    }
+
+    // §4.3.6, called after `url(` has been consumed.
+    fn consume_url(&mut self) -> CssTokenType {
+        let mut value = String::new();
+
+        while self.peek_nth(0).is_some_and(is_whitespace) {
+            self.consume();
+        }
+
+        loop {
+            match self.peek_nth(0) {
+                None => return CssTokenType::Url(value),
+                Some(')') => {
+                    self.consume();
+                    return CssTokenType::Url(value);
+                },
+                Some(c) if is_whitespace(c) => {
+                    while self.peek_nth(0).is_some_and(is_whitespace) {
+                        self.consume();
+                    }
+
+                    match self.peek_nth(0) {
+                        None => return CssTokenType::Url(value),
+                        Some(')') => {
+                            self.consume();
+                            return CssTokenType::Url(value);
+                        },
+                        Some(_) => {
+                            self.consume_bad_url_remnants();
+                            return CssTokenType::BadUrl;
+                        },
+                    }
+                },
+                Some('"' | '\'' | '(') => {
+                    self.consume_bad_url_remnants();
+                    return CssTokenType::BadUrl;
+                },
+                Some(c) if is_non_printable(c) => {
+                    self.consume_bad_url_remnants();
+                    return CssTokenType::BadUrl;
+                },
+                Some('\\') => {
+                    if self.starts_with_valid_escape(0) {
+                        self.consume();
+                        value.push(self.consume_escape());
+                    } else {
+                        self.consume_bad_url_remnants();
+                        return CssTokenType::BadUrl;
+                    }
+                },
+                Some(c) => {
+                    self.consume();
+                    value.push(c);
+                },
+            }
+        }
+    }
+
+    // §4.3.14
+    fn consume_bad_url_remnants(&mut self) {
+        loop {
+            match self.peek_nth(0) {
+                None => return,
+                Some(')') => {
+                    self.consume();
+                    return;
+                },
+                Some('\\') if self.starts_with_valid_escape(0) => {
+                    self.consume();
+                    self.consume_escape();
+                },
+                Some(_) => {
+                    self.consume();
+                },
+            }
+        }
+    }
+}
+
+impl Iterator for Tokenizer {
+    type Item = CssToken;
+
+    /// Yields tokens up to, but not including, EOF.
+    fn next(&mut self) -> Option<CssToken> {
+        let token = self.next_token();
+
+        if token.token_type == CssTokenType::EOF {
+            None
+        } else {
+            Some(token)
+        }
+    }
+}
+
+fn is_whitespace(ch: char) -> bool {
+    matches!(ch, '\n' | '\t' | ' ')
+}
+
+fn is_ident_start(ch: char) -> bool {
+    ch.is_ascii_alphabetic() || ch == '_' || ch >= '\u{80}'
+}
+
+fn is_ident_char(ch: char) -> bool {
+    is_ident_start(ch) || ch.is_ascii_digit() || ch == '-'
+}
+
+fn is_non_printable(ch: char) -> bool {
+    matches!(ch, '\u{0}'..='\u{8}' | '\u{B}' | '\u{E}'..='\u{1F}' | '\u{7F}')
+}
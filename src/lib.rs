@@ -0,0 +1,79 @@
+//! CSS parser library.
+//!
+//! The free functions below parse a complete input string and fail if
+//! anything is left over. Use `parser::CssParser` directly to parse several
+//! constructs from the same input or to recover from errors.
+
+mod css_parser;
+#[cfg(test)]
+mod tests;
+
+pub use css_parser::{ast, component_value, container, counter_style, error, font_face, font_feature_values, import, keyframes, namespace, page, lexer, media, parser, property, supports, tokenizer};
+
+use ast::{Declaration, Rule, Selector, Stylesheet, Value};
+use container::ContainerCondition;
+use error::ParseError;
+use media::MediaQueryList;
+use supports::SupportsCondition;
+use parser::CssParser;
+
+/// Parses a whole stylesheet.
+pub fn parse_stylesheet(css: &str) -> Result<Stylesheet<'_>, ParseError> {
+    CssParser::new(css).parse_stylesheet()
+}
+
+/// Parses a single style rule, e.g. `a:hover { color: red }`.
+pub fn parse_rule(css: &str) -> Result<Rule<'_>, ParseError> {
+    let mut parser = CssParser::new(css);
+    let rule = parser.parse_rule()?;
+    parser.expect_eof()?;
+    Ok(rule)
+}
+
+/// Parses declarations without surrounding braces, e.g. a `style` attribute.
+pub fn parse_declaration_list(css: &str) -> Result<Vec<Declaration<'_>>, ParseError> {
+    let mut parser = CssParser::new(css);
+    let declarations = parser.parse_declaration_list()?;
+    parser.expect_eof()?;
+    Ok(declarations)
+}
+
+/// Parses a comma separated selector list, e.g. `ul > li, .item`.
+pub fn parse_selector_list(css: &str) -> Result<Vec<Selector<'_>>, ParseError> {
+    let mut parser = CssParser::new(css);
+    let selectors = parser.parse_selector_list()?;
+    parser.expect_eof()?;
+    Ok(selectors)
+}
+
+/// Parses the value of `property`, e.g. `1px solid red` for `border`.
+pub fn parse_value_for_property<'a>(property: &str, css: &'a str) -> Result<Value<'a>, ParseError> {
+    let mut parser = CssParser::new(css);
+    let value = parser.parse_value_for_property(property)?;
+    parser.expect_eof()?;
+    Ok(value)
+}
+
+/// Parses a media query list, e.g. `screen and (min-width: 600px), print`.
+pub fn parse_media_query_list(css: &str) -> Result<MediaQueryList<'_>, ParseError> {
+    let mut parser = CssParser::new(css);
+    let queries = parser.parse_media_query_list()?;
+    parser.expect_eof()?;
+    Ok(queries)
+}
+
+/// Parses an `@supports` condition, e.g. `(display: grid) and selector(:has(a))`.
+pub fn parse_supports_condition(css: &str) -> Result<SupportsCondition<'_>, ParseError> {
+    let mut parser = CssParser::new(css);
+    let condition = parser.parse_supports_condition()?;
+    parser.expect_eof()?;
+    Ok(condition)
+}
+
+/// Parses an `@container` prelude, e.g. `card (inline-size > 30em)`.
+pub fn parse_container_condition(css: &str) -> Result<ContainerCondition<'_>, ParseError> {
+    let mut parser = CssParser::new(css);
+    let condition = parser.parse_container_condition()?;
+    parser.expect_eof()?;
+    Ok(condition)
+}
@@ -1,37 +1,18 @@
-mod css_parser;
-mod tests;
-
 use std::fs;
-use crate::css_parser::lexer::{Lexer, TokenType};
-use crate::css_parser::parser::CssParser;
+use std::time::Instant;
 
 fn main() {
     let file_path = "src/style.css";
     match fs::read_to_string(file_path) {
         Ok(css) => {
-            let mut parser = CssParser::new(css.clone());
-            let mut lexer = Lexer::new(css);
-
-            /*loop {
-                let token = lexer.next_token();
-                println!("{:?}", token);
-
-                if token.token_type == TokenType::EOF {
-                    break;
-                }
-            }*/
+            let start_time = Instant::now();
 
-            match parser.parse_stylesheet() {
+            match css_parser::parse_stylesheet(&css) {
                 Ok(stylesheet) => {
+                    println!("Parsed {} rules and {} at-rules in {:?}", stylesheet.style_rules().count(), stylesheet.at_rules().count(), start_time.elapsed());
                     println!("Parsed CSS:");
-                    println!("Regular rules: {}", stylesheet.rules.len());
                     for rule in &stylesheet.rules {
-                        println!("{:?}", rule);
-                    }
-
-                    println!("At-rules: {}", stylesheet.at_rules.len());
-                    for at_rule in &stylesheet.at_rules {
-                        println!("{}", at_rule);
+                        println!("{}", rule);
                     }
                 },
                 Err(err) => {
@@ -0,0 +1,83 @@
+use crate::css_parser::ast::{AtRuleType, CssRule, ValueKind};
+use crate::css_parser::error::ParseErrorKind;
+use pretty_assertions::assert_eq;
+
+#[test]
+fn test_entry_points() {
+    let stylesheet = crate::parse_stylesheet("a { color: red; } b { margin: 0; }").unwrap();
+    assert_eq!(stylesheet.rules.len(), 2);
+
+    let rule = crate::parse_rule("  ul > li, .item { padding: 4px 8px; }  ").unwrap();
+    assert_eq!(rule.selectors.len(), 2);
+    assert_eq!(rule.declarations[0].property, "padding");
+
+    let declarations = crate::parse_declaration_list("color: red; margin: 0 auto !important").unwrap();
+    assert_eq!(declarations.len(), 2);
+    assert_eq!(declarations[1].property, "margin");
+    assert!(declarations[1].is_important);
+
+    let selectors = crate::parse_selector_list("ul > li, .item:hover").unwrap();
+    assert_eq!(selectors.iter().map(|selector| selector.to_string()).collect::<Vec<_>>(), vec!["ul > li", ".item:hover"]);
+
+    let value = crate::parse_value_for_property("border", "1px solid red").unwrap();
+    assert_eq!(value.to_string(), "1px solid red");
+
+    let value = crate::parse_value_for_property("--empty", "").unwrap();
+    assert!(matches!(value.kind, ValueKind::Literal(text) if text.is_empty()));
+
+    let queries = crate::parse_media_query_list("screen and (min-width: 600px), print").unwrap();
+    assert_eq!(queries.queries.len(), 2);
+    assert_eq!(queries.to_string(), "screen and (min-width: 600px), print");
+}
+
+#[test]
+fn test_entry_points_reject_trailing_input() {
+    let error = crate::parse_rule("a { color: red; } b { color: blue; }").unwrap_err();
+    assert_eq!(error.kind, ParseErrorKind::UnexpectedToken);
+
+    let error = crate::parse_selector_list("a, b {").unwrap_err();
+    assert_eq!(error.kind, ParseErrorKind::UnexpectedToken);
+
+    let error = crate::parse_declaration_list("color: red; }").unwrap_err();
+    assert_eq!(error.kind, ParseErrorKind::UnexpectedToken);
+
+    let error = crate::parse_value_for_property("color", "").unwrap_err();
+    assert_eq!(error.kind, ParseErrorKind::UnexpectedEof);
+}
+
+#[test]
+fn test_value_for_property_depends_on_property() {
+    let value = crate::parse_value_for_property("font-family", "monospace, DejaVu Sans Mono").unwrap();
+    assert!(matches!(&value.kind, ValueKind::List(items) if matches!(&items[2].kind, ValueKind::Literal(name) if name == "DejaVu Sans Mono")));
+
+    // Only font families join the words after a comma into one name.
+    let value = crate::parse_value_for_property("transition", "opacity 1s ease, transform 2s ease-in").unwrap();
+    assert_eq!(value.to_string(), "opacity 1s ease , transform 2s ease-in");
+
+    let declarations = crate::parse_declaration_list("--empty:; color: red").unwrap();
+    assert!(matches!(&declarations[0].value.kind, ValueKind::Literal(text) if text.is_empty()));
+    assert_eq!(declarations[1].property, "color");
+}
+
+#[test]
+fn test_rule_traversal() {
+    let css = "@media print { a { color: red; & b { margin: 0; } } @keyframes x {} } @supports (display: grid) { @keyframes y {} }";
+    let stylesheet = crate::parse_stylesheet(css).unwrap();
+
+    let kinds: Vec<_> = stylesheet
+        .descendants()
+        .map(|rule| match rule {
+            CssRule::Style(rule) => rule.selectors[0].to_string(),
+            CssRule::At(at_rule) => format!("@{}", at_rule.name),
+        })
+        .collect();
+    assert_eq!(kinds, ["@media", "a", "& b", "@keyframes", "@supports", "@keyframes"]);
+
+    let names: Vec<_> = stylesheet.at_rules_of_type(AtRuleType::Keyframes).map(|at_rule| at_rule.query.as_str()).collect();
+    assert_eq!(names, ["x", "y"]);
+
+    let value = crate::parse_value_for_property("margin", "0 auto").unwrap();
+    assert_eq!(value.items().iter().map(|item| item.to_string()).collect::<Vec<_>>(), ["0", "auto"]);
+    let value = crate::parse_value_for_property("color", "red").unwrap();
+    assert_eq!(value.items().len(), 1);
+}
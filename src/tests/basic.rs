@@ -1,5 +1,5 @@
 use super::common::parse_test_file;
-use crate::css_parser::ast::{DeclarationExt, RuleExt, StylesheetExt, ValueExt};
+use crate::css_parser::ast::{RuleExt, StylesheetExt, ValueExt};
 use pretty_assertions::assert_eq;
 
 #[test]
@@ -7,7 +7,7 @@ fn test_basic_selectors() {
     let stylesheet = parse_test_file("basic.css").unwrap();
 
     assert_eq!(stylesheet.rules.len(), 25);
-    assert_eq!(stylesheet.at_rules.len(), 0);
+    assert_eq!(stylesheet.at_rules().count(), 0);
 
     let rule = stylesheet.get_rule_by_selector(".class").unwrap();
     let value = rule.get_declaration_value("color").unwrap();
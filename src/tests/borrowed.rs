@@ -0,0 +1,39 @@
+use std::borrow::Cow;
+
+use crate::css_parser::ast::{SelectorPartKind, Stylesheet, ValueKind};
+use crate::css_parser::lexer::{Lexer, TokenType};
+use crate::css_parser::parser::CssParser;
+use pretty_assertions::assert_eq;
+
+#[test]
+fn test_tokens_borrow_input() {
+    let mut lexer = Lexer::new(".a\\:b, c { width: 10px }");
+    lexer.next_token();
+
+    let escaped = lexer.next_token();
+    assert!(matches!(escaped.token_type, TokenType::Identifier(Cow::Owned(ref name)) if name == "a:b"));
+
+    lexer.next_token();
+    lexer.next_token();
+    let plain = lexer.next_token();
+    assert!(matches!(plain.token_type, TokenType::Identifier(Cow::Borrowed("c"))));
+}
+
+#[test]
+fn test_into_owned() {
+    let stylesheet: Stylesheet<'static> = {
+        let css = String::from(".item { font-family: \"Fira Code\"; color: red; }");
+        let stylesheet = CssParser::new(&css).parse_stylesheet().unwrap();
+
+        let rule = stylesheet.rules[0].as_style().unwrap();
+        assert!(matches!(rule.selectors[0].groups[0].parts[0].kind, SelectorPartKind::Class(Cow::Borrowed("item"))));
+        assert!(matches!(rule.declarations[0].property, Cow::Borrowed(_)));
+
+        stylesheet.into_owned()
+    };
+
+    let rule = stylesheet.rules[0].as_style().unwrap();
+    assert_eq!(rule.selectors[0].to_string(), ".item");
+    assert!(matches!(&rule.declarations[0].value.kind, ValueKind::QuotedString(text) if text == "Fira Code"));
+    assert_eq!(rule.declarations[1].to_string(), "color: red;");
+}
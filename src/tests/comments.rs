@@ -0,0 +1,83 @@
+use crate::css_parser::lexer::Lexer;
+use crate::css_parser::parser::CssParser;
+use pretty_assertions::assert_eq;
+
+const CSS: &str = include_str!("resources/comments.css");
+
+#[test]
+fn test_comments_attached() {
+    let stylesheet = CssParser::with_lexer(Lexer::new(CSS).preserve_comments(true)).parse_stylesheet().unwrap();
+
+    let rule = stylesheet.rules[0].as_style().unwrap();
+    assert_eq!(rule.comments.leading[0].text, "! License banner ");
+    assert_eq!(rule.comments.leading[0].span.text(CSS), "/*! License banner */");
+    assert_eq!(rule.comments.trailing[0].text, " end of a ");
+    assert_eq!(rule.declarations[0].comments.leading[0].text, " spacing ");
+    assert_eq!(rule.declarations[0].comments.trailing[0].text, " reset ");
+    assert!(rule.declarations[1].comments.is_empty());
+
+    let media = stylesheet.rules[1].as_at_rule().unwrap();
+    assert!(media.comments.is_empty());
+    let nested = media.rules[0].as_style().unwrap();
+    assert_eq!(nested.comments.leading[0].text, " nested ");
+    assert_eq!(nested.declarations[0].comments.trailing[0].text, " no semicolon ");
+
+    let import = stylesheet.rules[2].as_at_rule().unwrap();
+    assert_eq!(import.comments.trailing[0].text, " theme ");
+
+    assert_eq!(
+        stylesheet.to_string(),
+        "/*! License banner */\n\
+         a {\n    /* spacing */\n    margin: 0; /* reset */\n    color: red;\n} /* end of a */\n\
+         @media screen {\n    /* nested */\n    b {\n        color: blue; /* no semicolon */\n    }\n}\n\
+         @import \"theme.css\"; /* theme */\n"
+    );
+}
+
+#[test]
+fn test_comments_dropped_by_default() {
+    let stylesheet = crate::parse_stylesheet(CSS).unwrap();
+    let rule = stylesheet.rules[0].as_style().unwrap();
+    assert!(rule.comments.is_empty());
+    assert!(rule.declarations[0].comments.is_empty());
+}
+
+fn parse_with_comments(css: &str) -> crate::css_parser::ast::Stylesheet<'_> {
+    CssParser::with_lexer(Lexer::new(css).preserve_comments(true)).parse_stylesheet().unwrap()
+}
+
+#[test]
+fn test_comments_on_next_line_not_trailing() {
+    let stylesheet = parse_with_comments("a { color: red }\n/* next */\nb {}");
+    assert!(stylesheet.rules[0].as_style().unwrap().comments.trailing.is_empty());
+    assert_eq!(stylesheet.rules[1].as_style().unwrap().comments.leading[0].text, " next ");
+
+    let stylesheet = parse_with_comments("a { color: red\n/* c */ }");
+    let rule = stylesheet.rules[0].as_style().unwrap();
+    assert!(rule.declarations[0].comments.trailing.is_empty());
+    assert_eq!(rule.comments.dangling[0].text, " c ");
+}
+
+#[test]
+fn test_comments_dangling() {
+    let stylesheet = parse_with_comments("/* only a comment */");
+    assert!(stylesheet.rules.is_empty());
+    assert_eq!(stylesheet.comments[0].text, " only a comment ");
+
+    let stylesheet = parse_with_comments("@media screen { a { color: red } }\n/* footer */");
+    assert_eq!(stylesheet.comments[0].text, " footer ");
+
+    let stylesheet = parse_with_comments("a { /* empty */ }");
+    assert_eq!(stylesheet.rules[0].as_style().unwrap().comments.dangling[0].text, " empty ");
+
+    let stylesheet = parse_with_comments("a { b { color: red }\n/* after b */ }");
+    assert_eq!(stylesheet.rules[0].as_style().unwrap().comments.dangling[0].text, " after b ");
+
+    let stylesheet = parse_with_comments("@media screen { a {}\n/* after a */ }");
+    assert_eq!(stylesheet.rules[0].as_at_rule().unwrap().comments.dangling[0].text, " after a ");
+
+    assert_eq!(
+        parse_with_comments("a { color: red;\n/* x */ }\n/* footer */").to_string(),
+        "a {\n    color: red;\n    /* x */\n}\n/* footer */\n"
+    );
+}
@@ -1,4 +1,5 @@
-use crate::css_parser::ast::{CalcExpression, Color, Stylesheet, Value};
+use crate::css_parser::ast::{CalcExpression, Color, Stylesheet, Value, ValueKind};
+use crate::css_parser::error::ParseError;
 use crate::css_parser::parser::CssParser;
 pub fn read_test_file(filename: &str) -> String {
     let test_dir = std::path::Path::new("src/tests/resources");
@@ -8,36 +9,37 @@ pub fn read_test_file(filename: &str) -> String {
         .unwrap_or_else(|_| panic!("Failed to read test file: {}", filename))
 }
 
-pub fn parse_test_file(filename: &str) -> Result<Stylesheet, String> {
+pub fn parse_test_file(filename: &str) -> Result<Stylesheet<'static>, ParseError> {
     let css = read_test_file(filename);
-    let mut parser = CssParser::new(css);
+    let mut parser = CssParser::new(&css);
 
-    parser.parse_stylesheet()
+    parser.parse_stylesheet().map(Stylesheet::into_owned)
 }
 
-pub fn compare_values(a: &Value, b: &Value) -> bool {
-    match (a, b) {
-        (Value::Literal(a_str), Value::Literal(b_str)) => a_str == b_str,
+/// Compares a parsed value with the expected kind, ignoring spans.
+pub fn compare_values(a: &Value, b: &ValueKind) -> bool {
+    match (&a.kind, b) {
+        (ValueKind::Literal(a_str), ValueKind::Literal(b_str)) => a_str == b_str,
 
-        (Value::QuotedString(a_str), Value::QuotedString(b_str)) => a_str == b_str,
+        (ValueKind::QuotedString(a_str), ValueKind::QuotedString(b_str)) => a_str == b_str,
 
-        (Value::Number(a_num, a_unit), Value::Number(b_num, b_unit)) => {
+        (ValueKind::Number(a_num, a_unit), ValueKind::Number(b_num, b_unit)) => {
             (a_num - b_num).abs() < f64::EPSILON && a_unit == b_unit
         },
 
-        (Value::Color(a_color), Value::Color(b_color)) => match (a_color, b_color) {
+        (ValueKind::Color(a_color), ValueKind::Color(b_color)) => match (a_color, b_color) {
             (Color::Hex(a_hex), Color::Hex(b_hex)) => a_hex.to_lowercase() == b_hex.to_lowercase(),
             (Color::Named(a_name), Color::Named(b_name)) => a_name.to_lowercase() == b_name.to_lowercase(),
             _ => false,
         },
 
-        (Value::Function(a_name, a_args), Value::Function(b_name, b_args)) => {
+        (ValueKind::Function(a_name, a_args), ValueKind::Function(b_name, b_args)) => {
             if a_name != b_name || a_args.len() != b_args.len() {
                 return false;
             }
 
             for (a_arg, b_arg) in a_args.iter().zip(b_args.iter()) {
-                if !compare_values(a_arg, b_arg) {
+                if !compare_values(a_arg, &b_arg.kind) {
                     return false;
                 }
             }
@@ -45,25 +47,25 @@ pub fn compare_values(a: &Value, b: &Value) -> bool {
             true
         },
 
-        (Value::VarFunction(a_name, a_arg), Value::VarFunction(b_name, b_arg)) => {
+        (ValueKind::VarFunction(a_name, a_arg), ValueKind::VarFunction(b_name, b_arg)) => {
             if a_name != b_name {
                 return false;
             }
 
             match (a_arg, b_arg) {
-                (Some(a_val), Some(b_val)) => compare_values(a_val, b_val),
+                (Some(a_val), Some(b_val)) => compare_values(a_val, &b_val.kind),
                 (None, None) => true,
                 _ => false,
             }
         },
 
-        (Value::List(a_items), Value::List(b_items)) => {
+        (ValueKind::List(a_items), ValueKind::List(b_items)) => {
             if a_items.len() != b_items.len() {
                 return false;
             }
 
             for (a_item, b_item) in a_items.iter().zip(b_items.iter()) {
-                if !compare_values(a_item, b_item) {
+                if !compare_values(a_item, &b_item.kind) {
                     return false;
                 }
             }
@@ -71,9 +73,9 @@ pub fn compare_values(a: &Value, b: &Value) -> bool {
             true
         },
 
-        (Value::Keyword(a_key), Value::Keyword(b_key)) => a_key == b_key,
+        (ValueKind::Keyword(a_key), ValueKind::Keyword(b_key)) => a_key == b_key,
 
-        (Value::Calc(a_calc), Value::Calc(b_calc)) => compare_calc_expressions(a_calc, b_calc),
+        (ValueKind::Calc(a_calc), ValueKind::Calc(b_calc)) => compare_calc_expressions(a_calc, b_calc),
 
         _ => false,
     }
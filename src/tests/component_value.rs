@@ -0,0 +1,94 @@
+use crate::css_parser::ast::AtRuleType;
+use crate::css_parser::component_value::{to_css, BlockKind, ComponentValue};
+use crate::css_parser::tokenizer::CssTokenType;
+use pretty_assertions::assert_eq;
+
+const CSS: &str = r#"@tailwind base;
+@custom-media --narrow-window (max-width: 30em);
+@view-transition { navigation: auto; }
+.btn {
+    color: red;
+    @apply px-4 py-2 rounded;
+}
+@-moz-document url-prefix() { .a { color: red; } }
+"#;
+
+fn token(token_type: CssTokenType, text: &str) -> ComponentValue {
+    ComponentValue::Token(token_type, text.to_string())
+}
+
+fn ident(name: &str) -> ComponentValue {
+    token(CssTokenType::Ident(name.to_string()), name)
+}
+
+#[test]
+fn test_parse_component_values() {
+    let values = ComponentValue::parse_list("calc(1.0px +  2%) [a]/* c */{b}) ");
+    assert_eq!(values, vec![
+        ComponentValue::Function("calc".to_string(), vec![
+            token(CssTokenType::Dimension { value: 1.0, is_integer: false, unit: "px".to_string() }, "1.0px"),
+            token(CssTokenType::Whitespace, " "),
+            token(CssTokenType::Delim('+'), "+"),
+            token(CssTokenType::Whitespace, "  "),
+            token(CssTokenType::Percentage(2.0), "2%"),
+        ]),
+        token(CssTokenType::Whitespace, " "),
+        ComponentValue::Block(BlockKind::Bracket, vec![ident("a")]),
+        token(CssTokenType::Comment(" c ".to_string()), "/* c */"),
+        ComponentValue::Block(BlockKind::Brace, vec![ident("b")]),
+        token(CssTokenType::CloseParen, ")"),
+        token(CssTokenType::Whitespace, " "),
+    ]);
+    assert_eq!(to_css(&values), "calc(1.0px +  2%) [a]/* c */{b}) ");
+}
+
+#[test]
+fn test_unknown_at_rules() {
+    let stylesheet = crate::parse_stylesheet(CSS).unwrap();
+    assert_eq!(stylesheet.rules.len(), 5);
+
+    let tailwind = stylesheet.rules[0].as_at_rule().unwrap();
+    assert_eq!(tailwind.rule_type, AtRuleType::Unknown("tailwind".into()));
+    assert_eq!(tailwind.unknown_prelude(), Some(&[ident("base")][..]));
+    assert_eq!(tailwind.block, None);
+
+    let custom_media = stylesheet.rules[1].as_at_rule().unwrap();
+    assert_eq!(custom_media.query, "--narrow-window (max-width: 30em)");
+    assert_eq!(custom_media.block, None);
+
+    let view_transition = stylesheet.rules[2].as_at_rule().unwrap();
+    assert_eq!(view_transition.unknown_prelude(), Some(&[][..]));
+    assert_eq!(to_css(view_transition.block.as_ref().unwrap()), " navigation: auto; ");
+
+    let apply = stylesheet.rules[3].as_style().unwrap().rules[0].as_at_rule().unwrap();
+    assert_eq!(apply.name, "apply");
+    assert_eq!(apply.query, "px-4 py-2 rounded");
+
+    let document = stylesheet.rules[4].as_at_rule().unwrap();
+    assert!(matches!(stylesheet.rules[4].rule_type(), Some(AtRuleType::Unknown(_))));
+    assert_eq!(document.query, "url-prefix()");
+    assert!(document.rules.is_empty());
+    assert_eq!(to_css(document.block.as_ref().unwrap()), " .a { color: red; } ");
+}
+
+#[test]
+fn test_unknown_at_rules_round_trip() {
+    let stylesheet = crate::parse_stylesheet(CSS).unwrap();
+    let css = stylesheet.to_string();
+    assert!(css.contains("@tailwind base;\n@custom-media --narrow-window (max-width: 30em);\n@view-transition { navigation: auto; }\n"));
+    assert!(css.contains("    @apply px-4 py-2 rounded;\n"));
+    assert!(css.contains("@-moz-document url-prefix() { .a { color: red; } }\n"));
+
+    let reparsed = crate::parse_stylesheet(&css).unwrap();
+    let blocks = |stylesheet: &crate::css_parser::ast::Stylesheet| -> Vec<_> {
+        stylesheet.at_rules().map(|at_rule| (at_rule.unknown_prelude().map(<[_]>::to_vec), at_rule.block.clone())).collect()
+    };
+    assert_eq!(blocks(&reparsed), blocks(&stylesheet));
+}
+
+#[test]
+fn test_unknown_at_rules_keep_source_text() {
+    let css = "@foo 1.50 \"x\\\"y\" /* c */ #id;\n@foo  bar   baz { a  b }\n@media screen {\n    @apply  px-4\t py-2;\n}\n";
+    let stylesheet = crate::parse_stylesheet(css).unwrap();
+    assert_eq!(stylesheet.to_string(), css);
+}
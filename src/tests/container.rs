@@ -0,0 +1,56 @@
+use crate::css_parser::container::{ContainerInfo, ContainerQuery, StyleQuery};
+use crate::css_parser::error::ParseErrorKind;
+use pretty_assertions::assert_eq;
+
+#[test]
+fn test_container_condition_ast() {
+    let condition = crate::parse_container_condition("card (inline-size > 30em) and style(--theme: dark)").unwrap();
+    assert_eq!(condition.name.as_deref(), Some("card"));
+    let Some(ContainerQuery::And(queries)) = &condition.query else { panic!("expected and, got {:?}", condition.query) };
+    assert!(matches!(queries[0], ContainerQuery::Size(_)));
+    assert_eq!(queries[1], ContainerQuery::Style(StyleQuery::Declaration { property: "--theme".into(), value: "dark".into() }));
+    assert_eq!(condition.to_string(), "card (inline-size > 30em) and style(--theme: dark)");
+
+    let condition = crate::parse_container_condition("not (width < 400px)").unwrap();
+    assert_eq!(condition.name, None);
+    assert_eq!(condition.to_string(), "not (width < 400px)");
+
+    let condition = crate::parse_container_condition("style((--a: 1) or (not (--b)))").unwrap();
+    assert_eq!(condition.to_string(), "style((--a: 1) or (not (--b)))");
+
+    let stylesheet = crate::parse_stylesheet("@container sidebar (min-width:20em) { a { float: left } }").unwrap();
+    let container = stylesheet.rules[0].as_at_rule().unwrap();
+    assert_eq!(container.query, "sidebar (min-width: 20em)");
+    assert_eq!(container.container().and_then(|condition| condition.name.as_deref()), Some("sidebar"));
+    assert_eq!(container.rules.len(), 1);
+
+    let error = crate::parse_container_condition("(width > 1px) and (height > 1px) or (width > 2px)").unwrap_err();
+    assert_eq!(error.kind, ParseErrorKind::InvalidContainerQuery);
+}
+
+#[test]
+fn test_container_evaluation() {
+    let card = ContainerInfo::new(600.0, 400.0).with_name("card").with_custom_property("--theme", "  dark ");
+    let matches = |css: &str| crate::parse_container_condition(css).unwrap().evaluate(&card);
+
+    assert!(matches("card (inline-size > 30em) and style(--theme: dark)"));
+    assert!(!matches("sidebar (inline-size > 30em)"));
+    assert!(matches("(min-width: 500px) and (max-height: 400px)"));
+    assert!(!matches("(block-size > 400px)"));
+    assert!(matches("(400px < width <= 600px)"));
+    assert!(matches("style(--theme) and (not style(--accent))"));
+    assert!(!matches("style(--theme: light)"));
+    assert!(!matches("(orientation-unknown-thing)"));
+    assert!(matches("card"));
+
+    // Device features are unknown inside @container, negated or not.
+    assert!(!matches("(resolution > 1dppx)"));
+    assert!(!matches("not (resolution > 1dppx)"));
+    assert!(!matches("(device-width > 10px)"));
+    assert!(!matches("not (device-width > 10px)"));
+    assert!(matches("(aspect-ratio > 1) and (orientation: landscape)"));
+
+    let mut vertical = card.clone();
+    vertical.vertical = true;
+    assert!(crate::parse_container_condition("(inline-size: 400px)").unwrap().evaluate(&vertical));
+}
@@ -0,0 +1,79 @@
+use crate::css_parser::counter_style::{AdditiveSymbol, CounterStyleRule, CounterSymbol, CounterSystem, UNSUPPORTED_PREDEFINED};
+use pretty_assertions::assert_eq;
+
+const CSS: &str = r#"
+@counter-style thumbs { system: cyclic; symbols: "👍" "👎"; suffix: " "; }
+@counter-style chapter {
+    system: extends lower-roman;
+    prefix: "(";
+    suffix: ") ";
+    range: 1 10;
+    fallback: thumbs;
+}
+@counter-style money { system: additive; additive-symbols: 5 "V", 1 "I", 0 "-"; negative: "(" ")"; range: -10 10; }
+@counter-style binary { system: numeric; symbols: "0" "1"; pad: 4 "0"; }
+@counter-style broken { system: alphabetic; symbols: a; }
+"#;
+
+#[test]
+fn test_counter_style_descriptors() {
+    let stylesheet = crate::parse_stylesheet(CSS).unwrap();
+    let styles = stylesheet.counter_styles();
+
+    assert_eq!(styles.iter().map(|style| style.name.as_ref()).collect::<Vec<_>>(), ["thumbs", "chapter", "money", "binary"]);
+    assert_eq!(styles[1].system, Some(CounterSystem::Extends("lower-roman".into())));
+    assert_eq!(styles[1].prefix, Some(CounterSymbol::Text("(".into())));
+    assert_eq!(styles[1].range, Some(vec![1..=10]));
+    assert_eq!(styles[1].fallback.as_deref(), Some("thumbs"));
+    assert_eq!(
+        styles[2].additive_symbols,
+        [
+            AdditiveSymbol { weight: 5, symbol: CounterSymbol::Text("V".into()) },
+            AdditiveSymbol { weight: 1, symbol: CounterSymbol::Text("I".into()) },
+            AdditiveSymbol { weight: 0, symbol: CounterSymbol::Text("-".into()) },
+        ]
+    );
+    assert_eq!(styles[3].pad, Some((4, CounterSymbol::Text("0".into()))));
+
+    // Alphabetic counters need at least two symbols.
+    assert!(stylesheet.rules[4].as_at_rule().unwrap().counter_style().is_none());
+}
+
+#[test]
+fn test_render_counter_styles() {
+    let stylesheet = crate::parse_stylesheet(CSS).unwrap();
+    let styles = stylesheet.counter_styles();
+    let render = |style: &CounterStyleRule, values: &[i64]| values.iter().map(|&value| style.render_with(value, &styles)).collect::<Vec<_>>();
+
+    assert_eq!(render(&styles[0], &[1, 2, 3]), ["👍 ", "👎 ", "👍 "]);
+    // Out of range values use the fallback style, with its own suffix.
+    assert_eq!(render(&styles[1], &[4, 9, 11]), ["(iv) ", "(ix) ", "👍 "]);
+    assert_eq!(render(&styles[2], &[0, 7, -6, -11]), ["-. ", "VII. ", "(VI). ", "-11. "]);
+    assert_eq!(render(&styles[3], &[5, -1, 37]), ["0101. ", "-001. ", "100101. "]);
+}
+
+#[test]
+fn test_predefined_counter_styles() {
+    let render = |name: &str, value: i64| CounterStyleRule::predefined(name).unwrap().render(value);
+
+    assert_eq!(render("decimal", -12), "-12. ");
+    assert_eq!(render("decimal-leading-zero", 7), "07. ");
+    assert_eq!(render("lower-roman", 1999), "mcmxcix. ");
+    assert_eq!(render("upper-roman", 4000), "4000. ");
+    assert_eq!(render("lower-alpha", 28), "ab. ");
+    assert_eq!(render("upper-latin", 3), "C. ");
+    assert_eq!(render("lower-greek", 24), "ω. ");
+    assert_eq!(render("disc", 3), "• ");
+    assert_eq!(render("cjk-decimal", 2024), "二〇二四、");
+    assert_eq!(render("cjk-heavenly-stem", 11), "甲甲、");
+    assert_eq!(render("katakana", 2), "イ、");
+    assert_eq!(render("upper-armenian", 1), "Ա. ");
+    assert!(CounterStyleRule::predefined("no-such-style").is_none());
+
+    // The CJK and ethiopic algorithms are not implemented.
+    for name in UNSUPPORTED_PREDEFINED {
+        assert!(CounterStyleRule::predefined(name).is_none(), "{}", name);
+    }
+    let stylesheet = crate::parse_stylesheet("@counter-style x { system: extends simp-chinese-informal; }").unwrap();
+    assert_eq!(stylesheet.counter_styles()[0].render(12), "12. ");
+}
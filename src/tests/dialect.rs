@@ -0,0 +1,52 @@
+use crate::css_parser::error::ParseErrorKind;
+use crate::css_parser::lexer::{Dialect, Lexer, TokenType};
+use crate::css_parser::parser::CssParser;
+use pretty_assertions::assert_eq;
+
+fn token_types(css: &str, dialect: Dialect) -> Vec<TokenType<'_>> {
+    Lexer::new(css).with_dialect(dialect).map(|token| token.token_type).collect()
+}
+
+#[test]
+fn test_line_comments() {
+    assert_eq!(
+        token_types("1 // 2", Dialect::Standard),
+        vec![TokenType::Number(1.0), TokenType::Whitespace, TokenType::Slash, TokenType::Slash, TokenType::Whitespace, TokenType::Number(2.0)]
+    );
+    assert_eq!(token_types("1 // 2", Dialect::ScssLike), vec![TokenType::Number(1.0), TokenType::Whitespace]);
+
+    let css = "// header\na { color: red; } // trailing\n";
+    let stylesheet = CssParser::with_lexer(Lexer::new(css).with_dialect(Dialect::ScssLike)).parse_stylesheet().unwrap();
+    assert_eq!(stylesheet.rules.len(), 1);
+    assert!(CssParser::new(css).parse_stylesheet().is_err());
+}
+
+#[test]
+fn test_identifier_characters() {
+    assert_eq!(
+        token_types("a$b", Dialect::Standard),
+        vec![TokenType::Identifier("a".into()), TokenType::Dollar, TokenType::Identifier("b".into())]
+    );
+    assert_eq!(token_types("a$b", Dialect::Lenient), vec![TokenType::Identifier("a$b".into())]);
+    assert_eq!(token_types("a#b%c.d", Dialect::Lenient), vec![TokenType::Identifier("a#b%c.d".into())]);
+    assert_eq!(
+        token_types("red!important", Dialect::Lenient),
+        vec![TokenType::Identifier("red".into()), TokenType::ExclamationMark, TokenType::Identifier("important".into())]
+    );
+
+    let rule = CssParser::with_lexer(Lexer::new("a { color: red!important; }").with_dialect(Dialect::Lenient)).parse_rule().unwrap();
+    assert!(rule.declarations[0].is_important);
+    assert_eq!(rule.declarations[0].value.to_string(), "red");
+}
+
+#[test]
+fn test_property_hacks() {
+    let css = "a { *zoom: 1; color: red; }";
+
+    let rule = CssParser::with_lexer(Lexer::new(css).with_dialect(Dialect::Lenient)).parse_rule().unwrap();
+    assert_eq!(rule.declarations[0].property, "*zoom");
+    assert_eq!(rule.declarations[0].to_string(), "*zoom: 1;");
+
+    let error = crate::parse_stylesheet(css).unwrap_err();
+    assert_eq!(error.kind, ParseErrorKind::ExpectedPropertyName);
+}
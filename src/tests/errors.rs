@@ -0,0 +1,32 @@
+use crate::css_parser::error::ParseErrorKind;
+use crate::css_parser::lexer::TokenType;
+use crate::css_parser::parser::CssParser;
+use pretty_assertions::assert_eq;
+
+#[test]
+fn test_parse_error_location() {
+    let css = ".a {\n    color red;\n}";
+    let error = CssParser::new(css).parse_stylesheet().unwrap_err();
+
+    assert_eq!(error.kind, ParseErrorKind::UnexpectedToken);
+    assert_eq!(error.code(), "E002");
+    assert_eq!(error.expected.as_deref(), Some("':'"));
+    assert_eq!(error.found, Some(TokenType::Identifier("red".into())));
+    assert_eq!((error.line, error.column, error.length), (2, 11, 3));
+}
+
+#[test]
+fn test_parse_error_kinds() {
+    let error = CssParser::new("a { 10px: red; }").parse_stylesheet().unwrap_err();
+    assert_eq!(error.kind, ParseErrorKind::ExpectedPropertyName);
+
+    let error = CssParser::new("a { color: red !imp; }").parse_stylesheet().unwrap_err();
+    assert_eq!(error.kind, ParseErrorKind::InvalidImportant);
+
+    let error = CssParser::new("a { width: var(x); }").parse_stylesheet().unwrap_err();
+    assert_eq!(error.kind, ParseErrorKind::InvalidVariableName);
+
+    let error = CssParser::new("a { color: red;").parse_stylesheet().unwrap_err();
+    assert_eq!(error.kind, ParseErrorKind::UnexpectedEof);
+    assert_eq!(error.found, Some(TokenType::EOF));
+}
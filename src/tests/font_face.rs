@@ -0,0 +1,77 @@
+use std::borrow::Cow;
+
+use crate::css_parser::font_face::{FontDisplay, FontRange, FontSource, FontStyle, UnicodeRange};
+use pretty_assertions::assert_eq;
+
+const CSS: &str = r#"
+@font-face {
+    font-family: "Open Sans";
+    src: local("Open Sans"), local(OpenSans-Regular),
+         url("/fonts/OpenSans.woff2") format("woff2") tech(variations, color-COLRv1),
+         url(/fonts/OpenSans.woff) format(woff);
+    font-weight: 300 800;
+    font-stretch: condensed 125%;
+    font-style: oblique 0deg 20deg;
+    font-display: swap;
+    unicode-range: U+0000-00FF, U+0131, U+4??;
+}
+"#;
+
+#[test]
+fn test_font_face_descriptors() {
+    let stylesheet = crate::parse_stylesheet(CSS).unwrap();
+    let font_face = stylesheet.rules[0].as_at_rule().unwrap().font_face().unwrap();
+
+    assert_eq!(font_face.family.as_deref(), Some("Open Sans"));
+    assert_eq!(
+        font_face.sources,
+        [
+            FontSource::Local("Open Sans".into()),
+            FontSource::Local("OpenSans-Regular".into()),
+            FontSource::Url {
+                url: "/fonts/OpenSans.woff2".into(),
+                format: Some("woff2".into()),
+                tech: vec![Cow::from("variations"), Cow::from("color-COLRv1")],
+            },
+            FontSource::Url { url: "/fonts/OpenSans.woff".into(), format: Some("woff".into()), tech: vec![] },
+        ]
+    );
+    assert_eq!(font_face.weight, Some(FontRange { min: 300.0, max: 800.0 }));
+    assert_eq!(font_face.stretch, Some(FontRange { min: 75.0, max: 125.0 }));
+    assert_eq!(font_face.style, Some(FontStyle::Oblique(FontRange { min: 0.0, max: 20.0 })));
+    assert_eq!(font_face.display, Some(FontDisplay::Swap));
+    assert_eq!(
+        font_face.unicode_range,
+        [
+            UnicodeRange { start: 0, end: 0xFF },
+            UnicodeRange { start: 0x131, end: 0x131 },
+            UnicodeRange { start: 0x400, end: 0x4FF },
+        ]
+    );
+
+    // Invalid descriptors are ignored; the default range covers everything.
+    let font_face = crate::parse_stylesheet("@font-face { font-weight: 1200; font-style: italic; unicode-range: U+12-1; }").unwrap();
+    let font_face = font_face.rules[0].as_at_rule().unwrap().font_face().unwrap();
+    assert_eq!(font_face.weight, None);
+    assert_eq!(font_face.style, Some(FontStyle::Italic));
+    assert_eq!(font_face.unicode_range, [UnicodeRange::ALL]);
+    assert!(crate::parse_stylesheet("@media print { }").unwrap().rules[0].as_at_rule().unwrap().font_face().is_none());
+}
+
+#[test]
+fn test_unicode_range_coverage() {
+    let stylesheet = crate::parse_stylesheet(CSS).unwrap();
+    let font_face = stylesheet.rules[0].as_at_rule().unwrap().font_face().unwrap();
+
+    assert!(font_face.covers('a'));
+    assert!(font_face.covers('ı'));
+    assert!(font_face.covers('Ж'));
+    assert!(!font_face.covers('€'));
+    assert_eq!(font_face.coverage("abc€"), 0.75);
+    assert_eq!(font_face.coverage(""), 1.0);
+
+    assert_eq!(UnicodeRange::parse("U+1F6??"), Some(UnicodeRange { start: 0x1F600, end: 0x1F6FF }));
+    assert_eq!(UnicodeRange::parse("U+?1"), None);
+    assert_eq!(UnicodeRange::parse("U+110000"), None);
+    assert_eq!(UnicodeRange { start: 0x25, end: 0xFF }.to_string(), "U+25-FF");
+}
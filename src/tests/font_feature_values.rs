@@ -0,0 +1,64 @@
+use crate::css_parser::ast::AtRuleType;
+use crate::css_parser::font_face::FontDisplay;
+use crate::css_parser::font_feature_values::{FeatureSetting, FeatureType};
+use pretty_assertions::assert_eq;
+
+const CSS: &str = r#"
+@font-feature-values Font One, "Font Two" {
+    font-display: swap;
+    @styleset { nice-style: 12; pair: 1 3; too-big: 21; }
+    @swash { fancy: 1; }
+    @character-variant { alt-g: 2 4; }
+}
+@font-feature-values font one { @annotation { circled: 4; } }
+"#;
+
+#[test]
+fn test_font_feature_values_rule() {
+    let stylesheet = crate::parse_stylesheet(CSS).unwrap();
+    let at_rule = stylesheet.rules[0].as_at_rule().unwrap();
+    assert_eq!(at_rule.font_families().unwrap(), ["Font One", "Font Two"]);
+    assert_eq!(at_rule.rules[1].rule_type(), Some(&AtRuleType::FontFeatureType));
+    let styleset = at_rule.rules[1].as_at_rule().unwrap();
+    assert_eq!(styleset.feature_type(), Some(FeatureType::Styleset));
+
+    let rule = at_rule.font_feature_values().unwrap();
+    assert_eq!(rule.font_display, Some(FontDisplay::Swap));
+    assert_eq!(rule.get(FeatureType::Styleset, "nice-style"), Some(&[12][..]));
+    assert_eq!(rule.get(FeatureType::Styleset, "pair"), Some(&[1, 3][..]));
+    // Style sets only go up to 20.
+    assert_eq!(rule.get(FeatureType::Styleset, "too-big"), None);
+    assert_eq!(rule.get(FeatureType::CharacterVariant, "alt-g"), Some(&[2, 4][..]));
+
+    assert_eq!(
+        crate::parse_stylesheet("@font-feature-values Foo{@swash{fancy:1}}").unwrap().to_string(),
+        "@font-feature-values Foo {\n    @swash {\n        fancy: 1;\n    }\n}\n"
+    );
+}
+
+#[test]
+fn test_resolve_font_variant_alternates() {
+    let stylesheet = crate::parse_stylesheet(CSS).unwrap();
+    let values = stylesheet.font_feature_values("FONT ONE").unwrap();
+    assert!(stylesheet.font_feature_values("Font Three").is_none());
+
+    let alternates = crate::parse_value_for_property(
+        "font-variant-alternates",
+        "styleset(nice-style, pair) swash(fancy) historical-forms character-variant(alt-g, unknown) annotation(circled)",
+    )
+    .unwrap();
+    assert_eq!(
+        values.resolve(&alternates),
+        [
+            FeatureSetting::new("ss12", 1),
+            FeatureSetting::new("ss01", 1),
+            FeatureSetting::new("ss03", 1),
+            FeatureSetting::new("swsh", 1),
+            FeatureSetting::new("cswh", 1),
+            FeatureSetting::new("hist", 1),
+            FeatureSetting::new("cv02", 4),
+            FeatureSetting::new("nalt", 4),
+        ]
+    );
+    assert_eq!(FeatureSetting::new("ss01", 1).to_string(), "\"ss01\" 1");
+}
@@ -1,8 +1,7 @@
 use super::common::{compare_values, parse_test_file};
-use crate::css_parser::ast::ListSeparator::Space;
-use crate::css_parser::ast::Value::{Function, List, Literal, Number};
+use crate::css_parser::ast::ValueKind::{Function, List, Literal, Number};
 use crate::css_parser::ast::{
-    CalcExpression, CalcOperator, Color, RuleExt, StylesheetExt, Unit, Value,
+    CalcExpression, CalcOperator, Color, RuleExt, StylesheetExt, Unit, ValueKind,
 };
 
 #[test]
@@ -13,17 +12,17 @@ fn test_color_values() {
     let declarations = rule.get_declarations("color");
 
     // rgb(255, 0, 0)
-    let decl = declarations.get(0).unwrap();
+    let decl = declarations.first().unwrap();
     assert!(compare_values(
         &decl.value,
         &Function(
-            "rgb".to_string(),
+            "rgb".into(),
             vec![
-                Number(255f64, None),
-                Literal(",".to_string()),
-                Number(0f64, None),
-                Literal(",".to_string()),
-                Number(0f64, None),
+                Number(255f64, None).into(),
+                Literal(",".into()).into(),
+                Number(0f64, None).into(),
+                Literal(",".into()).into(),
+                Number(0f64, None).into(),
             ],
         )
     ));
@@ -33,15 +32,15 @@ fn test_color_values() {
     assert!(compare_values(
         &decl.value,
         &Function(
-            "rgba".to_string(),
+            "rgba".into(),
             vec![
-                Number(255f64, None),
-                Literal(",".to_string()),
-                Number(0f64, None),
-                Literal(",".to_string()),
-                Number(0f64, None),
-                Literal(",".to_string()),
-                Number(0.5f64, None),
+                Number(255f64, None).into(),
+                Literal(",".into()).into(),
+                Number(0f64, None).into(),
+                Literal(",".into()).into(),
+                Number(0f64, None).into(),
+                Literal(",".into()).into(),
+                Number(0.5f64, None).into(),
             ],
         )
     ));
@@ -51,13 +50,13 @@ fn test_color_values() {
     assert!(compare_values(
         &decl.value,
         &Function(
-            "rgba".to_string(),
+            "rgba".into(),
             vec![
-                Number(255f64, None),
-                Number(0f64, None),
-                Number(0f64, None),
-                Literal("/".to_string()),
-                Number(0.5f64, None),
+                Number(255f64, None).into(),
+                Number(0f64, None).into(),
+                Number(0f64, None).into(),
+                Literal("/".into()).into(),
+                Number(0.5f64, None).into(),
             ]
         )
     ));
@@ -67,13 +66,13 @@ fn test_color_values() {
     assert!(compare_values(
         &decl.value,
         &Function(
-            "rgba".to_string(),
+            "rgba".into(),
             vec![
-                Number(255f64, None),
-                Number(0f64, None),
-                Number(0f64, None),
-                Literal("/".to_string()),
-                Number(1f64, None),
+                Number(255f64, None).into(),
+                Number(0f64, None).into(),
+                Number(0f64, None).into(),
+                Literal("/".into()).into(),
+                Number(1f64, None).into(),
             ]
         )
     ));
@@ -83,13 +82,13 @@ fn test_color_values() {
     assert!(compare_values(
         &decl.value,
         &Function(
-            "hsl".to_string(),
+            "hsl".into(),
             vec![
-                Number(0f64, None),
-                Literal(",".to_string()),
-                Number(100f64, Some(Unit::Percent)),
-                Literal(",".to_string()),
-                Number(50f64, Some(Unit::Percent)),
+                Number(0f64, None).into(),
+                Literal(",".into()).into(),
+                Number(100f64, Some(Unit::Percent)).into(),
+                Literal(",".into()).into(),
+                Number(50f64, Some(Unit::Percent)).into(),
             ]
         )
     ));
@@ -99,13 +98,13 @@ fn test_color_values() {
     assert!(compare_values(
         &decl.value,
         &Function(
-            "hsla".to_string(),
+            "hsla".into(),
             vec![
-                Number(0f64, None),
-                Number(100f64, Some(Unit::Percent)),
-                Number(50f64, Some(Unit::Percent)),
-                Literal("/".to_string()),
-                Number(0.5f64, None),
+                Number(0f64, None).into(),
+                Number(100f64, Some(Unit::Percent)).into(),
+                Number(50f64, Some(Unit::Percent)).into(),
+                Literal("/".into()).into(),
+                Number(0.5f64, None).into(),
             ]
         )
     ));
@@ -115,11 +114,11 @@ fn test_color_values() {
     assert!(compare_values(
         &decl.value,
         &Function(
-            "oklab".to_string(),
+            "oklab".into(),
             vec![
-                Number(0f64, None),
-                Number(0.5f64, None),
-                Number(0.5f64, None),
+                Number(0f64, None).into(),
+                Number(0.5f64, None).into(),
+                Number(0.5f64, None).into(),
             ]
         )
     ));
@@ -129,15 +128,15 @@ fn test_color_values() {
     assert!(compare_values(
         &decl.value,
         &Function(
-            "rgba".to_string(),
+            "rgba".into(),
             vec![
-                Value::VarFunction("--test".to_string(), None),
-                Literal("/".to_string()),
-                Value::Calc(CalcExpression::BinaryOperation(
+                ValueKind::VarFunction("--test".into(), None).into(),
+                Literal("/".into()).into(),
+                ValueKind::Calc(CalcExpression::BinaryOperation(
                     Box::new(CalcExpression::Number(4f64, None)),
                     CalcOperator::Add,
                     Box::new(CalcExpression::Number(8f64, None))
-                ))
+                )).into()
             ]
         )
     ));
@@ -147,11 +146,11 @@ fn test_color_values() {
     assert!(compare_values(
         &decl.value,
         &Function(
-            "oklab".to_string(),
+            "oklab".into(),
             vec![
-                Number(40.1f64, Some(Unit::Percent)),
-                Number(0.1143f64, None),
-                Number(0.045f64, None),
+                Number(40.1f64, Some(Unit::Percent)).into(),
+                Number(0.1143f64, None).into(),
+                Number(0.045f64, None).into(),
             ]
         )
     ));
@@ -161,11 +160,11 @@ fn test_color_values() {
     assert!(compare_values(
         &decl.value,
         &Function(
-            "oklab".to_string(),
+            "oklab".into(),
             vec![
-                Number(59.69f64, Some(Unit::Percent)),
-                Number(0.1007f64, None),
-                Number(0.1191f64, None),
+                Number(59.69f64, Some(Unit::Percent)).into(),
+                Number(0.1007f64, None).into(),
+                Number(0.1191f64, None).into(),
             ]
         )
     ));
@@ -175,13 +174,13 @@ fn test_color_values() {
     assert!(compare_values(
         &decl.value,
         &Function(
-            "oklab".to_string(),
+            "oklab".into(),
             vec![
-                Number(59.69f64, Some(Unit::Percent)),
-                Number(0.1007f64, None),
-                Number(0.1191f64, None),
-                Literal("/".to_string()),
-                Number(0.5f64, None),
+                Number(59.69f64, Some(Unit::Percent)).into(),
+                Number(0.1007f64, None).into(),
+                Number(0.1191f64, None).into(),
+                Literal("/".into()).into(),
+                Number(0.5f64, None).into(),
             ]
         )
     ));
@@ -191,21 +190,21 @@ fn test_color_values() {
     assert!(compare_values(
         &decl.value,
         &Function(
-            "oklab".to_string(),
+            "oklab".into(),
             vec![
-                Literal("from".to_string()),
-                Value::Color(Color::Named("green".to_string())),
-                Literal("l".to_string()),
-                Literal("a".to_string()),
-                Literal("b".to_string()),
-                Literal("/".to_string()),
-                Number(0.5f64, None),
+                Literal("from".into()).into(),
+                ValueKind::Color(Color::Named("green".into())).into(),
+                Literal("l".into()).into(),
+                Literal("a".into()).into(),
+                Literal("b".into()).into(),
+                Literal("/".into()).into(),
+                Number(0.5f64, None).into(),
             ]
         )
     ));
 
     // oklab(from #0000FF calc(l + 0.1) a b / calc(alpha * 0.9))
-    let decl = declarations.get(12).unwrap();
+    let _decl = declarations.get(12).unwrap();
     // TODO l and alpha in calc expressions are not parsed correctly
     /*assert!(compare_values(
         &decl.value,
@@ -214,30 +213,30 @@ fn test_color_values() {
             vec![
                 List(
                     vec![
-                        Literal("from".to_string()),
-                        Value::Color(Color::Hex("#0000FF".to_string())),
-                        Value::Calc(CalcExpression::BinaryOperation(
+                        Literal("from".to_string()).into(),
+                        ValueKind::Color(Color::Hex("#0000FF".to_string())).into(),
+                        ValueKind::Calc(CalcExpression::BinaryOperation(
                             Box::new(CalcExpression::Variable("l".to_string())),
                             CalcOperator::Add,
                             Box::new(CalcExpression::Number(0.1f64, None))
-                        )),
-                        Value::Keyword("a".to_string()),
-                        Value::Keyword("b".to_string()),
+                        )).into(),
+                        ValueKind::Keyword("a".to_string()).into(),
+                        ValueKind::Keyword("b".to_string()).into(),
                     ],
                     Space
-                ),
-                Literal("/".to_string()),
-                Value::Calc(CalcExpression::BinaryOperation(
+                ).into(),
+                Literal("/".to_string()).into(),
+                ValueKind::Calc(CalcExpression::BinaryOperation(
                     Box::new(CalcExpression::Variable("alpha".to_string())),
                     CalcOperator::Multiply,
                     Box::new(CalcExpression::Number(0.9f64, None))
-                )),
+                )).into(),
             ]
         )
     ));*/
 
     // oklab(from hsl(180 100% 50%) calc(l - 0.1) a b)
-    let decl = declarations.get(13).unwrap();
+    let _decl = declarations.get(13).unwrap();
     // TODO l in calc expression is not parsed correctly
     /*assert!(compare_values(
         &decl.value,
@@ -245,28 +244,28 @@ fn test_color_values() {
             "oklab".to_string(),
             vec![List(
                 vec![
-                    Literal("from".to_string()),
+                    Literal("from".to_string()).into(),
                     Function(
                         "hsl".to_string(),
                         vec![List(
                             vec![
-                                Number(180f64, None),
-                                Number(100f64, Some(Unit::Percent)),
-                                Number(50f64, Some(Unit::Percent)),
+                                Number(180f64, None).into(),
+                                Number(100f64, Some(Unit::Percent)).into(),
+                                Number(50f64, Some(Unit::Percent)).into(),
                             ],
                             Space
-                        )]
-                    ),
-                    Value::Calc(CalcExpression::BinaryOperation(
+                        ).into()]
+                    ).into(),
+                    ValueKind::Calc(CalcExpression::BinaryOperation(
                         Box::new(CalcExpression::Variable("l".to_string())),
                         CalcOperator::Subtract,
                         Box::new(CalcExpression::Number(0.1f64, None))
-                    )),
-                    Literal("a".to_string()),
-                    Literal("b".to_string()),
+                    )).into(),
+                    Literal("a".to_string()).into(),
+                    Literal("b".to_string()).into(),
                 ],
                 Space
-            ),]
+            ).into(),]
         )
     ));*/
 
@@ -275,13 +274,13 @@ fn test_color_values() {
     assert!(compare_values(
         &decl.value,
         &Function(
-            "rgba".to_string(),
+            "rgba".into(),
             vec![
-                Number(0f64, None),
-                Number(0f64, None),
-                Number(0f64, None),
-                Literal("/".to_string()),
-                Number(0.5f64, Some(Unit::Percent)),
+                Number(0f64, None).into(),
+                Number(0f64, None).into(),
+                Number(0f64, None).into(),
+                Literal("/".into()).into(),
+                Number(0.5f64, Some(Unit::Percent)).into(),
             ]
         )
     ));
@@ -295,26 +294,26 @@ fn test_color_mix_values() {
     let declarations = rule.get_declarations("color");
 
     // color-mix(in hsl, hsl(200 50 80), coral 80%)
-    let decl = declarations.get(0).unwrap();
+    let decl = declarations.first().unwrap();
     assert!(compare_values(
         &decl.value,
         &Function(
-            "color-mix".to_string(),
+            "color-mix".into(),
             vec![
-                Literal("in".to_string()),
-                Literal("hsl".to_string()),
-                Literal(",".to_string()),
+                Literal("in".into()).into(),
+                Literal("hsl".into()).into(),
+                Literal(",".into()).into(),
                 Function(
-                    "hsl".to_string(),
+                    "hsl".into(),
                     vec![
-                        Number(200f64, None),
-                        Number(50f64, None),
-                        Number(80f64, None),
+                        Number(200f64, None).into(),
+                        Number(50f64, None).into(),
+                        Number(80f64, None).into(),
                     ]
-                ),
-                Literal(",".to_string()),
-                Value::Color(Color::Named("coral".to_string())),
-                Number(80f64, Some(Unit::Percent)),
+                ).into(),
+                Literal(",".into()).into(),
+                ValueKind::Color(Color::Named("coral".into())).into(),
+                Number(80f64, Some(Unit::Percent)).into(),
             ]
         )
     ));
@@ -324,23 +323,23 @@ fn test_color_mix_values() {
     assert!(compare_values(
         &decl.value,
         &Function(
-            "color-mix".to_string(),
+            "color-mix".into(),
             vec![
-                Literal("in".to_string()),
-                Literal("lch".to_string()),
-                Literal("longer".to_string()),
-                Literal("hue".to_string()),
-                Literal(",".to_string()),
+                Literal("in".into()).into(),
+                Literal("lch".into()).into(),
+                Literal("longer".into()).into(),
+                Literal("hue".into()).into(),
+                Literal(",".into()).into(),
                 Function(
-                    "hsl".to_string(),
+                    "hsl".into(),
                     vec![
-                        Number(200f64, Some(Unit::Deg)),
-                        Number(50f64, Some(Unit::Percent)),
-                        Number(80f64, Some(Unit::Percent)),
+                        Number(200f64, Some(Unit::Deg)).into(),
+                        Number(50f64, Some(Unit::Percent)).into(),
+                        Number(80f64, Some(Unit::Percent)).into(),
                     ]
-                ),
-                Literal(",".to_string()),
-                Value::Color(Color::Named("coral".to_string())),
+                ).into(),
+                Literal(",".into()).into(),
+                ValueKind::Color(Color::Named("coral".into())).into(),
             ]
         )
     ));
@@ -350,14 +349,14 @@ fn test_color_mix_values() {
     assert!(compare_values(
         &decl.value,
         &Function(
-            "color-mix".to_string(),
+            "color-mix".into(),
             vec![
-                Literal("in".to_string()),
-                Literal("srgb".to_string()),
-                Literal(",".to_string()),
-                Value::Color(Color::Named("plum".to_string())),
-                Literal(",".to_string()),
-                Value::Color(Color::Hex("#f00".to_string())),
+                Literal("in".into()).into(),
+                Literal("srgb".into()).into(),
+                Literal(",".into()).into(),
+                ValueKind::Color(Color::Named("plum".into())).into(),
+                Literal(",".into()).into(),
+                ValueKind::Color(Color::Hex("#f00".into())).into(),
             ]
         )
     ));
@@ -367,16 +366,16 @@ fn test_color_mix_values() {
     assert!(compare_values(
         &decl.value,
         &Function(
-            "color-mix".to_string(),
+            "color-mix".into(),
             vec![
-                Literal("in".to_string()),
-                Literal("lab".to_string()),
-                Literal(",".to_string()),
-                Value::Color(Color::Named("plum".to_string())),
-                Number(60f64, Some(Unit::Percent)),
-                Literal(",".to_string()),
-                Value::Color(Color::Hex("#f00".to_string())),
-                Number(50f64, Some(Unit::Percent)),
+                Literal("in".into()).into(),
+                Literal("lab".into()).into(),
+                Literal(",".into()).into(),
+                ValueKind::Color(Color::Named("plum".into())).into(),
+                Number(60f64, Some(Unit::Percent)).into(),
+                Literal(",".into()).into(),
+                ValueKind::Color(Color::Hex("#f00".into())).into(),
+                Number(50f64, Some(Unit::Percent)).into(),
             ]
         )
     ));
@@ -386,14 +385,14 @@ fn test_color_mix_values() {
     assert!(compare_values(
         &decl.value,
         &Function(
-            "color-mix".to_string(),
+            "color-mix".into(),
             vec![
-                Literal("in".to_string()),
-                Literal("--swop5c".to_string()),
-                Literal(",".to_string()),
-                Value::Color(Color::Named("red".to_string())),
-                Literal(",".to_string()),
-                Value::Color(Color::Named("blue".to_string())),
+                Literal("in".into()).into(),
+                Literal("--swop5c".into()).into(),
+                Literal(",".into()).into(),
+                ValueKind::Color(Color::Named("red".into())).into(),
+                Literal(",".into()).into(),
+                ValueKind::Color(Color::Named("blue".into())).into(),
             ]
         )
     ));
@@ -407,18 +406,18 @@ fn test_palette_mix_values() {
     let declarations = rule.get_declarations("font-palette");
 
     // palette-mix(in lch, normal, dark)
-    let decl = declarations.get(0).unwrap();
+    let decl = declarations.first().unwrap();
     assert!(compare_values(
         &decl.value,
         &Function(
-            "palette-mix".to_string(),
+            "palette-mix".into(),
             vec![
-                Literal("in".to_string()),
-                Literal("lch".to_string()),
-                Literal(",".to_string()),
-                Value::Keyword("normal".to_string()),
-                Literal(",".to_string()),
-                Literal("dark".to_string()),
+                Literal("in".into()).into(),
+                Literal("lch".into()).into(),
+                Literal(",".into()).into(),
+                ValueKind::Keyword("normal".into()).into(),
+                Literal(",".into()).into(),
+                Literal("dark".into()).into(),
             ]
         )
     ));
@@ -428,14 +427,14 @@ fn test_palette_mix_values() {
     assert!(compare_values(
         &decl.value,
         &Function(
-            "palette-mix".to_string(),
+            "palette-mix".into(),
             vec![
-                Literal("in".to_string()),
-                Literal("lch".to_string()),
-                Literal(",".to_string()),
-                Literal("--blues".to_string()),
-                Literal(",".to_string()),
-                Literal("--yellows".to_string()),
+                Literal("in".into()).into(),
+                Literal("lch".into()).into(),
+                Literal(",".into()).into(),
+                Literal("--blues".into()).into(),
+                Literal(",".into()).into(),
+                Literal("--yellows".into()).into(),
             ]
         )
     ));
@@ -445,16 +444,16 @@ fn test_palette_mix_values() {
     assert!(compare_values(
         &decl.value,
         &Function(
-            "palette-mix".to_string(),
+            "palette-mix".into(),
             vec![
-                Literal("in".to_string()),
-                Literal("lch".to_string()),
-                Literal(",".to_string()),
-                Literal("--blues".to_string()),
-                Number(50f64, Some(Unit::Percent)),
-                Literal(",".to_string()),
-                Literal("--yellows".to_string()),
-                Number(50f64, Some(Unit::Percent)),
+                Literal("in".into()).into(),
+                Literal("lch".into()).into(),
+                Literal(",".into()).into(),
+                Literal("--blues".into()).into(),
+                Number(50f64, Some(Unit::Percent)).into(),
+                Literal(",".into()).into(),
+                Literal("--yellows".into()).into(),
+                Number(50f64, Some(Unit::Percent)).into(),
             ]
         )
     ));
@@ -464,16 +463,16 @@ fn test_palette_mix_values() {
     assert!(compare_values(
         &decl.value,
         &Function(
-            "palette-mix".to_string(),
+            "palette-mix".into(),
             vec![
-                Literal("in".to_string()),
-                Literal("hsl".to_string()),
-                Literal("shorter".to_string()),
-                Literal("hue".to_string()),
-                Literal(",".to_string()),
-                Literal("--blues".to_string()),
-                Literal(",".to_string()),
-                Literal("--yellows".to_string()),
+                Literal("in".into()).into(),
+                Literal("hsl".into()).into(),
+                Literal("shorter".into()).into(),
+                Literal("hue".into()).into(),
+                Literal(",".into()).into(),
+                Literal("--blues".into()).into(),
+                Literal(",".into()).into(),
+                Literal("--yellows".into()).into(),
             ]
         )
     ));
@@ -491,11 +490,11 @@ fn test_space_separated_functions() {
         &decl.value,
         &List(
             vec![
-                Function("blur".to_string(), vec![Number(5.0, Some(Unit::Px))]),
+                Function("blur".into(), vec![Number(5.0, Some(Unit::Px)).into()]).into(),
                 Function(
-                    "brightness".to_string(),
-                    vec![Number(120.0, Some(Unit::Percent))]
-                ),
+                    "brightness".into(),
+                    vec![Number(120.0, Some(Unit::Percent)).into()]
+                ).into(),
             ],
         )
     ));
@@ -506,16 +505,16 @@ fn test_space_separated_functions() {
         &decl.value,
         &List(
             vec![
-                Function("rotate".to_string(), vec![Number(45.0, Some(Unit::Deg))]),
-                Function("scale".to_string(), vec![Number(2.0, None)]),
+                Function("rotate".into(), vec![Number(45.0, Some(Unit::Deg)).into()]).into(),
+                Function("scale".into(), vec![Number(2.0, None).into()]).into(),
                 Function(
-                    "translate".to_string(),
+                    "translate".into(),
                     vec![
-                        Number(10.0, Some(Unit::Px)),
-                        Literal(",".to_string()),
-                        Value::Calc(CalcExpression::Number(10.0, Some(Unit::Px))),
+                        Number(10.0, Some(Unit::Px)).into(),
+                        Literal(",".into()).into(),
+                        ValueKind::Calc(CalcExpression::Number(10.0, Some(Unit::Px))).into(),
                     ],
-                ),
+                ).into(),
             ],
         )
     ));
@@ -0,0 +1,53 @@
+use crate::css_parser::error::ImportError;
+use crate::css_parser::import::{inline_imports, FileSystemResolver};
+use crate::css_parser::supports::SupportsCondition;
+use pretty_assertions::assert_eq;
+
+#[test]
+fn test_import_prelude() {
+    let css = "@import url(\"theme.css\") layer(framework.base) supports(display: grid) screen and (min-width: 600px);\n\
+               @import 'reset.css' layer supports((display: flex) or (display: grid));";
+    let stylesheet = crate::parse_stylesheet(css).unwrap();
+
+    let import = stylesheet.rules[0].as_at_rule().unwrap().import().unwrap();
+    assert_eq!(import.url, "theme.css");
+    assert_eq!(import.layer.as_ref().unwrap().parts, vec!["framework", "base"]);
+    assert_eq!(import.supports, Some(SupportsCondition::Declaration { property: "display".into(), value: "grid".into() }));
+    assert_eq!(import.media.to_string(), "screen and (min-width: 600px)");
+    assert_eq!(
+        stylesheet.rules[0].as_at_rule().unwrap().query,
+        "\"theme.css\" layer(framework.base) supports(display: grid) screen and (min-width: 600px)"
+    );
+
+    let import = stylesheet.rules[1].as_at_rule().unwrap().import().unwrap();
+    assert!(import.layer.as_ref().unwrap().is_anonymous());
+    assert!(matches!(import.supports, Some(SupportsCondition::Or(_))));
+    assert!(import.media.queries.is_empty());
+    assert_eq!(import.to_string(), "\"reset.css\" layer supports((display: flex) or (display: grid))");
+}
+
+#[test]
+fn test_inline_imports() {
+    let resolver = FileSystemResolver::new("src/tests/resources/imports");
+
+    let stylesheet = inline_imports(&resolver, "main.css").unwrap();
+    assert_eq!(
+        stylesheet.to_string(),
+        "@charset \"utf-8\";\n\
+         @layer framework.base {\n    * {\n        box-sizing: border-box;\n    }\n    body {\n        margin: 0;\n    }\n}\n\
+         @supports (display: grid) {\n    @media print {\n        nav {\n            display: none;\n        }\n    }\n}\n\
+         main {\n    display: grid;\n}\n"
+    );
+
+    let error = inline_imports(&resolver, "cycle-a.css").unwrap_err();
+    let ImportError::Cycle(chain) = error else { panic!("expected a cycle, got {}", error) };
+    assert_eq!(chain.len(), 3);
+    assert!(chain[0].ends_with("cycle-a.css") && chain[1].ends_with("cycle-b.css") && chain[2].ends_with("cycle-a.css"));
+
+    assert!(matches!(inline_imports(&resolver, "missing.css"), Err(ImportError::Io { .. })));
+
+    let outside = |url: &str| matches!(inline_imports(&resolver, url), Err(ImportError::OutsideRoot(_)));
+    assert!(outside("../comments.css"));
+    assert!(outside("/etc/hosts"));
+    assert!(outside("escape.css"));
+}
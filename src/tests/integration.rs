@@ -4,6 +4,6 @@ use super::common::parse_test_file;
 fn test_full_stylesheet_parsing() {
     let stylesheet = parse_test_file("integration-youtube-www-player.css").unwrap();
 
-    assert_eq!(stylesheet.rules.len(), 2877);
-    assert_eq!(stylesheet.at_rules.len(), 74);
+    assert_eq!(stylesheet.style_rules().count(), 2877);
+    assert_eq!(stylesheet.at_rules().count(), 74);
 }
@@ -0,0 +1,65 @@
+use crate::css_parser::error::ParseErrorKind;
+use crate::css_parser::keyframes::{KeyframeOffset, TimelineRange};
+use pretty_assertions::assert_eq;
+
+const CSS: &str = r#"
+@keyframes fade {
+    from, 50% { opacity: 0; animation-timing-function: ease-in; }
+    to { opacity: 1 !important; color: red; }
+}
+@-webkit-keyframes fade { 0% { opacity: 0; } 100% { opacity: 1; } }
+@media (prefers-reduced-motion: no-preference) {
+    @keyframes reveal { cover 0% { opacity: 0; } contain 50%, exit-crossing 12.5% { opacity: 1; } }
+}
+"#;
+
+#[test]
+fn test_keyframes_rule() {
+    let stylesheet = crate::parse_stylesheet(CSS).unwrap();
+    let fade = stylesheet.rules[0].as_at_rule().unwrap().keyframes().unwrap();
+
+    assert_eq!(fade.name, "fade");
+    assert_eq!(fade.prefix, None);
+    assert_eq!(fade.frames[0].offsets, [KeyframeOffset { range: None, offset: 0.0 }, KeyframeOffset { range: None, offset: 0.5 }]);
+    assert_eq!(fade.frames[0].timing_function.as_ref().unwrap().to_string(), "ease-in");
+    assert_eq!(fade.frames[0].declarations.len(), 1);
+    // `!important` declarations don't apply in keyframes.
+    assert_eq!(fade.frames[1].declarations.iter().map(|declaration| declaration.property.as_ref()).collect::<Vec<_>>(), ["color"]);
+    assert_eq!(fade.frames_at(1.0).count(), 1);
+
+    let reveal = &stylesheet.keyframes("reveal")[0];
+    assert_eq!(
+        reveal.frames[1].offsets,
+        [
+            KeyframeOffset { range: Some(TimelineRange::Contain), offset: 0.5 },
+            KeyframeOffset { range: Some(TimelineRange::ExitCrossing), offset: 0.125 },
+        ]
+    );
+    assert_eq!(reveal.frames[1].offsets[1].to_string(), "exit-crossing 12.5%");
+
+    // Selectors are written back as given, keywords included.
+    let at_rule = stylesheet.rules[0].as_at_rule().unwrap();
+    assert_eq!(at_rule.to_string().lines().nth(1).unwrap().trim(), "from, 50% {");
+    assert_eq!(KeyframeOffset::parse("Cover 12.5%"), Some(KeyframeOffset { range: Some(TimelineRange::Cover), offset: 0.125 }));
+    assert_eq!(KeyframeOffset::parse("cover to"), None);
+
+    for css in ["@keyframes a { cover 10px { top: 0; } }", "@keyframes a { 150% { top: 0; } }", "@keyframes a { -5% { top: 0; } }", "@keyframes a { exit 101% { top: 0; } }"] {
+        let error = crate::parse_stylesheet(css).unwrap_err();
+        assert_eq!(error.kind, ParseErrorKind::InvalidKeyframeSelector, "{}", css);
+    }
+}
+
+#[test]
+fn test_keyframes_by_name() {
+    let stylesheet = crate::parse_stylesheet(CSS).unwrap();
+
+    let fade = stylesheet.keyframes("fade");
+    assert_eq!(fade.iter().map(|rule| rule.prefix).collect::<Vec<_>>(), [None, Some("-webkit-")]);
+    assert_eq!(fade[1].frames[1].offsets, [KeyframeOffset { range: None, offset: 1.0 }]);
+
+    let by_name = stylesheet.keyframes_by_name();
+    assert_eq!(by_name.len(), 2);
+    assert_eq!(by_name["fade"].len(), 2);
+    assert_eq!(by_name["reveal"].len(), 1);
+    assert!(stylesheet.keyframes("missing").is_empty());
+}
@@ -0,0 +1,52 @@
+use crate::css_parser::ast::{AtRulePrelude, LayerName};
+use pretty_assertions::assert_eq;
+
+fn names(order: &[LayerName<'_>]) -> Vec<String> {
+    order.iter().map(LayerName::to_string).collect()
+}
+
+#[test]
+fn test_layer_statements_and_blocks() {
+    let css = "@layer reset, framework.base;\n@layer framework { a { color: red; } }\n@layer { b { color: blue; } }\nc { color: green; }";
+    let stylesheet = crate::parse_stylesheet(css).unwrap();
+    assert_eq!(stylesheet.rules.len(), 4);
+
+    let statement = stylesheet.rules[0].as_at_rule().unwrap();
+    assert_eq!(names(statement.layer_names()), ["reset", "framework.base"]);
+    assert!(statement.rules.is_empty());
+
+    let block = stylesheet.rules[1].as_at_rule().unwrap();
+    assert!(matches!(&block.prelude, AtRulePrelude::LayerBlock(name) if name.parts == ["framework"]));
+    assert_eq!(block.rules.len(), 1);
+
+    let anonymous = stylesheet.rules[2].as_at_rule().unwrap();
+    assert!(anonymous.layer_names()[0].is_anonymous());
+
+    assert_eq!(
+        stylesheet.to_string(),
+        "@layer reset, framework.base;\n@layer framework {\n    a {\n        color: red;\n    }\n}\n@layer {\n    b {\n        color: blue;\n    }\n}\nc {\n    color: green;\n}\n"
+    );
+
+    assert!(crate::parse_stylesheet("@layer a, b { a { color: red; } }").is_err());
+    assert!(crate::parse_stylesheet("@layer;").is_err());
+}
+
+#[test]
+fn test_layer_order() {
+    let css = "\
+        @import url(\"theme.css\") layer(theme);\n\
+        @import \"extra.css\" layer;\n\
+        @layer reset, framework.base;\n\
+        @layer framework { @layer components { a { color: red; } } }\n\
+        @layer { b { color: blue; } }\n\
+        @media print { @layer print; @layer reset { } }\n\
+        .card { @layer nested { color: red; } }\n\
+        @layer theme.dark;";
+    let stylesheet = crate::parse_stylesheet(css).unwrap();
+
+    assert_eq!(
+        names(&stylesheet.layer_order()),
+        ["theme.dark", "theme", "", "reset", "framework.base", "framework.components", "framework", "", "print", "nested"]
+    );
+    assert!(stylesheet.layer_order()[2].is_anonymous());
+}
@@ -0,0 +1,66 @@
+use std::borrow::Cow;
+
+use crate::css_parser::lexer::{Lexer, TokenType};
+use pretty_assertions::assert_eq;
+
+#[test]
+fn test_token_stream() {
+    let types: Vec<TokenType> = Lexer::new("a { color: red }").map(|token| token.token_type).collect();
+    assert_eq!(
+        types,
+        vec![
+            TokenType::Identifier("a".into()),
+            TokenType::Whitespace,
+            TokenType::OpenBrace,
+            TokenType::Whitespace,
+            TokenType::Identifier("color".into()),
+            TokenType::Colon,
+            TokenType::Whitespace,
+            TokenType::Identifier("red".into()),
+            TokenType::Whitespace,
+            TokenType::CloseBrace,
+        ]
+    );
+
+    let mut lexer = Lexer::new("width: 10px");
+    assert_eq!(lexer.peek_nth(3).token_type, TokenType::Number(10.0));
+    assert_eq!(lexer.peek_nth(4).token_type, TokenType::Unit(Cow::Borrowed("px")));
+    assert_eq!(lexer.peek_nth(9).token_type, TokenType::EOF);
+    assert_eq!(lexer.next_token().token_type, TokenType::Identifier("width".into()));
+}
+
+#[test]
+fn test_checkpoints() {
+    let mut lexer = Lexer::new("a b c d");
+    lexer.next_token();
+
+    let outer = lexer.checkpoint();
+    lexer.next_token();
+    let inner = lexer.checkpoint();
+    assert_eq!(lexer.next_token().token_type, TokenType::Identifier("b".into()));
+    lexer.next_token();
+    lexer.restore(inner);
+    assert_eq!(lexer.next_token().token_type, TokenType::Identifier("b".into()));
+    lexer.commit(outer);
+
+    let rest: Vec<TokenType> = lexer.map(|token| token.token_type).collect();
+    assert_eq!(
+        rest,
+        vec![
+            TokenType::Whitespace,
+            TokenType::Identifier("c".into()),
+            TokenType::Whitespace,
+            TokenType::Identifier("d".into()),
+        ]
+    );
+}
+
+#[test]
+#[cfg(debug_assertions)]
+#[should_panic(expected = "innermost first")]
+fn test_checkpoints_out_of_order() {
+    let mut lexer = Lexer::new("a b");
+    let outer = lexer.checkpoint();
+    let _inner = lexer.checkpoint();
+    lexer.commit(outer);
+}
@@ -0,0 +1,87 @@
+use crate::css_parser::error::ParseErrorKind;
+use crate::css_parser::media::{DeviceProfile, MediaComparison, MediaCondition, MediaFeature, MediaFeatureValue, MediaQualifier};
+use pretty_assertions::assert_eq;
+
+#[test]
+fn test_media_query_ast() {
+    let list = crate::parse_media_query_list("only screen and (max-width: 959px), not print and (color), (400px <= width < 700px)").unwrap();
+    assert_eq!(list.queries.len(), 3);
+
+    let screen = &list.queries[0];
+    assert_eq!(screen.qualifier, Some(MediaQualifier::Only));
+    assert_eq!(screen.media_type.as_deref(), Some("screen"));
+    assert_eq!(
+        screen.condition,
+        Some(MediaCondition::Feature(MediaFeature::Plain { name: "max-width".into(), value: MediaFeatureValue::Dimension(959.0, "px".into()) }))
+    );
+
+    assert_eq!(list.queries[1].qualifier, Some(MediaQualifier::Not));
+
+    assert_eq!(
+        list.queries[2].condition,
+        Some(MediaCondition::Feature(MediaFeature::Range {
+            name: "width".into(),
+            start: Some((MediaFeatureValue::Dimension(400.0, "px".into()), MediaComparison::Le)),
+            end: Some((MediaComparison::Lt, MediaFeatureValue::Dimension(700.0, "px".into()))),
+        }))
+    );
+
+    let list = crate::parse_media_query_list("(min-aspect-ratio: 16/9) and ((hover) or (pointer: coarse)) and (unknown-thing: 1 2)").unwrap();
+    assert_eq!(list.to_string(), "(min-aspect-ratio: 16/9) and ((hover) or (pointer: coarse)) and (unknown-thing: 1 2)");
+    assert!(matches!(&list.queries[0].condition, Some(MediaCondition::And(conditions)) if matches!(conditions[2], MediaCondition::GeneralEnclosed(_))));
+
+    let stylesheet = crate::parse_stylesheet("@media screen and (max-width:959px) { a { color: red } }").unwrap();
+    let media = stylesheet.rules[0].as_at_rule().unwrap();
+    assert_eq!(media.query, "screen and (max-width: 959px)");
+    assert_eq!(media.media().unwrap().queries[0].media_type.as_deref(), Some("screen"));
+
+    let error = crate::parse_media_query_list("(hover) and (color) or (grid)").unwrap_err();
+    assert_eq!(error.kind, ParseErrorKind::InvalidMediaQuery);
+}
+
+#[test]
+fn test_media_query_evaluation() {
+    let phone = DeviceProfile::screen(375.0, 812.0).with_resolution(3.0);
+    let desktop = DeviceProfile::screen(1440.0, 900.0)
+        .with_feature("prefers-color-scheme", MediaFeatureValue::Ident("dark".into()));
+
+    let matches = |query: &str, device: &DeviceProfile| crate::parse_media_query_list(query).unwrap().evaluate(device);
+
+    assert!(matches("screen and (max-width: 600px)", &phone));
+    assert!(!matches("screen and (max-width: 600px)", &desktop));
+    assert!(matches("(400px <= width <= 1500px)", &desktop));
+    assert!(!matches("(400px <= width <= 1500px)", &phone));
+    assert!(matches("(width > 40em)", &desktop));
+    assert!(matches("(orientation: portrait) and (min-resolution: 2dppx)", &phone));
+    assert!(matches("(-webkit-min-device-pixel-ratio: 2)", &phone));
+    assert!(matches("(prefers-color-scheme: dark)", &desktop));
+    assert!(!matches("(prefers-color-scheme: dark)", &phone));
+    assert!(matches("print, (min-aspect-ratio: 16/10)", &desktop));
+    assert!(matches("not print", &phone));
+    assert!(matches("not (hover: none)", &phone));
+    assert!(matches("", &phone));
+
+    // `no-preference` and `none` are false in a boolean context.
+    let reduced_motion = DeviceProfile::screen(375.0, 812.0).with_feature("prefers-reduced-motion", MediaFeatureValue::Ident("reduce".into()));
+    assert!(!matches("(prefers-reduced-motion)", &phone));
+    assert!(matches("not all and (prefers-reduced-motion)", &phone));
+    assert!(matches("(prefers-reduced-motion)", &reduced_motion));
+    assert!(!matches("(prefers-contrast)", &phone));
+    assert!(!matches("(forced-colors)", &phone));
+    assert!(matches("(prefers-color-scheme)", &phone));
+
+    // A unitless 0 compares as a length or resolution.
+    assert!(matches("(min-width: 0)", &phone));
+    assert!(matches("(width >= 0)", &phone));
+    assert!(!matches("(width < 0)", &phone));
+    assert!(!matches("not (min-width: 0)", &phone));
+    assert!(matches("(min-resolution: 0)", &phone));
+
+    let condition = crate::parse_media_query_list("not (not (color))").unwrap();
+    assert_eq!(condition.to_string(), "not (not (color))");
+    assert!(matches("not (not (color))", &phone));
+
+    // Unknown features never match, negated or not.
+    assert!(!matches("(unknown-feature)", &phone));
+    assert!(!matches("not screen and (unknown-feature)", &phone));
+}
@@ -1,8 +1,33 @@
+mod api;
 mod at_rules;
 mod basic;
+mod borrowed;
 mod calc;
+mod comments;
 mod common;
+mod component_value;
+mod container;
+mod counter_style;
+mod dialect;
+mod errors;
+mod font_face;
+mod font_feature_values;
 mod functions;
+mod import;
 mod integration;
+mod keyframes;
+mod layer;
+mod lexer;
+mod media;
+mod namespace;
 mod misc;
+mod nesting;
+mod order;
+mod page;
+mod property;
+mod recovery;
+mod scope;
+mod spans;
+mod supports;
 mod text;
+mod tokenizer;
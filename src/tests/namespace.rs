@@ -0,0 +1,57 @@
+use crate::css_parser::ast::{AttributeOperator, SelectorPartKind};
+use crate::css_parser::namespace::NamespacePrefix;
+use pretty_assertions::assert_eq;
+
+const CSS: &str = r#"
+@namespace url(http://www.w3.org/1999/xhtml);
+@namespace svg url("http://www.w3.org/2000/svg");
+@namespace xlink "http://www.w3.org/1999/xlink";
+@namespace svg "http://example.com/svg";
+svg|rect, *|*, |p, [xlink|href], a[*|hreflang|=en] { fill: red; }
+math|mi { color: blue; }
+"#;
+
+fn parts(selector: &str) -> Vec<SelectorPartKind<'_>> {
+    let stylesheet = crate::parse_stylesheet(selector).unwrap();
+    let rule = stylesheet.style_rules().next().unwrap();
+    rule.selectors[0].groups[0].parts.iter().map(|part| part.kind.clone().into_owned()).collect()
+}
+
+#[test]
+fn test_namespace_map() {
+    let stylesheet = crate::parse_stylesheet(CSS).unwrap();
+    let namespace = stylesheet.rules[1].as_at_rule().unwrap().namespace().unwrap();
+    assert_eq!(namespace.prefix.as_deref(), Some("svg"));
+    assert_eq!(namespace.url, "http://www.w3.org/2000/svg");
+    assert_eq!(stylesheet.rules[1].as_at_rule().unwrap().query, "svg \"http://www.w3.org/2000/svg\"");
+
+    let namespaces = stylesheet.namespaces();
+    assert_eq!(namespaces.default.as_deref(), Some("http://www.w3.org/1999/xhtml"));
+    assert_eq!(namespaces.get("svg"), Some("http://example.com/svg"));
+    assert_eq!(namespaces.get("xlink"), Some("http://www.w3.org/1999/xlink"));
+    assert_eq!(namespaces.get("math"), None);
+
+    let rules: Vec<_> = stylesheet.style_rules().collect();
+    assert!(namespaces.declares_all(&rules[0].selectors[0]));
+    assert!(!namespaces.declares_all(&rules[1].selectors[0]));
+}
+
+#[test]
+fn test_namespaced_selectors() {
+    assert_eq!(parts("svg|rect {}"), vec![SelectorPartKind::Element(Some(NamespacePrefix::Named("svg".into())), "rect".into())]);
+    assert_eq!(parts("*|* {}"), vec![SelectorPartKind::Universal(Some(NamespacePrefix::Any))]);
+    assert_eq!(parts("|p {}"), vec![SelectorPartKind::Element(Some(NamespacePrefix::None), "p".into())]);
+    assert_eq!(parts("[xlink|href] {}"), vec![SelectorPartKind::AttributeSelector(Some(NamespacePrefix::Named("xlink".into())), "href".into(), None)]);
+    assert_eq!(
+        parts("a[*|hreflang|=en] {}"),
+        vec![
+            SelectorPartKind::Element(None, "a".into()),
+            SelectorPartKind::AttributeSelector(Some(NamespacePrefix::Any), "hreflang".into(), Some((AttributeOperator::DashMatch, "en".into(), None))),
+        ]
+    );
+
+    let stylesheet = crate::parse_stylesheet(CSS).unwrap();
+    let rule = stylesheet.style_rules().next().unwrap();
+    let selectors: Vec<String> = rule.selectors.iter().map(ToString::to_string).collect();
+    assert_eq!(selectors, vec!["svg|rect", "*|*", "|p", "[xlink|href]", "a[*|hreflang|=\"en\"]"]);
+}
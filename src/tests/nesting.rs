@@ -0,0 +1,61 @@
+use crate::css_parser::ast::{CssRule, RuleExt, SelectorCombinator, SelectorPartKind};
+use crate::css_parser::error::ParseErrorKind;
+use pretty_assertions::assert_eq;
+
+const CSS: &str = include_str!("resources/nesting.css");
+
+#[test]
+fn test_nested_rules() {
+    let stylesheet = crate::parse_stylesheet(CSS).unwrap();
+    let card = stylesheet.rules[0].as_style().unwrap();
+    assert_eq!(card.declaration_count(), 1);
+    assert_eq!(card.rules.len(), 8);
+
+    let selectors: Vec<_> = card.rules.iter().filter_map(CssRule::as_style).filter(|rule| !rule.is_declaration_block()).map(|rule| rule.selectors[0].to_string()).collect();
+    assert_eq!(selectors, ["&:hover", ".title", "> img", "+ .card", ".dark &", "a:hover"]);
+
+    let hover = card.rules[0].as_style().unwrap();
+    assert_eq!(hover.selectors[0].groups[0].parts[0].kind, SelectorPartKind::Nesting);
+    let image = card.rules[2].as_style().unwrap();
+    assert_eq!(image.selectors[0].leading_combinator, Some(SelectorCombinator::Child));
+
+    let media = card.rules[6].as_at_rule().unwrap();
+    assert_eq!(media.query, "(max-width: 600px)");
+    let block = media.rules[0].as_style().unwrap();
+    assert!(block.is_declaration_block());
+    assert_eq!(block.declarations[0].property, "padding");
+    assert_eq!(media.rules[1].as_style().unwrap().selectors[0].to_string(), "& .title");
+
+    // Declarations after a nested rule stay after it, in a declaration block.
+    let trailing = card.rules[7].as_style().unwrap();
+    assert!(trailing.is_declaration_block());
+    assert_eq!(trailing.get_declaration_value("background").unwrap().to_string(), "none");
+    assert_eq!(trailing.span.text(CSS), "background: none");
+}
+
+#[test]
+fn test_nested_rules_display() {
+    let stylesheet = crate::parse_stylesheet(".a { color: red; &:hover { color: blue; } @media print { display: none; } }").unwrap();
+    assert_eq!(
+        stylesheet.to_string(),
+        ".a {\n    color: red;\n    &:hover {\n        color: blue;\n    }\n    @media print {\n        display: none;\n    }\n}\n"
+    );
+
+    let printed = stylesheet.to_string();
+    assert_eq!(crate::parse_stylesheet(&printed).unwrap().to_string(), printed);
+
+    // Declarations and nested rules keep their source order.
+    let stylesheet = crate::parse_stylesheet(".a { & { color: red } color: blue }").unwrap();
+    let printed = stylesheet.to_string();
+    assert_eq!(printed, ".a {\n    & {\n        color: red;\n    }\n    color: blue;\n}\n");
+    assert_eq!(crate::parse_stylesheet(&printed).unwrap().to_string(), printed);
+
+    // Relative selectors only make sense inside another rule.
+    let error = crate::parse_stylesheet("> a { color: red; }").unwrap_err();
+    assert_eq!(error.kind, ParseErrorKind::InvalidSelector);
+
+    // Without a `{` ahead, an item is parsed as a declaration.
+    let error = crate::parse_stylesheet(".a { color red; }").unwrap_err();
+    assert_eq!(error.kind, ParseErrorKind::UnexpectedToken);
+    assert_eq!(error.expected.as_deref(), Some("':'"));
+}
@@ -0,0 +1,33 @@
+use super::common::parse_test_file;
+use crate::css_parser::ast::{AtRuleType, CssRule};
+use crate::css_parser::parser::CssParser;
+use pretty_assertions::assert_eq;
+
+#[test]
+fn test_source_order() {
+    let stylesheet = parse_test_file("order.css").unwrap();
+
+    assert_eq!(stylesheet.rules.len(), 3);
+    assert!(matches!(stylesheet.rules[0], CssRule::Style(_)));
+    assert_eq!(stylesheet.rules[1].rule_type(), Some(&AtRuleType::Media));
+    assert!(matches!(stylesheet.rules[2], CssRule::Style(_)));
+
+    let media_rule = stylesheet.rules[1].as_at_rule().unwrap();
+    assert_eq!(media_rule.rules.len(), 3);
+    assert!(matches!(media_rule.rules[0], CssRule::Style(_)));
+    assert_eq!(media_rule.rules[1].rule_type(), Some(&AtRuleType::Page));
+    assert!(matches!(media_rule.rules[2], CssRule::Style(_)));
+}
+
+#[test]
+fn test_source_order_serialization() {
+    let stylesheet = parse_test_file("order.css").unwrap();
+    let css = stylesheet.to_string();
+
+    let print_position = css.find("@media print").unwrap();
+    assert!(css.find("color: red").unwrap() < print_position);
+    assert!(css.find("color: blue").unwrap() > print_position);
+
+    let reparsed = CssParser::new(&css).parse_stylesheet().unwrap();
+    assert_eq!(reparsed.to_string(), css);
+}
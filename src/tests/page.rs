@@ -0,0 +1,60 @@
+use crate::css_parser::ast::AtRuleType;
+use crate::css_parser::page::{MarginBox, PageInfo, PagePseudoClass, PageSelector};
+use pretty_assertions::assert_eq;
+
+const CSS: &str = include_str!("resources/page.css");
+
+#[test]
+fn test_page_rules() {
+    let stylesheet = crate::parse_stylesheet(CSS).unwrap();
+
+    let default = stylesheet.rules[0].as_at_rule().unwrap();
+    assert_eq!(default.page_selectors(), Some(&[][..]));
+    assert_eq!(default.rules[0].as_style().unwrap().declarations.len(), 2);
+    assert_eq!(default.rules[1].rule_type(), Some(&AtRuleType::PageMargin));
+    let bottom_right = default.rules[1].as_at_rule().unwrap();
+    assert_eq!(bottom_right.margin_box(), Some(MarginBox::BottomRight));
+
+    let invoice = stylesheet.rules[4].as_at_rule().unwrap();
+    assert_eq!(
+        invoice.page_selectors().unwrap(),
+        [
+            PageSelector { name: Some("invoice".into()), pseudo_classes: vec![] },
+            PageSelector { name: None, pseudo_classes: vec![PagePseudoClass::Blank] },
+        ]
+    );
+    assert_eq!(invoice.query, "invoice, :blank");
+
+    assert_eq!(
+        crate::parse_stylesheet("@page chapter:first{margin:0;@top-center{content:\"Title\"}}").unwrap().to_string(),
+        "@page chapter:first {\n    margin: 0;\n    @top-center {\n        content: \"Title\";\n    }\n}\n"
+    );
+    assert!(crate::parse_stylesheet("@page :middle { margin: 0; }").is_err());
+    assert_eq!(MarginBox::ALL.len(), 16);
+}
+
+#[test]
+fn test_page_style() {
+    let stylesheet = crate::parse_stylesheet(CSS).unwrap();
+    let value = |page: &PageInfo, property: &str| stylesheet.page_style(page).get(property).map(|declaration| declaration.value.to_string());
+
+    let first = PageInfo::new(0);
+    assert_eq!(value(&first, "margin-top").as_deref(), Some("4cm"));
+    assert_eq!(value(&first, "margin-right").as_deref(), Some("3cm"));
+    assert_eq!(value(&first, "margin-left"), None);
+    assert_eq!(value(&first, "size").as_deref(), Some("letter"));
+
+    let second = PageInfo::new(1);
+    assert_eq!(value(&second, "margin-left").as_deref(), Some("3cm"));
+    assert_eq!(value(&second, "size").as_deref(), Some("A4"));
+
+    let style = stylesheet.page_style(&first);
+    let top_center = &style.margin_boxes[&MarginBox::TopCenter];
+    assert_eq!(top_center[0].to_string(), "content: \"Invoice\";");
+    assert!(!stylesheet.page_style(&second).margin_boxes.contains_key(&MarginBox::TopCenter));
+
+    let invoice = PageInfo::new(3).with_name("invoice");
+    let style = stylesheet.page_style(&invoice);
+    assert_eq!(style.get("margin").unwrap().to_string(), "margin: 1cm !important;");
+    assert_eq!(style.margin_boxes[&MarginBox::BottomRight][0].value.to_string(), "none");
+}
@@ -0,0 +1,90 @@
+use crate::css_parser::error::PropertyError;
+use crate::css_parser::property::{Multiplier, PropertyRegistry, PropertySyntax, SyntaxComponent, SyntaxType};
+use pretty_assertions::assert_eq;
+
+const CSS: &str = r#"
+@property --gap { syntax: "<length> | auto"; inherits: false; initial-value: 8px; }
+@property --palette { syntax: "<color>#"; inherits: true; initial-value: red, #fff; }
+@property --anything { syntax: "*"; inherits: false; }
+@property --relative { syntax: "<length>"; inherits: false; initial-value: 2em; }
+@property --typo { syntax: "<lenght>"; inherits: false; initial-value: 1px; }
+@property --no-initial { syntax: "<angle>+"; inherits: true; }
+.card {
+    --gap: 2em;
+    --palette: blue, rgb(0 0 0);
+    --anything: 1 2 3;
+    &:hover { --gap: red; }
+}
+@media print { .card { --gap: calc(var(--base) * 2); --palette: 4px; } }
+"#;
+
+#[test]
+fn test_property_rules() {
+    let stylesheet = crate::parse_stylesheet(CSS).unwrap();
+    let property = |index: usize| stylesheet.rules[index].as_at_rule().unwrap().property().unwrap();
+
+    let gap = property(0).unwrap();
+    assert_eq!(gap.name, "--gap");
+    assert_eq!(
+        gap.syntax,
+        PropertySyntax::Alternatives(vec![
+            SyntaxComponent { kind: SyntaxType::Length, multiplier: None },
+            SyntaxComponent { kind: SyntaxType::Ident("auto".into()), multiplier: None },
+        ])
+    );
+    assert!(!gap.inherits);
+    assert_eq!(gap.initial_value.unwrap().to_string(), "8px");
+    assert_eq!(property(1).unwrap().syntax.to_string(), "<color>#");
+    assert_eq!(property(2).unwrap().syntax, PropertySyntax::Universal);
+
+    assert!(matches!(property(3), Err(PropertyError::ComputationallyDependent { .. })));
+    assert!(matches!(property(4), Err(PropertyError::InvalidSyntax { .. })));
+    assert!(matches!(property(5), Err(PropertyError::MissingDescriptor { descriptor: "initial-value", .. })));
+    assert!(stylesheet.rules[6].as_at_rule().is_none());
+
+    // CSS-wide keywords are valid values but not initial values.
+    let keywords = [
+        r#"@property --x { syntax: "<length>"; inherits: false; initial-value: inherit; }"#,
+        r#"@property --x { syntax: "*"; inherits: false; initial-value: UNSET; }"#,
+    ];
+    for css in keywords {
+        let stylesheet = crate::parse_stylesheet(css).unwrap();
+        let property = stylesheet.rules[0].as_at_rule().unwrap().property().unwrap();
+        assert!(matches!(property, Err(PropertyError::InvalidInitialValue { .. })), "{}", css);
+    }
+
+    assert_eq!(PropertySyntax::parse("<transform-list>+"), Err("<transform-list>+".to_string()));
+    assert_eq!(
+        PropertySyntax::parse("<integer>+").unwrap(),
+        PropertySyntax::Alternatives(vec![SyntaxComponent { kind: SyntaxType::Integer, multiplier: Some(Multiplier::Space) }])
+    );
+}
+
+#[test]
+fn test_property_registry() {
+    let stylesheet = crate::parse_stylesheet(CSS).unwrap();
+    let registry = PropertyRegistry::from_stylesheet(&stylesheet);
+
+    assert!(registry.get("--gap").is_some());
+    assert!(registry.get("--relative").is_none());
+    assert_eq!(registry.errors().len(), 3);
+
+    // Uses with var() are only known once substituted.
+    let errors: Vec<_> = registry
+        .check_stylesheet(&stylesheet)
+        .into_iter()
+        .map(|error| match error {
+            PropertyError::ValueMismatch { property, value, syntax, .. } => format!("{}: {} ({})", property, value, syntax),
+            error => panic!("unexpected error {:?}", error),
+        })
+        .collect();
+    assert_eq!(errors, ["--gap: red (<length> | auto)", "--palette: 4px (<color>#)"]);
+
+    let syntax = PropertySyntax::parse("<length-percentage> | <integer>#").unwrap();
+    let matches = |css: &str| syntax.matches(&crate::parse_value_for_property("--x", css).unwrap());
+    assert!(matches("calc(100% - 2rem)"));
+    assert!(matches("1, 2, 3"));
+    assert!(matches("inherit"));
+    assert!(!matches("1.5, 2"));
+    assert!(!matches("calc(1px + 2s)"));
+}
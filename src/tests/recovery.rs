@@ -0,0 +1,63 @@
+use super::common::read_test_file;
+use crate::css_parser::ast::{RuleExt, StylesheetExt};
+use crate::css_parser::error::{ParseErrorKind, Recovery};
+use crate::css_parser::parser::CssParser;
+use pretty_assertions::assert_eq;
+
+#[test]
+fn test_recovery() {
+    let css = read_test_file("recovery.css");
+
+    assert!(CssParser::new(&css).parse_stylesheet().is_err());
+
+    let (stylesheet, diagnostics) = CssParser::new(&css).parse_stylesheet_with_recovery();
+
+    assert_eq!(stylesheet.style_rules().count(), 2);
+    assert_eq!(stylesheet.at_rules().count(), 1);
+
+    let rule = stylesheet.get_rule_by_selector(".first").unwrap();
+    assert_eq!(rule.declaration_count(), 2);
+    assert!(rule.has_declaration("color"));
+    assert!(rule.has_declaration("height"));
+
+    let media_rule = stylesheet.at_rules().next().unwrap();
+    assert_eq!(media_rule.rules.len(), 1);
+    assert_eq!(media_rule.rules[0].as_style().unwrap().declaration_count(), 1);
+
+    let rule = stylesheet.get_rule_by_selector(".fifth").unwrap();
+    assert_eq!(rule.declaration_count(), 2);
+
+    let summary: Vec<(ParseErrorKind, Recovery, usize)> = diagnostics
+        .iter()
+        .map(|diagnostic| (diagnostic.error.kind, diagnostic.recovery, diagnostic.error.line))
+        .collect();
+
+    assert_eq!(summary, vec![
+        (ParseErrorKind::UnexpectedToken, Recovery::SkippedDeclaration, 3),
+        (ParseErrorKind::UnexpectedToken, Recovery::SkippedRule, 5),
+        (ParseErrorKind::InvalidAttributeSelector, Recovery::SkippedRule, 7),
+        (ParseErrorKind::InvalidVariableName, Recovery::SkippedDeclaration, 14),
+        (ParseErrorKind::ExpectedIdentifier, Recovery::SkippedRule, 17),
+        (ParseErrorKind::UnexpectedToken, Recovery::SkippedRule, 20),
+        (ParseErrorKind::InvalidImportant, Recovery::SkippedDeclaration, 24),
+        (ParseErrorKind::UnexpectedEof, Recovery::ClosedBlock, 26),
+    ]);
+}
+
+#[test]
+fn test_recovery_stray_close_brace() {
+    assert!(CssParser::new("a{color:red} } b{color:blue}").parse_stylesheet().is_err());
+
+    let (stylesheet, diagnostics) = CssParser::new("a{color:red} } b{color:blue}").parse_stylesheet_with_recovery();
+
+    assert_eq!(stylesheet.style_rules().count(), 2);
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(diagnostics[0].recovery, Recovery::SkippedRule);
+    assert_eq!(diagnostics[0].error.column, 14);
+
+    assert!(CssParser::new("@media screen { a { color: red } } }").parse_stylesheet().is_err());
+
+    let (stylesheet, diagnostics) = CssParser::new("@media screen { a { color: red } } }").parse_stylesheet_with_recovery();
+    assert_eq!(stylesheet.at_rules().count(), 1);
+    assert_eq!(diagnostics.len(), 1);
+}
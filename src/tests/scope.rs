@@ -0,0 +1,61 @@
+use crate::css_parser::ast::{AtRuleType, SelectorCombinator, SelectorPartKind};
+use pretty_assertions::assert_eq;
+
+#[test]
+fn test_scope_rule() {
+    let css = "@scope (.card, .panel) to (.content) { color: red; :scope { border: 0; } > img { width: 100%; } & p { margin: 0; } }";
+    let stylesheet = crate::parse_stylesheet(css).unwrap();
+    assert_eq!(stylesheet.rules[0].rule_type(), Some(&AtRuleType::Scope));
+    let scope_rule = stylesheet.rules[0].as_at_rule().unwrap();
+
+    let scope = scope_rule.scope().unwrap();
+    assert_eq!(scope.start.len(), 2);
+    assert_eq!(scope.end[0].to_string(), ".content");
+    assert_eq!(scope_rule.query, "(.card, .panel) to (.content)");
+
+    // Declarations directly inside @scope apply to the scoping root.
+    let block = scope_rule.rules[0].as_style().unwrap();
+    assert!(block.is_declaration_block());
+    assert_eq!(block.declarations[0].property, "color");
+
+    let root = scope_rule.rules[1].as_style().unwrap();
+    assert_eq!(root.selectors[0].groups[0].parts[0].kind, SelectorPartKind::PseudoClass("scope".into()));
+    let image = scope_rule.rules[2].as_style().unwrap();
+    assert_eq!(image.selectors[0].leading_combinator, Some(SelectorCombinator::Child));
+    assert_eq!(scope_rule.rules[3].as_style().unwrap().selectors[0].to_string(), "& p");
+
+    // Selectors without `:scope` or `&` are relative to the scoping root.
+    let implied: Vec<_> = scope_rule.style_rules().skip(1).map(|rule| rule.selectors[0].with_implied_scope().to_string()).collect();
+    assert_eq!(implied, [":scope", ":scope > img", "& p"]);
+    let selectors = crate::parse_selector_list("img, :is(:scope .a) b").unwrap();
+    assert_eq!(selectors[0].with_implied_scope().to_string(), ":scope img");
+    assert!(selectors[1].refers_to_scope());
+
+    let stylesheet = crate::parse_stylesheet("@scope to (figure) { img { border: 0; } }\n@scope { p { color: red; } }").unwrap();
+    let limit_only = stylesheet.rules[0].as_at_rule().unwrap().scope().unwrap();
+    assert!(limit_only.start.is_empty());
+    assert_eq!(stylesheet.to_string(), "@scope to (figure) {\n    img {\n        border: 0;\n    }\n}\n@scope {\n    p {\n        color: red;\n    }\n}\n");
+}
+
+#[test]
+fn test_starting_style() {
+    let css = "@starting-style { .dialog { opacity: 0; } }\n.toast { opacity: 1; @starting-style { opacity: 0; } }";
+    let stylesheet = crate::parse_stylesheet(css).unwrap();
+
+    assert_eq!(stylesheet.rules[0].rule_type(), Some(&AtRuleType::StartingStyle));
+    let top_level = stylesheet.rules[0].as_at_rule().unwrap();
+    assert_eq!(top_level.query, "");
+    assert_eq!(top_level.style_rules().next().unwrap().selectors[0].to_string(), ".dialog");
+
+    let toast = stylesheet.rules[1].as_style().unwrap();
+    assert_eq!(toast.rules[0].rule_type(), Some(&AtRuleType::StartingStyle));
+    let nested = toast.rules[0].as_at_rule().unwrap();
+    let block = nested.rules[0].as_style().unwrap();
+    assert!(block.is_declaration_block());
+    assert_eq!(block.declarations[0].to_string(), "opacity: 0;");
+
+    assert_eq!(
+        stylesheet.to_string(),
+        "@starting-style {\n    .dialog {\n        opacity: 0;\n    }\n}\n.toast {\n    opacity: 1;\n    @starting-style {\n        opacity: 0;\n    }\n}\n"
+    );
+}
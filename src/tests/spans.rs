@@ -0,0 +1,66 @@
+use crate::css_parser::ast::{RuleExt, ValueKind};
+use crate::css_parser::parser::CssParser;
+use pretty_assertions::assert_eq;
+
+#[test]
+fn test_rule_spans() {
+    let css = "a { color: red; }\n\n.nav > li:hover, #id  {\n    margin: 0 auto !important;\n    width: 10px\n}\n";
+    let stylesheet = CssParser::new(css).parse_stylesheet().unwrap();
+
+    let rule = stylesheet.rules[1].as_style().unwrap();
+    assert_eq!(rule.span.text(css), &css[19..css.len() - 1]);
+    assert_eq!((rule.span.line, rule.span.column), (3, 1));
+
+    let selector = &rule.selectors[0];
+    assert_eq!(selector.span.text(css), ".nav > li:hover");
+    assert_eq!(selector.groups[1].span.text(css), "li:hover");
+    let parts: Vec<&str> = selector.groups[1].parts.iter().map(|part| part.span.text(css)).collect();
+    assert_eq!(parts, vec!["li", ":hover"]);
+    assert_eq!(rule.selectors[1].span.text(css), "#id");
+
+    let declaration = rule.get_declaration("margin").unwrap();
+    assert_eq!(declaration.span.text(css), "margin: 0 auto !important");
+    assert_eq!(declaration.value.span.text(css), "0 auto");
+    assert_eq!((declaration.span.line, declaration.span.column), (4, 5));
+
+    let declaration = rule.get_declaration("width").unwrap();
+    assert_eq!(declaration.span.text(css), "width: 10px");
+}
+
+#[test]
+fn test_at_rule_spans() {
+    let css = "@import url(\"a.css\");\n@media (min-width: 1px) {\n  p { color: #fff }\n}";
+    let stylesheet = CssParser::new(css).parse_stylesheet().unwrap();
+
+    let import_rule = stylesheet.rules[0].as_at_rule().unwrap();
+    assert_eq!(import_rule.span.text(css), "@import url(\"a.css\");");
+
+    let media_rule = stylesheet.rules[1].as_at_rule().unwrap();
+    assert_eq!(media_rule.span.text(css), &css[22..]);
+
+    let rule = media_rule.rules[0].as_style().unwrap();
+    assert_eq!(rule.span.text(css), "p { color: #fff }");
+    assert_eq!(rule.declarations[0].value.span.text(css), "#fff");
+}
+
+#[test]
+fn test_value_spans() {
+    let css = "a { font-family: serif, Fira Code; color: rgb(1 2 3 / 50%); background: url(\"a.png\") }";
+    let stylesheet = CssParser::new(css).parse_stylesheet().unwrap();
+    let rule = stylesheet.rules[0].as_style().unwrap();
+
+    let value = &rule.get_declaration("font-family").unwrap().value;
+    let ValueKind::List(items) = &value.kind else { panic!("expected a list, got {:?}", value) };
+    let items: Vec<&str> = items.iter().map(|item| item.span.text(css)).collect();
+    assert_eq!(items, vec!["serif", ",", "Fira Code"]);
+
+    let value = &rule.get_declaration("color").unwrap().value;
+    assert_eq!(value.span.text(css), "rgb(1 2 3 / 50%)");
+    let ValueKind::Function(_, args) = &value.kind else { panic!("expected a function, got {:?}", value) };
+    let args: Vec<&str> = args.iter().map(|arg| arg.span.text(css)).collect();
+    assert_eq!(args, vec!["1", "2", "3", "/", "50%"]);
+
+    let value = &rule.get_declaration("background").unwrap().value;
+    let ValueKind::Function(_, args) = &value.kind else { panic!("expected a function, got {:?}", value) };
+    assert_eq!(args[0].span.text(css), "\"a.png\"");
+}
@@ -0,0 +1,45 @@
+use crate::css_parser::error::ParseErrorKind;
+use crate::css_parser::supports::{SupportTable, SupportsCondition};
+use pretty_assertions::assert_eq;
+
+#[test]
+fn test_supports_condition_ast() {
+    let condition = crate::parse_supports_condition("(display: grid) and (not selector(:has(> img)))").unwrap();
+    let SupportsCondition::And(conditions) = &condition else { panic!("expected and, got {:?}", condition) };
+    assert_eq!(conditions[0], SupportsCondition::Declaration { property: "display".into(), value: "grid".into() });
+    assert!(matches!(&conditions[1], SupportsCondition::Not(inner) if matches!(**inner, SupportsCondition::Selector(_))));
+    assert_eq!(condition.to_string(), "(display: grid) and (not selector(:has(> img)))");
+
+    let condition = crate::parse_supports_condition("font-tech(color-COLRv1) or font-format(woff2) or (--x: 1 2) or foo(bar)").unwrap();
+    assert_eq!(condition.to_string(), "font-tech(color-COLRv1) or font-format(woff2) or (--x: 1 2) or foo(bar)");
+    assert!(matches!(&condition, SupportsCondition::Or(conditions) if matches!(conditions[3], SupportsCondition::GeneralEnclosed(_))));
+
+    let stylesheet = crate::parse_stylesheet("@supports not (display:grid) { a { float: left } }").unwrap();
+    let supports = stylesheet.rules[0].as_at_rule().unwrap();
+    assert_eq!(supports.query, "not (display: grid)");
+    assert!(matches!(supports.supports(), Some(SupportsCondition::Not(_))));
+
+    let error = crate::parse_supports_condition("(a: b) and (c: d) or (e: f)").unwrap_err();
+    assert_eq!(error.kind, ParseErrorKind::InvalidSupportsCondition);
+}
+
+#[test]
+fn test_supports_evaluation() {
+    let engine = SupportTable::new()
+        .with_property("display", &["grid", "flex", "block"])
+        .with_property("gap", &[])
+        .with_selector(":has")
+        .with_font_format("woff2");
+
+    let supported = |css: &str| crate::parse_supports_condition(css).unwrap().evaluate(&engine);
+
+    assert!(supported("(display: grid)"));
+    assert!(!supported("(display: contents)"));
+    assert!(supported("(gap: 1rem 2rem)"));
+    assert!(supported("(--anything: at all)"));
+    assert!(supported("selector(.card:has(img)) and font-format(woff2)"));
+    assert!(!supported("selector(::backdrop)"));
+    assert!(supported("not font-tech(color-COLRv1)"));
+    assert!(supported("(display: subgrid) or (display: flex)"));
+    assert!(!supported("unknown(x)"));
+}
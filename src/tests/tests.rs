@@ -23,12 +23,12 @@ mod tests {
 
         let decl = get_first_declaration(&stylesheet).unwrap();
         assert_eq!(decl.property, "font-family");
-        assert!(matches!(decl.value, Value::List(ref items, ListSeparator::Comma) if items.len() == 3));
+        assert!(matches!(decl.value, ValueKind::List(ref items, ListSeparator::Comma) if items.len() == 3));
 
-        if let Value::List(items, _) = &decl.value {
-            assert!(matches!(items[0], Value::Literal(ref name) if name == "Arial"));
-            assert!(matches!(items[1], Value::QuotedString(ref name) if name == "Helvetica Neue"));
-            assert!(matches!(items[2], Value::Literal(ref name) if name == "sans-serif"));
+        if let ValueKind::List(items, _) = &decl.value {
+            assert!(matches!(items[0], ValueKind::Literal(ref name) if name == "Arial"));
+            assert!(matches!(items[1], ValueKind::QuotedString(ref name) if name == "Helvetica Neue"));
+            assert!(matches!(items[2], ValueKind::Literal(ref name) if name == "sans-serif"));
         }
     }
 
@@ -1,7 +1,6 @@
 use super::common::{compare_values, parse_test_file};
-use crate::css_parser::ast::ListSeparator::Comma;
-use crate::css_parser::ast::Value::List;
-use crate::css_parser::ast::{RuleExt, StylesheetExt, Value};
+use crate::css_parser::ast::ValueKind::List;
+use crate::css_parser::ast::{RuleExt, StylesheetExt, ValueKind};
 
 #[test]
 fn test_unicode_range() {
@@ -10,82 +9,82 @@ fn test_unicode_range() {
     let rule = stylesheet.get_rule_by_selector(".unicode-range").unwrap();
     let declarations = rule.get_declarations("unicode-range");
 
-    let decl = declarations.get(0).unwrap();
+    let decl = declarations.first().unwrap();
     println!("{:?}", decl);
     assert!(compare_values(
         &decl.value,
         &List(vec![
-            Value::Literal("U+0000-00FF".to_string()),
-            Value::Literal(",".to_string()),
-            Value::Literal("U+0131".to_string()),
-            Value::Literal(",".to_string()),
-            Value::Literal("U+0152-0153".to_string()),
-            Value::Literal(",".to_string()),
-            Value::Literal("U+02BB-02BC".to_string()),
-            Value::Literal(",".to_string()),
-            Value::Literal("U+02C6".to_string()),
-            Value::Literal(",".to_string()),
-            Value::Literal("U+02DA".to_string()),
-            Value::Literal(",".to_string()),
-            Value::Literal("U+02DC".to_string()),
-            Value::Literal(",".to_string()),
-            Value::Literal("U+0304".to_string()),
-            Value::Literal(",".to_string()),
-            Value::Literal("U+0308".to_string()),
-            Value::Literal(",".to_string()),
-            Value::Literal("U+0329".to_string()),
-            Value::Literal(",".to_string()),
-            Value::Literal("U+2000-206F".to_string()),
-            Value::Literal(",".to_string()),
-            Value::Literal("U+20AC".to_string()),
-            Value::Literal(",".to_string()),
-            Value::Literal("U+2122".to_string()),
-            Value::Literal(",".to_string()),
-            Value::Literal("U+2191".to_string()),
-            Value::Literal(",".to_string()),
-            Value::Literal("U+2193".to_string()),
-            Value::Literal(",".to_string()),
-            Value::Literal("U+2212".to_string()),
-            Value::Literal(",".to_string()),
-            Value::Literal("U+2215".to_string()),
-            Value::Literal(",".to_string()),
-            Value::Literal("U+FEFF".to_string()),
-            Value::Literal(",".to_string()),
-            Value::Literal("U+FFFD".to_string()),
+            ValueKind::Literal("U+0000-00FF".into()).into(),
+            ValueKind::Literal(",".into()).into(),
+            ValueKind::Literal("U+0131".into()).into(),
+            ValueKind::Literal(",".into()).into(),
+            ValueKind::Literal("U+0152-0153".into()).into(),
+            ValueKind::Literal(",".into()).into(),
+            ValueKind::Literal("U+02BB-02BC".into()).into(),
+            ValueKind::Literal(",".into()).into(),
+            ValueKind::Literal("U+02C6".into()).into(),
+            ValueKind::Literal(",".into()).into(),
+            ValueKind::Literal("U+02DA".into()).into(),
+            ValueKind::Literal(",".into()).into(),
+            ValueKind::Literal("U+02DC".into()).into(),
+            ValueKind::Literal(",".into()).into(),
+            ValueKind::Literal("U+0304".into()).into(),
+            ValueKind::Literal(",".into()).into(),
+            ValueKind::Literal("U+0308".into()).into(),
+            ValueKind::Literal(",".into()).into(),
+            ValueKind::Literal("U+0329".into()).into(),
+            ValueKind::Literal(",".into()).into(),
+            ValueKind::Literal("U+2000-206F".into()).into(),
+            ValueKind::Literal(",".into()).into(),
+            ValueKind::Literal("U+20AC".into()).into(),
+            ValueKind::Literal(",".into()).into(),
+            ValueKind::Literal("U+2122".into()).into(),
+            ValueKind::Literal(",".into()).into(),
+            ValueKind::Literal("U+2191".into()).into(),
+            ValueKind::Literal(",".into()).into(),
+            ValueKind::Literal("U+2193".into()).into(),
+            ValueKind::Literal(",".into()).into(),
+            ValueKind::Literal("U+2212".into()).into(),
+            ValueKind::Literal(",".into()).into(),
+            ValueKind::Literal("U+2215".into()).into(),
+            ValueKind::Literal(",".into()).into(),
+            ValueKind::Literal("U+FEFF".into()).into(),
+            ValueKind::Literal(",".into()).into(),
+            ValueKind::Literal("U+FFFD".into()).into(),
         ],)
     ));
 
     let decl = declarations.get(1).unwrap();
     assert!(compare_values(
         &decl.value,
-        &Value::Literal("U+26".to_string())
+        &ValueKind::Literal("U+26".into())
     ));
 
     let decl = declarations.get(2).unwrap();
     assert!(compare_values(
         &decl.value,
-        &Value::Literal("U+0-7F".to_string())
+        &ValueKind::Literal("U+0-7F".into())
     ));
 
     let decl = declarations.get(3).unwrap();
     assert!(compare_values(
         &decl.value,
-        &Value::Literal("U+0025-00FF".to_string())
+        &ValueKind::Literal("U+0025-00FF".into())
     ));
 
     let decl = declarations.get(4).unwrap();
     assert!(compare_values(
         &decl.value,
-        &Value::Literal("U+4??".to_string())
+        &ValueKind::Literal("U+4??".into())
     ));
 
     let decl = declarations.get(5).unwrap();
     assert!(compare_values(
         &decl.value,
         &List(vec![
-            Value::Literal("U+0025-00FF".to_string()),
-            Value::Literal(",".to_string()),
-            Value::Literal("U+4??".to_string()),
+            ValueKind::Literal("U+0025-00FF".into()).into(),
+            ValueKind::Literal(",".into()).into(),
+            ValueKind::Literal("U+4??".into()).into(),
         ],)
     ));
 }
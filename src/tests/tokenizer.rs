@@ -0,0 +1,82 @@
+use crate::css_parser::tokenizer::{CssTokenType, Tokenizer};
+use pretty_assertions::assert_eq;
+
+fn token_types(css: &str) -> Vec<CssTokenType> {
+    Tokenizer::new(css).map(|token| token.token_type).collect()
+}
+
+#[test]
+fn test_spec_tokens() {
+    assert_eq!(token_types("@media #main .5em 50% -10px +3 1e3"), vec![
+        CssTokenType::AtKeyword("media".to_string()),
+        CssTokenType::Whitespace,
+        CssTokenType::Hash { value: "main".to_string(), is_id: true },
+        CssTokenType::Whitespace,
+        CssTokenType::Dimension { value: 0.5, is_integer: false, unit: "em".to_string() },
+        CssTokenType::Whitespace,
+        CssTokenType::Percentage(50.0),
+        CssTokenType::Whitespace,
+        CssTokenType::Dimension { value: -10.0, is_integer: true, unit: "px".to_string() },
+        CssTokenType::Whitespace,
+        CssTokenType::Number { value: 3.0, is_integer: true },
+        CssTokenType::Whitespace,
+        CssTokenType::Number { value: 1000.0, is_integer: false },
+    ]);
+
+    assert_eq!(token_types("#1a rgb( url(a.png) url( \"b.png\" )"), vec![
+        CssTokenType::Hash { value: "1a".to_string(), is_id: false },
+        CssTokenType::Whitespace,
+        CssTokenType::Function("rgb".to_string()),
+        CssTokenType::Whitespace,
+        CssTokenType::Url("a.png".to_string()),
+        CssTokenType::Whitespace,
+        CssTokenType::Function("url".to_string()),
+        CssTokenType::Whitespace,
+        CssTokenType::String("b.png".to_string()),
+        CssTokenType::Whitespace,
+        CssTokenType::CloseParen,
+    ]);
+
+    assert_eq!(token_types("<!-- a/**/b --> ^ \\41 x"), vec![
+        CssTokenType::CDO,
+        CssTokenType::Whitespace,
+        CssTokenType::Ident("a".to_string()),
+        CssTokenType::Ident("b".to_string()),
+        CssTokenType::Whitespace,
+        CssTokenType::CDC,
+        CssTokenType::Whitespace,
+        CssTokenType::Delim('^'),
+        CssTokenType::Whitespace,
+        CssTokenType::Ident("Ax".to_string()),
+    ]);
+
+    let kept: Vec<_> = Tokenizer::keeping_comments("a/**/b /* c").map(|token| token.token_type).collect();
+    assert_eq!(kept, vec![
+        CssTokenType::Ident("a".to_string()),
+        CssTokenType::Comment(String::new()),
+        CssTokenType::Ident("b".to_string()),
+        CssTokenType::Whitespace,
+        CssTokenType::Comment(" c".to_string()),
+    ]);
+}
+
+#[test]
+fn test_error_tokens_and_preprocessing() {
+    assert_eq!(token_types("'abc\nx url(a b) url(a\"b)"), vec![
+        CssTokenType::BadString,
+        CssTokenType::Whitespace,
+        CssTokenType::Ident("x".to_string()),
+        CssTokenType::Whitespace,
+        CssTokenType::BadUrl,
+        CssTokenType::Whitespace,
+        CssTokenType::BadUrl,
+    ]);
+
+    assert_eq!(token_types("a\0b"), vec![CssTokenType::Ident("a\u{FFFD}b".to_string())]);
+
+    let tokens = Tokenizer::new("a\r\n\u{C}b").tokenize();
+    assert_eq!(tokens.len(), 4);
+    assert_eq!((tokens[1].position, tokens[1].length), (1, 3));
+    assert_eq!((tokens[2].token_type.clone(), tokens[2].line, tokens[2].column, tokens[2].position), (CssTokenType::Ident("b".to_string()), 3, 1, 4));
+    assert_eq!(tokens[3].token_type, CssTokenType::EOF);
+}